@@ -7,19 +7,138 @@ fn test_cli_parsing_start_command() {
     let cli = Cli::try_parse_from(args).unwrap();
 
     match cli.command {
-        Commands::Start { label } => {
-            assert_eq!(label, "My Task");
+        Some(Commands::Start { label, .. }) => {
+            assert_eq!(label, ["My Task"]);
         },
         _ => panic!("Expected Start command"),
     }
 }
 
+#[test]
+fn test_cli_parsing_start_command_with_tags() {
+    let args = vec![
+        "task-timer",
+        "start",
+        "My Task",
+        "-t",
+        "acme",
+        "--tag",
+        "urgent",
+    ];
+    let cli = Cli::try_parse_from(args).unwrap();
+
+    match cli.command {
+        Some(Commands::Start { tags, .. }) => {
+            assert_eq!(tags, vec!["acme", "urgent"]);
+        },
+        _ => panic!("Expected Start command"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_set_priority_command() {
+    let args = vec!["task-timer", "set-priority", "2", "high"];
+    let cli = Cli::try_parse_from(args).unwrap();
+
+    match cli.command {
+        Some(Commands::SetPriority { index, level }) => {
+            assert_eq!(index, 2);
+            assert_eq!(level, Priority::High);
+        },
+        _ => panic!("Expected SetPriority command"),
+    }
+
+    let cli = Cli::try_parse_from(vec!["task-timer", "estimate", "2", "1h30m"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Estimate { index: 2, duration }) if duration == Duration::from_secs(5400)
+    ));
+    assert!(Cli::try_parse_from(vec!["task-timer", "estimate", "2", "soon"]).is_err());
+
+    let args = vec!["task-timer", "set-priority", "2", "urgent"];
+    assert!(Cli::try_parse_from(args).is_err());
+}
+
+#[test]
+fn test_cli_parsing_rename_active() {
+    let args = vec!["task-timer", "rename", "--active", "New Label"];
+    let cli = Cli::try_parse_from(args).unwrap();
+
+    match cli.command {
+        Some(Commands::Rename { index, new_label, active, id }) => {
+            assert_eq!(index, None);
+            assert_eq!(id, None);
+            assert_eq!(new_label, "New Label");
+            assert!(active);
+        },
+        _ => panic!("Expected Rename command"),
+    }
+
+    // The index-based form is unchanged
+    let args = vec!["task-timer", "rename", "2", "New Label"];
+    let cli = Cli::try_parse_from(args).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Rename { index: Some(2), active: false, .. })
+    ));
+
+    // Without --active, the index is still required
+    assert!(Cli::try_parse_from(vec!["task-timer", "rename", "New Label"]).is_err());
+}
+
+#[test]
+fn test_cli_parsing_stable_id() {
+    let cli = Cli::try_parse_from(vec!["task-timer", "delete", "--id", "7"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Delete { index: None, id: Some(7), .. })
+    ));
+
+    let cli = Cli::try_parse_from(vec!["task-timer", "rename", "--id", "7", "New Label"]).unwrap();
+    match cli.command {
+        Some(Commands::Rename { index, new_label, id, .. }) => {
+            assert_eq!(index, None);
+            assert_eq!(new_label, "New Label");
+            assert_eq!(id, Some(7));
+        },
+        _ => panic!("Expected Rename command"),
+    }
+
+    // An ID can't be combined with another way of picking the task
+    for args in [
+        vec!["task-timer", "delete", "2", "--id", "7"],
+        vec!["task-timer", "delete", "--completed", "--id", "7"],
+        vec!["task-timer", "complete", "2", "--id", "7"],
+        vec!["task-timer", "rename", "2", "New Label", "--id", "7"],
+        vec!["task-timer", "rename", "--active", "New Label", "--id", "7"],
+    ] {
+        assert!(Cli::try_parse_from(args).is_err());
+    }
+}
+
+#[test]
+fn test_cli_parsing_start_at_relative_offset() {
+    let args = vec!["task-timer", "start", "My Task", "--at", "-20m"];
+    let cli = Cli::try_parse_from(args).unwrap();
+
+    match cli.command {
+        Some(Commands::Start { at, .. }) => assert_eq!(at.as_deref(), Some("-20m")),
+        _ => panic!("Expected Start command"),
+    }
+}
+
 #[test]
 fn test_cli_parsing_pause_command() {
     let args = vec!["task-timer", "pause"];
     let cli = Cli::try_parse_from(args).unwrap();
 
-    matches!(cli.command, Commands::Pause);
+    matches!(cli.command, Some(Commands::Pause { .. }));
+
+    let cli = Cli::try_parse_from(vec!["task-timer", "pause", "--all"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Pause { all: true, note: None })
+    ));
 }
 
 #[test]
@@ -27,7 +146,7 @@ fn test_cli_parsing_resume_command() {
     let args = vec!["task-timer", "resume"];
     let cli = Cli::try_parse_from(args).unwrap();
 
-    matches!(cli.command, Commands::Resume);
+    matches!(cli.command, Some(Commands::Resume { .. }));
 }
 
 #[test]
@@ -35,7 +154,7 @@ fn test_cli_parsing_status_command() {
     let args = vec!["task-timer", "status"];
     let cli = Cli::try_parse_from(args).unwrap();
 
-    matches!(cli.command, Commands::Status);
+    matches!(cli.command, Some(Commands::Status { .. }));
 }
 
 #[test]
@@ -43,7 +162,7 @@ fn test_cli_parsing_list_command() {
     let args = vec!["task-timer", "list"];
     let cli = Cli::try_parse_from(args).unwrap();
 
-    matches!(cli.command, Commands::List);
+    matches!(cli.command, Some(Commands::List { .. }));
 }
 
 #[test]
@@ -59,6 +178,22 @@ fn test_cli_help_generation() {
     assert!(help_str.contains("resume"));
 }
 
+#[test]
+fn test_completion_script_lists_subcommands() {
+    let script = completion_script(Shell::Bash);
+    assert!(script.contains("_tt()"));
+    assert!(script.contains("start"));
+    assert!(script.contains("complete"));
+
+    let args = vec!["task-timer", "completions", "zsh"];
+    let cli = Cli::try_parse_from(args).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::Completions { shell: Shell::Zsh })
+    ));
+    assert!(Cli::try_parse_from(vec!["task-timer", "completions", "tcsh"]).is_err());
+}
+
 #[test]
 fn test_start_command_requires_label() {
     let args = vec!["task-timer", "start"];
@@ -70,11 +205,37 @@ fn test_start_command_requires_label() {
 #[test]
 fn test_start_command_with_multi_word_label() {
     let args = vec!["task-timer", "start", "My", "Complex", "Task", "Name"];
-    let result = Cli::try_parse_from(args);
+    let cli = Cli::try_parse_from(args).unwrap();
 
-    // This should fail because clap expects a single argument for label
-    // User would need to quote: "My Complex Task Name"
-    assert!(result.is_err());
+    // Unquoted words are collected and later joined like the quoted form
+    match cli.command {
+        Some(Commands::Start { label, .. }) => {
+            assert_eq!(label.join(" "), "My Complex Task Name");
+        },
+        _ => panic!("Expected Start command"),
+    }
+}
+
+#[test]
+fn test_start_command_with_multi_word_label_and_options() {
+    let args = vec![
+        "task-timer",
+        "start",
+        "Fix",
+        "login",
+        "--project",
+        "Web",
+        "bug",
+    ];
+    let cli = Cli::try_parse_from(args).unwrap();
+
+    match cli.command {
+        Some(Commands::Start { label, project, .. }) => {
+            assert_eq!(label.join(" "), "Fix login bug");
+            assert_eq!(project.as_deref(), Some("Web"));
+        },
+        _ => panic!("Expected Start command"),
+    }
 }
 
 #[test]
@@ -83,24 +244,89 @@ fn test_start_command_with_quoted_label() {
     let cli = Cli::try_parse_from(args).unwrap();
 
     match cli.command {
-        Commands::Start { label } => {
-            assert_eq!(label, "My Complex Task Name");
+        Some(Commands::Start { label, .. }) => {
+            assert_eq!(label, ["My Complex Task Name"]);
         },
         _ => panic!("Expected Start command"),
     }
 }
 
+#[test]
+fn test_prints_output_only_for_commands_that_show_something() {
+    let parse = |args: &[&str]| {
+        Cli::try_parse_from([&["tt"], args].concat())
+            .unwrap()
+            .command
+            .unwrap()
+    };
+
+    assert!(parse(&["list"]).prints_output());
+    assert!(parse(&["status"]).prints_output());
+    assert!(parse(&["delete", "1", "--dry-run"]).prints_output());
+    assert!(parse(&["archive", "--list"]).prints_output());
+    assert!(!parse(&["start", "Task"]).prints_output());
+    assert!(!parse(&["delete", "1"]).prints_output());
+    assert!(!parse(&["archive"]).prints_output());
+}
+
 #[test]
 fn test_command_names() {
     assert_eq!(
-        Commands::Start { label: "test".to_string() }.name(),
+        Commands::Start {
+            label: vec!["test".to_string()],
+            project: None,
+            tags: Vec::new(),
+            priority: None,
+            at: None,
+            force: false,
+            goal: None,
+            estimate: None,
+        }
+        .name(),
         "start"
     );
-    assert_eq!(Commands::Pause.name(), "pause");
-    assert_eq!(Commands::Resume.name(), "resume");
-    assert_eq!(Commands::Status.name(), "status");
-    assert_eq!(Commands::List.name(), "list");
-    assert_eq!(Commands::Complete.name(), "complete");
+    assert_eq!(Commands::Pause { all: false, note: None }.name(), "pause");
+    assert_eq!(Commands::Resume { index: None }.name(), "resume");
+    assert_eq!(
+        Commands::Status {
+            label_max: None,
+            porcelain: false,
+            utc: false
+        }
+        .name(),
+        "status"
+    );
+    assert_eq!(
+        Commands::List {
+            relative: false,
+            sort: None,
+            reverse: false,
+            group_by: None,
+            round_sessions: None,
+            round: None,
+            no_float: false,
+            utc: false,
+            plain: false,
+            since: None,
+            until: None,
+            format: ListFormat::Detailed,
+            limit: None,
+            ids: false,
+        }
+        .name(),
+        "list"
+    );
+    assert_eq!(
+        Commands::Complete {
+            index: None,
+            id: None,
+            cap_session: None,
+            note: None,
+            no_journal: false
+        }
+        .name(),
+        "complete"
+    );
 }
 
 #[test]
@@ -109,8 +335,8 @@ fn test_cli_parsing_short_start_command() {
     let cli = Cli::try_parse_from(args).unwrap();
 
     match cli.command {
-        Commands::Start { label } => {
-            assert_eq!(label, "My Task");
+        Some(Commands::Start { label, .. }) => {
+            assert_eq!(label, ["My Task"]);
         },
         _ => panic!("Expected Start command"),
     }
@@ -121,7 +347,7 @@ fn test_cli_parsing_short_pause_command() {
     let args = vec!["task-timer", "p"];
     let cli = Cli::try_parse_from(args).unwrap();
 
-    matches!(cli.command, Commands::Pause);
+    matches!(cli.command, Some(Commands::Pause { .. }));
 }
 
 #[test]
@@ -129,7 +355,7 @@ fn test_cli_parsing_short_resume_command() {
     let args = vec!["task-timer", "r"];
     let cli = Cli::try_parse_from(args).unwrap();
 
-    matches!(cli.command, Commands::Resume);
+    matches!(cli.command, Some(Commands::Resume { .. }));
 }
 
 #[test]
@@ -137,7 +363,7 @@ fn test_cli_parsing_short_list_command() {
     let args = vec!["task-timer", "l"];
     let cli = Cli::try_parse_from(args).unwrap();
 
-    matches!(cli.command, Commands::List);
+    matches!(cli.command, Some(Commands::List { .. }));
 }
 
 #[test]
@@ -145,5 +371,67 @@ fn test_cli_parsing_short_complete_command() {
     let args = vec!["task-timer", "c"];
     let cli = Cli::try_parse_from(args).unwrap();
 
-    matches!(cli.command, Commands::Complete);
+    matches!(cli.command, Some(Commands::Complete { .. }));
+}
+
+#[test]
+fn test_cli_parsing_without_subcommand() {
+    let args = vec!["task-timer"];
+    let cli = Cli::try_parse_from(args).unwrap();
+
+    assert!(cli.command.is_none());
+}
+
+#[test]
+fn test_cli_parsing_list_group_and_sort() {
+    let args = vec![
+        "task-timer",
+        "list",
+        "--group-by",
+        "project",
+        "--sort",
+        "created",
+    ];
+    let cli = Cli::try_parse_from(args).unwrap();
+
+    match cli.command {
+        Some(Commands::List { sort, group_by, .. }) => {
+            assert_eq!(sort, Some(SortKey::Created));
+            assert_eq!(group_by, Some(GroupBy::Project));
+        },
+        _ => panic!("Expected List command"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_split_command() {
+    let args = vec!["task-timer", "split", "3", "0.25", "Code review"];
+    let cli = Cli::try_parse_from(args).unwrap();
+
+    match cli.command {
+        Some(Commands::Split { index, ratio, new_label }) => {
+            assert_eq!(index, 3);
+            assert_eq!(ratio, 0.25);
+            assert_eq!(new_label, "Code review");
+        },
+        _ => panic!("Expected Split command"),
+    }
+}
+
+#[test]
+fn test_cli_parsing_list_format() {
+    let cli = Cli::try_parse_from(vec!["task-timer", "list"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::List { format: ListFormat::Detailed, .. })
+    ));
+
+    let cli = Cli::try_parse_from(vec!["task-timer", "list", "--format", "compact"]).unwrap();
+    assert!(matches!(
+        cli.command,
+        Some(Commands::List { format: ListFormat::Compact, .. })
+    ));
+
+    let args = vec!["task-timer", "list", "--format", "compact", "--plain"];
+    assert!(Cli::try_parse_from(args).is_err());
 }
@@ -1,13 +1,62 @@
-use clap::{Parser, Subcommand};
+use crate::display::{ColorChoice, GroupBy, ListFormat, SortKey};
+use crate::duration::parse_duration;
+use crate::export::{ExportFormat, WorklogDate};
+use crate::report::ReportPeriod;
+use crate::task::Priority;
+use chrono::NaiveDate;
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::path::PathBuf;
+use std::time::Duration;
 
 /// CLI Task Timer - A command-line timer for tracking time spent on tasks
 #[derive(Parser)]
 #[command(name = "task-timer")]
 #[command(about = "A CLI tool for tracking time spent on tasks")]
 #[command(version)]
+#[command(after_help = "Exit codes:
+  0   Success
+  1   Any other error
+  2   Invalid command line (unknown command or bad arguments)
+  10  No active task
+  11  Invalid request, such as a bad task index or a task in the wrong state
+  12  The task file could not be read, written or decrypted")]
 pub(crate) struct Cli {
+    /// Reject labels containing control characters instead of replacing them with spaces
+    #[arg(long, global = true)]
+    pub(crate) strict_labels: bool,
+
+    /// Print `status`, `list` and `report` output as JSON
+    #[arg(long, global = true)]
+    pub(crate) json: bool,
+
+    /// Print JSON on a single line, e.g. for piping to other tools; implies `--json`
+    #[arg(long, global = true)]
+    pub(crate) json_compact: bool,
+
+    /// Show statuses as plain text instead of emoji (or set `TT_NO_EMOJI=true`)
+    #[arg(long, global = true)]
+    pub(crate) no_emoji: bool,
+
+    /// Color statuses: auto (only on a terminal), always or never
+    #[arg(long, global = true, value_enum, value_name = "WHEN")]
+    pub(crate) color: Option<ColorChoice>,
+
+    /// Keep tasks in this file instead of the config directory (overrides `TT_CONFIG_DIR`)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub(crate) store: Option<PathBuf>,
+
+    /// Log what happens (loading, status changes, saving) to stderr
+    #[arg(long, global = true)]
+    pub(crate) verbose: bool,
+
+    /// Don't print confirmations of changes; commands that show tasks still print them
+    #[arg(long, global = true)]
+    pub(crate) quiet: bool,
+
+    /// Command to run; defaults to `status` (or `TT_DEFAULT_COMMAND`) when omitted
     #[command(subcommand)]
-    pub(crate) command: Commands,
+    pub(crate) command: Option<Commands>,
 }
 
 #[derive(Subcommand)]
@@ -15,56 +64,475 @@ pub(crate) enum Commands {
     /// Start a new task with a label
     #[command(visible_alias = "s")]
     Start {
-        /// Label for the task
-        label: String,
+        /// Label for the task; several words are joined with spaces, so quoting is optional
+        #[arg(required = true, num_args = 1..)]
+        label: Vec<String>,
+        /// Project the task belongs to
+        #[arg(long)]
+        project: Option<String>,
+        /// Tag the task, e.g. with the client it's billed to (repeatable)
+        #[arg(short = 't', long = "tag", value_name = "TAG")]
+        tags: Vec<String>,
+        /// How important the task is (defaults to normal)
+        #[arg(long, value_enum)]
+        priority: Option<Priority>,
+        /// When work actually began: an RFC 3339 timestamp or an offset like -20m
+        #[arg(long, value_name = "TIME", allow_hyphen_values = true)]
+        at: Option<String>,
+        /// Start even if a running or paused task already has this label
+        #[arg(long)]
+        force: bool,
+        /// Announce once when the task's time reaches this goal (e.g. 25m)
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        goal: Option<Duration>,
+        /// How long you expect the task to take (e.g. 2h), shown next to the actual time
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        estimate: Option<Duration>,
     },
     /// Pause the currently running task
     #[command(visible_alias = "p")]
-    Pause,
-    /// Resume the currently paused task
+    Pause {
+        /// Pause every running task; having none running is not an error
+        #[arg(long)]
+        all: bool,
+        /// Note where you stopped; it's kept on the task and shown by `status`
+        #[arg(long, value_name = "TEXT", conflicts_with = "all")]
+        note: Option<String>,
+    },
+    /// Resume the currently paused task, or a paused task by index
     #[command(visible_alias = "r")]
-    Resume,
+    Resume {
+        /// Index of a paused task to switch to (1-based)
+        index: Option<usize>,
+    },
+    /// Pause every running task
+    Stop,
     /// Show the current task status
-    Status,
+    Status {
+        /// Truncate the displayed label to this many characters
+        #[arg(long, value_name = "N")]
+        label_max: Option<usize>,
+        /// Print a stable `label|status|total_seconds` line for shell prompts
+        #[arg(long)]
+        porcelain: bool,
+        /// Show times in UTC instead of the local timezone
+        #[arg(long)]
+        utc: bool,
+    },
     /// List all tasks and their durations
     #[command(visible_alias = "l")]
-    List,
+    List {
+        /// Show creation times relative to now (e.g. "2 hours ago")
+        #[arg(long)]
+        relative: bool,
+        /// Sort the listed tasks
+        #[arg(long, value_enum)]
+        sort: Option<SortKey>,
+        /// Reverse the listing order (of `--sort`, or of stored order without it)
+        #[arg(long)]
+        reverse: bool,
+        /// Group the listed tasks, with a subtotal per group
+        #[arg(long, value_enum)]
+        group_by: Option<GroupBy>,
+        /// Round each work session up to this many minutes before summing
+        #[arg(long, value_name = "MINUTES")]
+        round_sessions: Option<u64>,
+        /// Round each task's total time up to this many minutes
+        #[arg(long, value_name = "MINUTES")]
+        round: Option<u64>,
+        /// Keep stored order instead of listing running and paused tasks first (implied by --sort)
+        #[arg(long)]
+        no_float: bool,
+        /// Show times in UTC instead of the local timezone
+        #[arg(long)]
+        utc: bool,
+        /// Print one tab-separated line per task (index, label, status, seconds) for scripts
+        #[arg(long)]
+        plain: bool,
+        /// Only list tasks created on or after this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<NaiveDate>,
+        /// Only list tasks created on or before this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        until: Option<NaiveDate>,
+        /// Layout: one short line per task (compact) or the full listing (detailed)
+        #[arg(long, value_enum, default_value = "detailed", conflicts_with_all = ["plain", "group_by"])]
+        format: ListFormat,
+        /// Only show the N most recently created tasks (totals still cover every task)
+        #[arg(long, value_name = "N")]
+        limit: Option<usize>,
+        /// Show each task's stable ID, shortened to the fewest digits `--id` still accepts
+        #[arg(long, conflicts_with = "plain")]
+        ids: bool,
+    },
+    /// List only the tasks with a given status
+    Filter {
+        /// Status to show: running, paused or completed
+        status: String,
+    },
+    /// Show a one-line overview of all tasks
+    Summary {
+        /// Print the totals block from `list` (total time and counts) instead
+        #[arg(long)]
+        totals: bool,
+    },
+    /// Print the total time tracked across all tasks
+    Total {
+        /// Print the total as a whole number of seconds
+        #[arg(long)]
+        seconds: bool,
+    },
+    /// List the tasks created or worked on today, e.g. for a standup
+    Today,
+    /// Redraw the active task's status every second until Ctrl-C
+    Watch,
     /// Complete the current task
     #[command(visible_alias = "c")]
-    Complete,
+    Complete {
+        /// Index of the task to complete (1-based); defaults to the active task
+        index: Option<usize>,
+        /// Complete the task with this stable ID instead of picking it by index
+        #[arg(long, conflicts_with = "index")]
+        id: Option<u64>,
+        /// Credit at most this much time (e.g. 2h or 1h30m) for the running session
+        #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+        cap_session: Option<Duration>,
+        /// Record a note about the finished work on the task and in the journal
+        #[arg(long)]
+        note: Option<String>,
+        /// Keep the note on the task only, without adding it to the journal
+        #[arg(long)]
+        no_journal: bool,
+    },
     /// Delete a task by index or all completed tasks
     #[command(visible_alias = "d")]
     Delete {
         /// Index of the task to delete (1-based)
         index: Option<usize>,
+        /// Delete the task with this stable ID instead of picking it by index
+        #[arg(long, conflicts_with_all = ["index", "completed"])]
+        id: Option<u64>,
         /// Delete all completed tasks
         #[arg(long)]
         completed: bool,
+        /// Show which tasks would be deleted without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Delete the paused active task without asking for confirmation
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Start a new task with the label, tags and other details of an existing one
+    Clone {
+        /// Index of the task to copy (1-based)
+        index: usize,
+    },
+    /// Move all time from one task into another and delete the emptied task
+    Merge {
+        /// Index of the task to keep (1-based)
+        into: usize,
+        /// Index of the task whose time is moved, then deleted (1-based)
+        from: usize,
+        /// Clip sessions that overlap after the merge instead of only warning about them
+        #[arg(long)]
+        trim_overlaps: bool,
+    },
+    /// Move part of a task's time into a new completed task
+    Split {
+        /// Index of the task to split (1-based); it must be paused or completed
+        index: usize,
+        /// Share of the time to move, between 0 and 1 (e.g. 0.25)
+        ratio: f64,
+        /// Label for the new task
+        new_label: String,
     },
-    /// Rename a task by index
-    #[command(visible_alias = "e")]
+    /// Reset a task's tracked time to zero
+    Restart {
+        /// Index of the task to reset (1-based)
+        index: usize,
+    },
+    /// Rename a task by index or stable ID, or the active task with --active
+    #[command(visible_alias = "e", allow_missing_positional = true)]
     Rename {
         /// Index of the task to rename (1-based)
-        index: usize,
+        #[arg(required_unless_present_any = ["active", "id"], conflicts_with_all = ["active", "id"])]
+        index: Option<usize>,
         /// New label for the task
         new_label: String,
+        /// Rename the active task instead of one picked by index
+        #[arg(long, conflicts_with = "id")]
+        active: bool,
+        /// Rename the task with this stable ID instead of picking it by index
+        #[arg(long)]
+        id: Option<u64>,
+    },
+    /// Attach a note to a task, or clear it with an empty note
+    Note {
+        /// Index of the task to annotate (1-based)
+        index: usize,
+        /// Note text
+        text: String,
+    },
+    /// Change how important a task is
+    SetPriority {
+        /// Index of the task to update (1-based)
+        index: usize,
+        /// New priority level
+        #[arg(value_enum)]
+        level: Priority,
+    },
+    /// Set or update how long a task is expected to take
+    Estimate {
+        /// Index of the task to update (1-based)
+        index: usize,
+        /// Expected time (e.g. 2h or 1h30m)
+        #[arg(value_parser = parse_duration)]
+        duration: Duration,
+    },
+    /// Add time worked without the timer running to a task
+    AddTime {
+        /// Index of the task to add time to (1-based)
+        index: usize,
+        /// Time to add (e.g. 45m or 1h30m)
+        duration: String,
+    },
+    /// Move a task to a different position in the list
+    Reorder {
+        /// Current index of the task (1-based)
+        from: usize,
+        /// New index for the task (1-based)
+        to: usize,
+    },
+    /// Add a prefix to the labels of every task in a project
+    Relabel {
+        /// Project whose tasks are relabelled
+        #[arg(long)]
+        project: String,
+        /// Text to put in front of each label
+        #[arg(long)]
+        prefix: String,
+    },
+    /// Show detailed information about a task
+    Info {
+        /// Index of the task to inspect (1-based), or part of its label (case-insensitive)
+        query: String,
+        /// Show how the total duration is computed
+        #[arg(long)]
+        explain: bool,
+        /// Show times in UTC instead of the local timezone
+        #[arg(long)]
+        utc: bool,
+    },
+    /// Show details of the task worked on most recently, even if it's completed
+    Last,
+    /// Check the timer state, e.g. to guard CI runs
+    Check {
+        /// Exit with an error if any task is still running
+        #[arg(long)]
+        fail_if_running: bool,
+    },
+    /// List paused tasks that haven't been worked on for a while
+    Stale {
+        /// Only list tasks idle for longer than this (e.g. 12h or 2d)
+        #[arg(long, value_name = "DURATION", default_value = "1d", value_parser = parse_duration)]
+        older_than: Duration,
+    },
+    /// Merge work sessions separated by only a short break
+    TidySessions {
+        /// Merge sessions separated by less than this many seconds
+        #[arg(long, default_value_t = 60)]
+        gap: u64,
+        /// Clip overlapping sessions instead of only warning about them
+        #[arg(long)]
+        trim_overlaps: bool,
+    },
+    /// Remove all running and paused tasks without completing them
+    Abort {
+        /// Abort every running and paused task
+        #[arg(long)]
+        all: bool,
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove all tasks to start over
+    Clean {
+        /// Keep the active task as the only remaining task
+        #[arg(long)]
+        keep_active: bool,
+        /// Skip the confirmation prompt
+        #[arg(long)]
+        force: bool,
+    },
+    /// Move completed tasks to the archive next to the task file
+    Archive {
+        /// Print the archived tasks instead of archiving anything
+        #[arg(long)]
+        list: bool,
+    },
+    /// Revert the last command that changed tasks (only one level is kept)
+    Undo,
+    /// Export tasks for use in other tools
+    Export {
+        /// Output format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: ExportFormat,
+        /// Only export tasks created on or after this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<NaiveDate>,
+        /// Only export tasks created on or before this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        until: Option<NaiveDate>,
+        /// Date each task is filed under in a worklog
+        #[arg(long, value_enum, default_value = "created")]
+        by: WorklogDate,
+        /// Round each work session up to this many minutes before summing (csv and worklog)
+        #[arg(long, value_name = "MINUTES")]
+        round_sessions: Option<u64>,
+        /// Export the whole task file, including which task is active (needs `--format json`)
+        #[arg(long, conflicts_with_all = ["since", "until"])]
+        full: bool,
+    },
+    /// Import tasks from a JSON file, e.g. one copied from another machine
+    Import {
+        /// File with a JSON array of tasks, or a whole tasks.json
+        path: PathBuf,
+        /// Add the imported tasks to the current ones instead of replacing them
+        #[arg(long)]
+        merge: bool,
+        /// Clip overlapping sessions after the import instead of only warning about them
+        #[arg(long)]
+        trim_overlaps: bool,
+    },
+    /// Report time per day for a week
+    Report {
+        /// Show a grid of time per task and weekday instead of a list of days
+        #[arg(long)]
+        weekly: bool,
+        /// Report on the week containing this day (YYYY-MM-DD) instead of the current week
+        #[arg(long, value_name = "DATE")]
+        week_of: Option<NaiveDate>,
+        /// Total time per day of one week, or per ISO week tasks were created in
+        #[arg(long, value_enum, default_value = "day", conflicts_with_all = ["weekly", "week_of"])]
+        by: ReportPeriod,
+        /// Round each task's time per day up to this many minutes
+        #[arg(long, value_name = "MINUTES")]
+        round: Option<u64>,
+        /// Round each work session up to this many minutes before summing
+        #[arg(long, value_name = "MINUTES")]
+        round_sessions: Option<u64>,
+        /// Only include tasks created on or after this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        since: Option<NaiveDate>,
+        /// Only include tasks created on or before this day (YYYY-MM-DD)
+        #[arg(long, value_name = "DATE")]
+        until: Option<NaiveDate>,
+    },
+    /// Inspect the timer's configuration
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Show how a duration string such as 1h30m is interpreted
+    #[command(hide = true)]
+    ParseDuration {
+        /// Duration to parse (e.g. 45m, 2h or 1h30m)
+        input: String,
+    },
+    /// Print a shell completion script
+    #[command(hide = true)]
+    Completions {
+        /// Shell to generate the script for
+        #[arg(value_enum)]
+        shell: Shell,
     },
 }
 
+#[derive(Subcommand)]
+pub(crate) enum ConfigAction {
+    /// Print every effective setting and where its value came from
+    Show,
+}
+
 #[allow(dead_code)]
 impl Commands {
     pub(crate) fn name(&self) -> &'static str {
         match self {
             Commands::Start { .. } => "start",
-            Commands::Pause => "pause",
-            Commands::Resume => "resume",
-            Commands::Status => "status",
-            Commands::List => "list",
-            Commands::Complete => "complete",
+            Commands::Pause { .. } => "pause",
+            Commands::Resume { .. } => "resume",
+            Commands::Stop => "stop",
+            Commands::Status { .. } => "status",
+            Commands::List { .. } => "list",
+            Commands::Filter { .. } => "filter",
+            Commands::Summary { .. } => "summary",
+            Commands::Total { .. } => "total",
+            Commands::Today => "today",
+            Commands::Watch => "watch",
+            Commands::Complete { .. } => "complete",
             Commands::Delete { .. } => "delete",
+            Commands::Clone { .. } => "clone",
+            Commands::Merge { .. } => "merge",
+            Commands::Split { .. } => "split",
+            Commands::Restart { .. } => "restart",
             Commands::Rename { .. } => "rename",
+            Commands::Note { .. } => "note",
+            Commands::SetPriority { .. } => "set-priority",
+            Commands::Estimate { .. } => "estimate",
+            Commands::AddTime { .. } => "add-time",
+            Commands::Reorder { .. } => "reorder",
+            Commands::Relabel { .. } => "relabel",
+            Commands::Info { .. } => "info",
+            Commands::Last => "last",
+            Commands::Check { .. } => "check",
+            Commands::Stale { .. } => "stale",
+            Commands::TidySessions { .. } => "tidy-sessions",
+            Commands::Abort { .. } => "abort",
+            Commands::Clean { .. } => "clean",
+            Commands::Archive { .. } => "archive",
+            Commands::Undo => "undo",
+            Commands::Export { .. } => "export",
+            Commands::Import { .. } => "import",
+            Commands::Report { .. } => "report",
+            Commands::Config { .. } => "config",
+            Commands::ParseDuration { .. } => "parse-duration",
+            Commands::Completions { .. } => "completions",
         }
     }
+
+    /// Whether printing is the point of the command, so `--quiet` must not silence it
+    ///
+    /// The remaining commands change tasks and only print a confirmation.
+    pub(crate) fn prints_output(&self) -> bool {
+        matches!(
+            self,
+            Commands::Status { .. }
+                | Commands::List { .. }
+                | Commands::Filter { .. }
+                | Commands::Summary { .. }
+                | Commands::Total { .. }
+                | Commands::Today
+                | Commands::Watch
+                | Commands::Info { .. }
+                | Commands::Last
+                | Commands::Check { .. }
+                | Commands::Stale { .. }
+                | Commands::Archive { list: true }
+                | Commands::Delete { dry_run: true, .. }
+                | Commands::Export { .. }
+                | Commands::Report { .. }
+                | Commands::Config { .. }
+                | Commands::ParseDuration { .. }
+                | Commands::Completions { .. }
+        )
+    }
+}
+
+/// Generates the tab-completion script for `shell`, completing the `tt` binary
+pub(crate) fn completion_script(shell: Shell) -> String {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "tt", &mut script);
+    String::from_utf8_lossy(&script).into_owned()
 }
 
 #[cfg(test)]
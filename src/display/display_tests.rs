@@ -1,5 +1,5 @@
 use super::*;
-use crate::task::Task;
+use crate::task::{Priority, Task, WorkSession};
 use std::time::Duration;
 
 #[test]
@@ -28,25 +28,79 @@ fn test_format_duration_zero() {
 
 #[test]
 fn test_format_status() {
-    assert!(format_status(&TaskStatus::Running).contains("Running"));
-    assert!(format_status(&TaskStatus::Paused).contains("Paused"));
-    assert!(format_status(&TaskStatus::Completed).contains("Completed"));
+    assert!(format_status(&TaskStatus::Running, false, false).contains("Running"));
+    assert!(format_status(&TaskStatus::Paused, false, false).contains("Paused"));
+    assert!(format_status(&TaskStatus::Completed, false, false).contains("Completed"));
+}
+
+#[test]
+fn test_format_status_with_color() {
+    assert_eq!(
+        format_status(&TaskStatus::Running, true, true),
+        "\u{1b}[32mRunning\u{1b}[39m"
+    );
+    assert_eq!(
+        format_status(&TaskStatus::Paused, true, true),
+        "\u{1b}[33mPaused\u{1b}[39m"
+    );
+    assert_eq!(
+        format_status(&TaskStatus::Completed, true, true),
+        "\u{1b}[90mCompleted\u{1b}[39m"
+    );
+    // Emoji are kept when coloring
+    assert!(format_status(&TaskStatus::Running, false, true).contains("🏃 Running"));
+}
+
+#[test]
+fn test_color_choice_enabled() {
+    assert!(ColorChoice::Auto.enabled(true));
+    assert!(!ColorChoice::Auto.enabled(false));
+    assert!(ColorChoice::Always.enabled(false));
+    assert!(!ColorChoice::Never.enabled(true));
+}
+
+#[test]
+fn test_format_status_without_emoji() {
+    assert_eq!(format_status(&TaskStatus::Running, true, false), "Running");
+    assert_eq!(format_status(&TaskStatus::Paused, true, false), "Paused");
+    assert_eq!(
+        format_status(&TaskStatus::Completed, true, false),
+        "Completed"
+    );
+
+    let task = Task::new("Plain".to_string(), Vec::new());
+    let options = DisplayOptions { no_emoji: true, ..Default::default() };
+    assert!(display_task(&task, None, &options).starts_with("Plain [Running] - "));
+    assert!(display_current_status(Some(&task), None, true, false, false).contains("[Running]"));
+    assert!(display_task_summary(std::slice::from_ref(&task), &options).contains("[Running]"));
 }
 
 #[test]
 fn test_display_task_with_index() {
-    let task = Task::new("Test Task".to_string());
-    let display = display_task(&task, Some(0));
+    let task = Task::new("Test Task".to_string(), Vec::new());
+    let display = display_task(&task, Some(0), &DisplayOptions::default());
 
     assert!(display.starts_with("1. Test Task"));
     assert!(display.contains("Running"));
-    assert!(display.contains("Created:"));
+    assert!(display.contains("(1 session) (Created:"));
+}
+
+#[test]
+fn test_display_task_session_count() {
+    let mut task = Task::new("Fragmented".to_string(), Vec::new());
+    task.pause().unwrap();
+    task.resume().unwrap();
+    assert!(display_task(&task, None, &DisplayOptions::default()).contains(" (2 sessions) ("));
+
+    // Unknown for tasks from older versions, so nothing is shown
+    task.session_count = 0;
+    assert!(!display_task(&task, None, &DisplayOptions::default()).contains("session"));
 }
 
 #[test]
 fn test_display_task_without_index() {
-    let task = Task::new("Test Task".to_string());
-    let display = display_task(&task, None);
+    let task = Task::new("Test Task".to_string(), Vec::new());
+    let display = display_task(&task, None, &DisplayOptions::default());
 
     assert!(display.starts_with("Test Task"));
     assert!(!display.starts_with("1."));
@@ -55,34 +109,151 @@ fn test_display_task_without_index() {
 
 #[test]
 fn test_display_current_status_with_task() {
-    let task = Task::new("Active Task".to_string());
-    let status = display_current_status(Some(&task));
+    let task = Task::new("Active Task".to_string(), Vec::new());
+    let status = display_current_status(Some(&task), None, false, false, false);
 
     assert!(status.contains("Current Task: Active Task"));
     assert!(status.contains("Running"));
 }
 
+#[test]
+fn test_display_current_status_shows_session_start() {
+    let mut task = Task::new("Active Task".to_string(), Vec::new());
+    let status = display_current_status(Some(&task), None, false, false, false);
+    let expected = format!(
+        " (started at {})",
+        format_session_start(task.started_at.unwrap(), Local::now(), false)
+    );
+    assert!(status.ends_with(&expected));
+
+    task.pause().unwrap();
+    let status = display_current_status(Some(&task), None, false, false, false);
+    assert!(!status.contains("started at"));
+}
+
+#[test]
+fn test_progress_bar() {
+    let goal = Duration::from_secs(25 * 60);
+    assert_eq!(progress_bar(Duration::ZERO, goal, 8), "[--------] 0%");
+    assert_eq!(progress_bar(goal / 2, goal, 8), "[####----] 50%");
+    assert_eq!(progress_bar(goal, goal, 8), "[########] 100%");
+    // Past the goal the bar stays full
+    assert_eq!(progress_bar(goal * 3, goal, 8), "[########] 100%");
+    // Just short of the goal is not shown as done
+    assert_eq!(
+        progress_bar(goal - Duration::from_secs(1), goal, 8),
+        "[#######-] 99%"
+    );
+}
+
+#[test]
+fn test_display_current_status_shows_goal_progress() {
+    let mut task = Task::new("Pomodoro".to_string(), Vec::new());
+    let status = display_current_status(Some(&task), None, false, false, false);
+    assert!(!status.contains('%'));
+
+    task.goal = Some(Duration::from_secs(60 * 60));
+    task.accumulated_duration = Duration::from_secs(30 * 60);
+    let status = display_current_status(Some(&task), None, false, false, false);
+    assert!(status.ends_with(" [#####-----] 50%"));
+
+    // Only a running task shows its progress
+    task.pause().unwrap();
+    let status = display_current_status(Some(&task), None, false, false, false);
+    assert!(!status.contains('%'));
+}
+
+#[test]
+fn test_format_session_start() {
+    let now = Local::now();
+    let started_at = now.with_timezone(&Utc);
+    assert_eq!(
+        format_session_start(started_at, now, false),
+        now.format("%H:%M").to_string()
+    );
+
+    let earlier = started_at - chrono::Duration::days(2);
+    assert_eq!(
+        format_session_start(earlier, now, false),
+        earlier
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M")
+            .to_string()
+    );
+}
+
+#[test]
+fn test_format_session_start_utc() {
+    let now = Local::now();
+    let started_at = now.with_timezone(&Utc);
+    assert_eq!(
+        format_session_start(started_at, now, true),
+        started_at.format("%H:%M UTC").to_string()
+    );
+
+    let earlier = started_at - chrono::Duration::days(2);
+    assert_eq!(
+        format_session_start(earlier, now, true),
+        earlier.format("%Y-%m-%d %H:%M UTC").to_string()
+    );
+}
+
+#[test]
+fn test_display_current_status_truncates_label() {
+    let task = Task::new("Refactor the authentication module".to_string(), Vec::new());
+    let status = display_current_status(Some(&task), Some(8), false, false, false);
+
+    assert!(status.contains("Current Task: Refactor… ["));
+}
+
+#[test]
+fn test_display_current_status_porcelain() {
+    let mut task = task_in_project("Fix a|b", None, 30, 1547);
+    assert_eq!(
+        display_current_status_porcelain(Some(&task)),
+        "Fix a\\|b|paused|1547"
+    );
+
+    task.complete().unwrap();
+    assert_eq!(
+        display_current_status_porcelain(Some(&task)),
+        "Fix a\\|b|completed|1547"
+    );
+    assert_eq!(display_current_status_porcelain(None), "");
+}
+
+#[test]
+fn test_truncate_label() {
+    assert_eq!(truncate_label("Short", 10), "Short");
+    assert_eq!(truncate_label("Exactly10!", 10), "Exactly10!");
+    assert_eq!(truncate_label("Longer label", 6), "Longer…");
+    // Multi-byte characters are counted, and never split, as single characters
+    assert_eq!(truncate_label("Café ☕ résumé", 6), "Café ☕…");
+    assert_eq!(truncate_label("日本語のタスク", 3), "日本語…");
+    assert_eq!(truncate_label("Anything", 0), "…");
+}
+
 #[test]
 fn test_display_current_status_no_task() {
-    let status = display_current_status(None);
+    let status = display_current_status(None, None, false, false, false);
     assert_eq!(status, "No active task");
 }
 
 #[test]
 fn test_display_task_summary_empty() {
     let tasks: Vec<Task> = vec![];
-    let summary = display_task_summary(&tasks);
+    let summary = display_task_summary(&tasks, &DisplayOptions::default());
     assert_eq!(summary, "No tasks found");
 }
 
 #[test]
 fn test_display_task_summary_with_tasks() {
     let tasks = vec![
-        Task::new("Task 1".to_string()),
-        Task::new("Task 2".to_string()),
+        Task::new("Task 1".to_string(), Vec::new()),
+        Task::new("Task 2".to_string(), Vec::new()),
     ];
 
-    let summary = display_task_summary(&tasks);
+    let summary = display_task_summary(&tasks, &DisplayOptions::default());
 
     assert!(summary.contains("Task Summary (2 tasks)"));
     assert!(summary.contains("Task 1"));
@@ -93,12 +264,38 @@ fn test_display_task_summary_with_tasks() {
     assert!(summary.contains("Completed: "));
 }
 
+#[test]
+fn test_display_task_summary_compact() {
+    let mut tasks = vec![
+        Task::new("Task 1".to_string(), Vec::new()),
+        Task::new("Task 2".to_string(), Vec::new()),
+    ];
+    tasks[0].complete().unwrap();
+    tasks[0].accumulated_duration = Duration::from_secs(90);
+    tasks[1].pause().unwrap();
+    tasks[1].accumulated_duration = Duration::from_secs(3600);
+
+    let options = DisplayOptions {
+        float_active: true,
+        format: ListFormat::Compact,
+        ..Default::default()
+    };
+    assert_eq!(
+        display_task_compact(&tasks[1], 1, &options),
+        "2. Task 2 — 1h 0m 0s"
+    );
+
+    // Indices stay the stored ones when active tasks float to the top
+    let summary = display_task_summary(&tasks, &options);
+    assert_eq!(summary, "2. Task 2 — 1h 0m 0s\n1. Task 1 — 1m 30s");
+}
+
 #[test]
 fn test_display_task_summary_counts() {
     let mut tasks = vec![
-        Task::new("Running Task".to_string()),
-        Task::new("Paused Task".to_string()),
-        Task::new("Completed Task".to_string()),
+        Task::new("Running Task".to_string(), Vec::new()),
+        Task::new("Paused Task".to_string(), Vec::new()),
+        Task::new("Completed Task".to_string(), Vec::new()),
     ];
 
     // Pause the second task
@@ -107,9 +304,826 @@ fn test_display_task_summary_counts() {
     // Complete the third task
     tasks[2].complete().unwrap();
 
-    let summary = display_task_summary(&tasks);
+    let summary = display_task_summary(&tasks, &DisplayOptions::default());
 
     assert!(summary.contains("Running: 1"));
     assert!(summary.contains("Paused: 1"));
     assert!(summary.contains("Completed: 1"));
 }
+
+#[test]
+fn test_display_today_summary_notes_tasks_running_since_before_today() {
+    let now = Utc::now();
+    let today = now.with_timezone(&Local).date_naive();
+    let overnight = Task::new_at(
+        "Overnight".to_string(),
+        Vec::new(),
+        now - chrono::Duration::days(1),
+    );
+    let fresh = Task::new("Fresh".to_string(), Vec::new());
+
+    let summary = display_today_summary(
+        &[(0, &overnight), (3, &fresh)],
+        today,
+        &DisplayOptions::default(),
+    );
+    assert!(summary.contains("Task Summary (2 tasks)"));
+    assert!(summary.contains("4. Fresh"));
+    assert!(summary.ends_with(
+        "Note: 'Overnight' has been running since before today; its time includes earlier days"
+    ));
+    assert!(!summary.contains("'Fresh' has been running"));
+
+    assert_eq!(
+        display_today_summary(&[], today, &DisplayOptions::default()),
+        "No tasks worked on today"
+    );
+}
+
+#[test]
+fn test_compute_totals() {
+    let mut tasks = vec![
+        task_in_project("Paused", None, 30, 420),
+        task_in_project("Done", None, 20, 60),
+        Task::new("Running".to_string(), Vec::new()),
+    ];
+    tasks[1].complete().unwrap();
+
+    let totals = compute_totals(&tasks[..2], &DisplayOptions::default());
+    assert_eq!(
+        totals,
+        TaskStats {
+            running: 0,
+            paused: 1,
+            completed: 1,
+            total_tasks: 2,
+            total_duration: Duration::from_secs(480),
+        }
+    );
+
+    // Rounding applies per task, as in the listing
+    let options = DisplayOptions { round: Some(15), ..Default::default() };
+    assert_eq!(
+        compute_totals(&tasks[..2], &options).total_duration,
+        Duration::from_secs(30 * 60)
+    );
+    assert_eq!(compute_totals(&tasks, &options).running, 1);
+}
+
+#[test]
+fn test_display_totals_matches_summary_footer() {
+    let tasks = vec![
+        task_in_project("Paused", None, 30, 420),
+        Task::new("Running".to_string(), Vec::new()),
+    ];
+    let options = DisplayOptions::default();
+
+    let totals = display_totals(&tasks, &options);
+    assert!(totals.starts_with("Total Time: 7m 0s\n"));
+    assert!(totals.ends_with("Running: 1 | Paused: 1 | Completed: 0"));
+    assert!(!totals.contains("Paused ["));
+    assert!(display_task_summary(&tasks, &options).ends_with(&totals));
+
+    assert_eq!(display_totals(&[], &options), "No tasks found");
+}
+
+#[test]
+fn test_display_task_info() {
+    let task = Task::new("Inspect Me".to_string(), Vec::new());
+    let options = DisplayOptions { utc: true, ..Default::default() };
+    let info = display_task_info(&task, 3, &options);
+
+    assert!(info.starts_with("Task 3: Inspect Me"));
+    assert!(info.contains("Status:"));
+    assert!(info.contains("Running"));
+    assert!(info.contains("Created:"));
+    assert!(info.contains("Duration:"));
+    assert!(!info.contains("Goal:"));
+    assert!(!info.contains("Tags:"));
+    assert!(info.ends_with("  Sessions: 1 (0 recorded)"));
+}
+
+#[test]
+fn test_display_task_info_tags() {
+    let task = Task::new(
+        "Weekly sync".to_string(),
+        vec!["acme".to_string(), "meetings".to_string()],
+    );
+    let info = display_task_info(&task, 1, &DisplayOptions::default());
+    assert!(info.contains("  Tags:     #acme #meetings\n"));
+}
+
+#[test]
+fn test_display_task_info_goal() {
+    let mut task = task_in_project("Pomodoro", None, 30, 600);
+    task.goal = Some(Duration::from_secs(25 * 60));
+    let info = display_task_info(&task, 1, &DisplayOptions::default());
+    assert!(info.contains("  Goal:     25m 0s\n"));
+
+    task.accumulated_duration = Duration::from_secs(30 * 60);
+    let info = display_task_info(&task, 1, &DisplayOptions::default());
+    assert!(info.contains("  Goal:     25m 0s (reached)\n"));
+}
+
+#[test]
+fn test_display_task_with_estimate() {
+    let mut task = task_in_project("Migration", None, 30, 90 * 60);
+    task.estimate = Some(Duration::from_secs(2 * 60 * 60));
+    let options = DisplayOptions { no_emoji: true, ..Default::default() };
+
+    let line = display_task(&task, Some(0), &options);
+    assert!(line.contains("[Paused] - 1h 30m 0s/2h 0m 0s (75%) (1 session)"));
+    assert!(!line.contains("over budget"));
+
+    task.accumulated_duration = Duration::from_secs(150 * 60);
+    let line = display_task(&task, Some(0), &options);
+    assert!(line.contains("- 2h 30m 0s/2h 0m 0s (125%) [over budget] (1 session)"));
+
+    let info = display_task_info(&task, 1, &DisplayOptions::default());
+    assert!(info.contains("  Estimate: 2h 0m 0s (125%) ⚠️ over budget\n"));
+}
+
+#[test]
+fn test_display_duration_explanation_running_task() {
+    let mut task = Task::new("Explained Task".to_string(), Vec::new());
+    task.accumulated_duration = Duration::from_secs(60);
+    task.started_at = Some(Utc::now() - chrono::Duration::seconds(30));
+
+    let explanation = display_duration_explanation(&task, true);
+
+    assert!(explanation.contains("Accumulated (previous sessions): 60s (1m 0s)"));
+    assert!(explanation.contains("+ Live session (started "));
+    assert!(explanation.contains("): 30s (30s)"));
+    assert!(explanation.contains("= Total: 90s (1m 30s)"));
+}
+
+#[test]
+fn test_display_duration_explanation_paused_task() {
+    let mut task = Task::new("Paused Task".to_string(), Vec::new());
+    task.pause().unwrap();
+    task.accumulated_duration = Duration::from_secs(125);
+
+    let explanation = display_duration_explanation(&task, true);
+
+    assert!(explanation.contains("Accumulated (previous sessions): 125s (2m 5s)"));
+    assert!(explanation.contains("Live session: none"));
+    assert!(explanation.contains("= Total: 125s (2m 5s)"));
+    assert!(!explanation.contains("clock"));
+}
+
+#[test]
+fn test_display_duration_explanation_notes_clock_skew() {
+    let started_at = DateTime::parse_from_rfc3339("2024-06-03T09:00:00Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let mut task = Task::new_at("Skewed Task".to_string(), Vec::new(), started_at);
+    task.pause().unwrap();
+    task.sessions = vec![WorkSession {
+        started_at,
+        duration: Duration::ZERO,
+        clock_skew: Some(Duration::from_secs(600)),
+    }];
+    task.accumulated_duration = Duration::ZERO;
+
+    let explanation = display_duration_explanation(&task, true);
+    assert!(explanation.contains(
+        "    (session started 2024-06-03 09:00:00 UTC counted as 0s: the clock moved back 10m 0s)\n"
+    ));
+
+    // A running session that starts in the future is clamped to zero as well
+    let mut task = Task::new("Running Task".to_string(), Vec::new());
+    task.started_at = Some(Utc::now() + chrono::Duration::minutes(5));
+    let explanation = display_duration_explanation(&task, true);
+    assert!(explanation.contains("counted as 0s: the clock is behind the session's start"));
+}
+
+#[test]
+fn test_format_relative_to() {
+    let now = Utc::now();
+
+    assert_eq!(format_relative_to(now, now), "just now");
+    assert_eq!(
+        format_relative_to(now - chrono::Duration::minutes(1), now),
+        "1 minute ago"
+    );
+    assert_eq!(
+        format_relative_to(now - chrono::Duration::hours(2), now),
+        "2 hours ago"
+    );
+    assert_eq!(
+        format_relative_to(now - chrono::Duration::days(3), now),
+        "3 days ago"
+    );
+    // Future timestamps never render as negative
+    assert_eq!(
+        format_relative_to(now + chrono::Duration::hours(1), now),
+        "just now"
+    );
+}
+
+#[test]
+fn test_format_relative_to_unit_boundaries() {
+    let now = Utc::now();
+    let ago = |seconds: i64| format_relative_to(now - chrono::Duration::seconds(seconds), now);
+
+    assert_eq!(ago(59), "just now");
+    assert_eq!(ago(60), "1 minute ago");
+    assert_eq!(ago(59 * 60), "59 minutes ago");
+    assert_eq!(ago(59 * 60 + 59), "59 minutes ago");
+    assert_eq!(ago(60 * 60), "1 hour ago");
+    assert_eq!(ago(23 * 3600 + 59 * 60), "23 hours ago");
+    assert_eq!(ago(24 * 3600), "1 day ago");
+}
+
+#[test]
+fn test_display_task_relative_replaces_absolute_timestamp() {
+    let mut task = Task::new("Relative Task".to_string(), Vec::new());
+    task.created_at = Utc::now() - chrono::Duration::hours(2);
+
+    let options = DisplayOptions { relative: true, ..Default::default() };
+    let display = display_task(&task, Some(0), &options);
+
+    assert!(display.ends_with("(2 hours ago)"));
+    assert!(!display.contains("Created:"));
+    assert!(!display.contains("UTC"));
+}
+
+#[test]
+fn test_display_task_shows_completion_time() {
+    let dt = DateTime::parse_from_rfc3339("2025-10-30T14:05:09Z")
+        .unwrap()
+        .with_timezone(&Utc);
+    let mut task = Task::new("Done Task".to_string(), Vec::new());
+    task.complete().unwrap();
+    task.completed_at = Some(dt);
+
+    let options = DisplayOptions { utc: true, ..Default::default() };
+    let display = display_task(&task, None, &options);
+    assert!(display.ends_with(", Completed: 2025-10-30 14:05:09 UTC)"));
+
+    task.completed_at = Some(Utc::now() - chrono::Duration::hours(3));
+    let options = DisplayOptions { relative: true, ..Default::default() };
+    let display = display_task(&task, None, &options);
+    assert!(display.ends_with(", completed 3 hours ago)"));
+
+    // Tasks completed before completion times were recorded show only the creation time
+    task.completed_at = None;
+    assert!(!display_task(&task, None, &options).contains("completed"));
+}
+
+fn task_in_project(label: &str, project: Option<&str>, minutes_ago: i64, secs: u64) -> Task {
+    let mut task = Task::new(label.to_string(), Vec::new());
+    task.pause().unwrap();
+    task.created_at = Utc::now() - chrono::Duration::minutes(minutes_ago);
+    task.accumulated_duration = Duration::from_secs(secs);
+    task.project = project.map(str::to_string);
+    task
+}
+
+#[test]
+fn test_display_task_summary_grouped_by_project_sorted_by_created() {
+    let tasks = vec![
+        task_in_project("Web newer", Some("Web"), 10, 600),
+        task_in_project("Api only", Some("Api"), 30, 300),
+        task_in_project("Loose task", None, 5, 60),
+        task_in_project("Web older", Some("Web"), 50, 1200),
+    ];
+
+    let options = DisplayOptions {
+        sort: Some(SortKey::Created),
+        group_by: Some(GroupBy::Project),
+        ..Default::default()
+    };
+    let summary = display_task_summary(&tasks, &options);
+
+    // Groups are alphabetical with unassigned tasks last
+    let api = summary.find("Project: Api").unwrap();
+    let web = summary.find("Project: Web").unwrap();
+    let none = summary.find("Project: (no project)").unwrap();
+    assert!(api < web && web < none);
+
+    // Within a group tasks are ordered by creation time but keep their real indices
+    let older = summary.find("4. Web older").unwrap();
+    let newer = summary.find("1. Web newer").unwrap();
+    assert!(web < older && older < newer && newer < none);
+
+    assert!(summary.contains("Subtotal: 5m 0s"));
+    assert!(summary.contains("Subtotal: 30m 0s"));
+    assert!(summary.contains("Subtotal: 1m 0s"));
+    assert!(summary.contains("Total Time: 36m 0s"));
+}
+
+#[test]
+fn test_display_task_summary_sorted_without_grouping() {
+    let tasks = vec![
+        task_in_project("Newest", None, 1, 0),
+        task_in_project("Oldest", None, 60, 0),
+    ];
+
+    let options = DisplayOptions {
+        sort: Some(SortKey::Created),
+        ..Default::default()
+    };
+    let summary = display_task_summary(&tasks, &options);
+
+    assert!(summary.find("2. Oldest").unwrap() < summary.find("1. Newest").unwrap());
+    assert!(!summary.contains("Subtotal"));
+}
+
+#[test]
+fn test_display_task_summary_sorted_by_priority() {
+    let mut tasks = vec![
+        task_in_project("Low chore", None, 40, 0),
+        task_in_project("First normal", None, 30, 0),
+        task_in_project("Hotfix", None, 20, 0),
+        task_in_project("Second normal", None, 10, 0),
+    ];
+    tasks[0].priority = Priority::Low;
+    tasks[2].priority = Priority::High;
+
+    let options = DisplayOptions {
+        sort: Some(SortKey::Priority),
+        ..Default::default()
+    };
+    let summary = display_task_summary(&tasks, &options);
+
+    // High to low, with equal priorities left in their stored order
+    let hotfix = summary.find("3. Hotfix").unwrap();
+    let first = summary.find("2. First normal").unwrap();
+    let second = summary.find("4. Second normal").unwrap();
+    let low = summary.find("1. Low chore").unwrap();
+    assert!(hotfix < first && first < second && second < low);
+}
+
+#[test]
+fn test_display_task_summary_sorted_by_duration_reversed() {
+    let tasks = vec![
+        task_in_project("Medium", None, 40, 600),
+        task_in_project("Longest", None, 30, 3600),
+        task_in_project("Short", None, 20, 60),
+        task_in_project("Also medium", None, 10, 600),
+    ];
+    let options = DisplayOptions {
+        sort: Some(SortKey::Duration),
+        reverse: true,
+        format: ListFormat::Compact,
+        ..Default::default()
+    };
+
+    // Longest first, with equal durations left in their stored order
+    assert_eq!(
+        display_task_summary(&tasks, &options),
+        "2. Longest — 1h 0m 0s\n1. Medium — 10m 0s\n4. Also medium — 10m 0s\n3. Short — 1m 0s"
+    );
+    // The stored order is untouched
+    assert_eq!(tasks[0].label, "Medium");
+}
+
+#[test]
+fn test_display_task_summary_marks_active_task() {
+    let mut tasks = vec![
+        task_in_project("First", None, 30, 60),
+        task_in_project("Second", None, 20, 60),
+        task_in_project("Done", None, 10, 60),
+    ];
+    tasks[2].complete().unwrap();
+    let options = DisplayOptions { active: Some(1), ..Default::default() };
+
+    let output = display_task_summary(&tasks, &options);
+    assert!(output.contains("\n1. First ["));
+    assert!(output.contains("\n* 2. Second ["));
+
+    let compact = DisplayOptions { format: ListFormat::Compact, ..options };
+    assert_eq!(
+        display_task_summary(&tasks, &compact),
+        "1. First — 1m 0s\n* 2. Second — 1m 0s\n3. Done — 1m 0s"
+    );
+
+    // A completed task is never marked, even if it's pointed at
+    let completed = DisplayOptions { active: Some(2), ..Default::default() };
+    assert!(!display_task_summary(&tasks, &completed).contains("* "));
+}
+
+#[test]
+fn test_display_task_summary_limit_keeps_most_recent_and_full_totals() {
+    let tasks = vec![
+        task_in_project("Oldest", None, 30, 600),
+        task_in_project("Newest", None, 10, 60),
+        task_in_project("Middle", None, 20, 60),
+    ];
+    let options = DisplayOptions { limit: Some(2), ..Default::default() };
+
+    let output = display_task_summary(&tasks, &options);
+    assert!(output.starts_with("Task Summary (showing 2 of 3 tasks):"));
+    assert!(!output.contains("Oldest"));
+    // Listed in the usual order, not by age
+    assert!(output.find("2. Newest").unwrap() < output.find("3. Middle").unwrap());
+    assert!(output.contains("Total Time: 12m 0s"));
+
+    // A limit past the task count shows everything
+    let options = DisplayOptions { limit: Some(5), ..Default::default() };
+    assert!(display_task_summary(&tasks, &options).starts_with("Task Summary (3 tasks):"));
+}
+
+#[test]
+fn test_display_task_summary_sorted_by_label() {
+    let tasks = vec![
+        task_in_project("beta", None, 30, 0),
+        task_in_project("Alpha", None, 20, 0),
+        task_in_project("gamma", None, 10, 0),
+    ];
+    let options = DisplayOptions {
+        sort: Some(SortKey::Label),
+        format: ListFormat::Compact,
+        ..Default::default()
+    };
+    assert_eq!(
+        display_task_summary(&tasks, &options),
+        "2. Alpha — 0s\n1. beta — 0s\n3. gamma — 0s"
+    );
+
+    // Without a sort key, --reverse flips the stored order
+    let options = DisplayOptions { sort: None, reverse: true, ..options };
+    assert_eq!(
+        display_task_summary(&tasks, &options),
+        "3. gamma — 0s\n2. Alpha — 0s\n1. beta — 0s"
+    );
+}
+
+#[test]
+fn test_display_task_summary_tag_totals() {
+    let mut tasks = vec![
+        task_in_project("Both", None, 30, 600),
+        task_in_project("Acme only", None, 20, 300),
+        task_in_project("Plain", None, 10, 60),
+    ];
+    tasks[0].tags = vec!["internal".to_string(), "acme".to_string()];
+    tasks[1].tags = vec!["acme".to_string()];
+
+    let summary = display_task_summary(&tasks, &DisplayOptions::default());
+
+    // Below the footer, alphabetical with untagged tasks last; "Both" counts toward each tag
+    let footer = summary.find("Running: ").unwrap();
+    let section = &summary[summary.find("Tag Totals:").unwrap()..];
+    assert!(summary.find("Tag Totals:").unwrap() > footer);
+    assert_eq!(
+        section,
+        "Tag Totals:\n  #acme: 15m 0s\n  #internal: 10m 0s\n  (untagged): 1m 0s"
+    );
+}
+
+#[test]
+fn test_display_task_summary_without_tags_has_no_tag_totals() {
+    let tasks = vec![task_in_project("Plain", None, 10, 60)];
+    let summary = display_task_summary(&tasks, &DisplayOptions::default());
+    assert!(!summary.contains("Tag Totals"));
+}
+
+#[test]
+fn test_display_task_summary_plain() {
+    let mut tasks = vec![
+        task_in_project("Write docs", None, 30, 1547),
+        task_in_project("Review", None, 10, 60),
+    ];
+    tasks[1].complete().unwrap();
+
+    let entries: Vec<_> = tasks.iter().enumerate().collect();
+    assert_eq!(
+        display_task_summary_plain(&entries),
+        "1\tWrite docs\tpaused\t1547\n2\tReview\tcompleted\t60"
+    );
+    // A subset keeps the stored positions
+    assert_eq!(
+        display_task_summary_plain(&entries[1..]),
+        "2\tReview\tcompleted\t60"
+    );
+    assert_eq!(display_task_summary_plain(&[]), "");
+}
+
+#[test]
+fn test_display_task_summary_plain_escapes_labels() {
+    // Only reachable through a hand-edited store, since labels are sanitized on input
+    let task = task_in_project("a\tb\nc\\d", None, 1, 0);
+
+    let plain = display_task_summary_plain(&[(0, &task)]);
+    assert_eq!(plain, "1\ta\\tb\\nc\\\\d\tpaused\t0");
+    assert_eq!(plain.split('\t').count(), 4);
+}
+
+#[test]
+fn test_idle_warnings() {
+    let mut overnight = Task::new("Overnight".to_string(), Vec::new());
+    overnight.started_at = Some(Utc::now() - chrono::Duration::hours(9));
+    let mut paused = Task::new("Paused".to_string(), Vec::new());
+    paused.pause().unwrap();
+    let fresh = Task::new("Fresh".to_string(), Vec::new());
+
+    let warnings = idle_warnings(&[overnight, paused, fresh], Duration::from_secs(8 * 3600));
+
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("Warning: 'Overnight' has been running for 9h 0m"));
+}
+
+#[test]
+fn test_round_duration() {
+    let fifteen = 15;
+
+    assert_eq!(round_duration(Duration::ZERO, fifteen), Duration::ZERO);
+    assert_eq!(
+        round_duration(Duration::from_secs(15 * 60), fifteen),
+        Duration::from_secs(15 * 60)
+    );
+    assert_eq!(
+        round_duration(Duration::from_secs(15 * 60 + 1), fifteen),
+        Duration::from_secs(30 * 60)
+    );
+    assert_eq!(
+        round_duration(Duration::from_millis(500), fifteen),
+        Duration::from_secs(15 * 60)
+    );
+    // A zero increment disables rounding
+    assert_eq!(
+        round_duration(Duration::from_secs(61), 0),
+        Duration::from_secs(61)
+    );
+}
+
+#[test]
+fn test_round_sessions_rounds_each_session_before_summing() {
+    let mut task = Task::new("Billable".to_string(), Vec::new());
+    task.pause().unwrap();
+
+    let start = Utc::now() - chrono::Duration::hours(1);
+    task.sessions = vec![
+        WorkSession {
+            started_at: start,
+            duration: Duration::from_secs(7 * 60),
+            clock_skew: None,
+        },
+        WorkSession {
+            started_at: start + chrono::Duration::minutes(30),
+            duration: Duration::from_secs(7 * 60),
+            clock_skew: None,
+        },
+    ];
+    task.accumulated_duration = Duration::from_secs(14 * 60);
+
+    // Two 7 minute sessions round to 15 minutes each
+    let options = DisplayOptions {
+        round_sessions: Some(15),
+        ..Default::default()
+    };
+    assert_eq!(task_duration(&task, &options), Duration::from_secs(30 * 60));
+
+    // Rounding the 14 minute total once would only give 15 minutes
+    assert_eq!(
+        round_duration(task.total_duration(), 15),
+        Duration::from_secs(15 * 60)
+    );
+
+    let summary = display_task_summary(&[task], &options);
+    assert!(summary.contains("- 30m 0s"));
+    assert!(summary.contains("Total Time: 30m 0s"));
+}
+
+#[test]
+fn test_display_stale_tasks() {
+    assert_eq!(display_stale_tasks(&[]), "No stale paused tasks");
+
+    let mut task = Task::new("Forgotten".to_string(), Vec::new());
+    task.pause().unwrap();
+    task.sessions[0].started_at = Utc::now() - chrono::Duration::hours(74);
+    task.created_at = task.sessions[0].started_at;
+
+    let output = display_stale_tasks(&[(4, &task)]);
+    assert!(output.starts_with("Stale paused tasks (resume or complete them):"));
+    assert!(output.contains("5. Forgotten - last active 3 days ago (0s tracked)"));
+}
+
+#[test]
+fn test_format_duration_compact() {
+    assert_eq!(format_duration_compact(Duration::from_secs(0)), "0s");
+    assert_eq!(format_duration_compact(Duration::from_secs(45)), "45s");
+    assert_eq!(
+        format_duration_compact(Duration::from_secs(25 * 60 + 30)),
+        "25m"
+    );
+    assert_eq!(format_duration_compact(Duration::from_secs(2 * 3600)), "2h");
+    assert_eq!(
+        format_duration_compact(Duration::from_secs(4 * 3600 + 10 * 60)),
+        "4h10m"
+    );
+}
+
+#[test]
+fn test_format_duration_iso8601() {
+    assert_eq!(format_duration_iso8601(Duration::ZERO), "PT0S");
+    assert_eq!(format_duration_iso8601(Duration::from_millis(900)), "PT0S");
+    assert_eq!(format_duration_iso8601(Duration::from_secs(45)), "PT45S");
+    assert_eq!(
+        format_duration_iso8601(Duration::from_secs(90 * 60)),
+        "PT1H30M"
+    );
+    assert_eq!(
+        format_duration_iso8601(Duration::from_secs(3600 + 5)),
+        "PT1H5S"
+    );
+    assert_eq!(
+        format_duration_iso8601(Duration::from_secs(26 * 3600)),
+        "PT26H"
+    );
+}
+
+#[test]
+fn test_display_one_line_summary() {
+    assert_eq!(
+        display_one_line_summary(&TaskStats::default(), None),
+        "No tasks"
+    );
+
+    let mut done = Task::new("Done".to_string(), Vec::new());
+    done.complete().unwrap();
+    done.accumulated_duration = Duration::from_secs(3 * 3600 + 45 * 60);
+    let mut paused = Task::new("Paused".to_string(), Vec::new());
+    paused.pause().unwrap();
+    let mut build = Task::new("Build".to_string(), Vec::new());
+    build.started_at = Some(Utc::now() - chrono::Duration::minutes(25));
+    let tasks = vec![done, paused, build];
+
+    let stats = TaskStats::collect(&tasks, Task::total_duration);
+    let line = display_one_line_summary(&stats, Some(&tasks[2]));
+
+    assert_eq!(
+        line,
+        "3 tasks · 1 running (Build 25m) · 1 paused · 1 completed · total 4h10m"
+    );
+}
+
+#[test]
+fn test_display_task_summary_floats_active_tasks_keeping_indices() {
+    let mut done = Task::new("Done first".to_string(), Vec::new());
+    done.complete().unwrap();
+    let mut paused = Task::new("Paused second".to_string(), Vec::new());
+    paused.pause().unwrap();
+    let running = Task::new("Running third".to_string(), Vec::new());
+    let tasks = vec![done, paused, running];
+
+    let options = DisplayOptions { float_active: true, ..Default::default() };
+    let summary = display_task_summary(&tasks, &options);
+
+    // Active tasks come first in index order, but every task keeps its real index
+    let paused = summary.find("2. Paused second").unwrap();
+    let running = summary.find("3. Running third").unwrap();
+    let done = summary.find("1. Done first").unwrap();
+    assert!(paused < running);
+    assert!(running < done);
+    assert!(!summary.contains("Status:"));
+}
+
+#[test]
+fn test_display_task_summary_sort_overrides_floating() {
+    let mut done = Task::new("Long done".to_string(), Vec::new());
+    done.complete().unwrap();
+    done.accumulated_duration = Duration::from_secs(3 * 3600);
+    let running = Task::new("Short running".to_string(), Vec::new());
+    let tasks = vec![done, running];
+
+    let options = DisplayOptions {
+        sort: Some(SortKey::Duration),
+        reverse: true,
+        float_active: true,
+        ..Default::default()
+    };
+    let summary = display_task_summary(&tasks, &options);
+
+    // Longest first, even though the shorter task is still running
+    assert!(summary.find("1. Long done").unwrap() < summary.find("2. Short running").unwrap());
+}
+
+#[test]
+fn test_format_journal_entry() {
+    let mut task = Task::new("Write report".to_string(), Vec::new());
+    task.complete().unwrap();
+    task.accumulated_duration = Duration::from_secs(80 * 60);
+    task.completed_at = Some(
+        DateTime::parse_from_rfc3339("2025-10-30T14:05:00Z")
+            .unwrap()
+            .with_timezone(&Utc),
+    );
+
+    assert_eq!(
+        format_journal_entry(&task, "Sent to\n  the team"),
+        "- 2025-10-30 14:05  Write report (1h20m): Sent to the team"
+    );
+}
+
+#[test]
+fn test_display_task_shows_tags_after_label() {
+    let task = Task::new(
+        "Client call".to_string(),
+        vec!["acme".to_string(), "billable".to_string()],
+    );
+
+    let display = display_task(&task, Some(0), &DisplayOptions::default());
+    assert!(display.starts_with("1. Client call #acme #billable [🏃 Running]"));
+}
+
+#[test]
+fn test_display_filtered_task_summary_keeps_indices_and_totals_subset() {
+    let tasks = vec![
+        task_in_project("First", None, 10, 600),
+        task_in_project("Second", None, 5, 60),
+    ];
+    let subset: Vec<(usize, &Task)> = vec![(1, &tasks[1])];
+
+    let summary = display_filtered_task_summary(&subset, &DisplayOptions::default());
+    assert!(summary.contains("Task Summary (1 tasks)"));
+    assert!(summary.contains("2. Second"));
+    assert!(!summary.contains("First"));
+    assert!(summary.contains("Total Time: 1m 0s"));
+
+    assert_eq!(
+        display_filtered_task_summary(&[], &DisplayOptions::default()),
+        "No matching tasks found"
+    );
+}
+
+#[test]
+fn test_json_task_list_uses_task_view_fields() {
+    let mut task = Task::new("Json Task".to_string(), vec!["internal".to_string()]);
+    task.id = 4;
+    task.pause().unwrap();
+    task.accumulated_duration = Duration::from_secs(90);
+
+    let json = json_task_list(&[(0, &task)], &DisplayOptions::default(), false).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(
+        value,
+        serde_json::json!([{
+            "id": 4,
+            "label": "Json Task",
+            "status": "paused",
+            "total_seconds": 90,
+            "total_iso8601": "PT1M30S",
+            "created_at": task.created_at.to_rfc3339(),
+        }])
+    );
+    assert_eq!(json_current_status(None, false).unwrap(), "null");
+
+    let compact = json_task_list(&[(0, &task)], &DisplayOptions::default(), true).unwrap();
+    assert!(!compact.contains('\n'));
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+        value
+    );
+}
+
+#[test]
+fn test_format_timestamp() {
+    let dt = DateTime::parse_from_rfc3339("2025-10-30T14:05:09Z")
+        .unwrap()
+        .with_timezone(&Utc);
+
+    assert_eq!(format_timestamp(dt, true), "2025-10-30 14:05:09 UTC");
+
+    let expected_local = dt.with_timezone(&Local);
+    assert_eq!(
+        format_timestamp(dt, false),
+        format!(
+            "{} {}",
+            expected_local.format("%Y-%m-%d %H:%M:%S"),
+            expected_local.offset()
+        )
+    );
+}
+
+#[test]
+fn test_display_task_shows_note_on_second_line() {
+    let mut task = Task::new("Annotated".to_string(), Vec::new());
+    let options = DisplayOptions::default();
+    assert!(!display_task(&task, Some(0), &options).contains('\n'));
+
+    task.note = Some("Fixed the flaky test".to_string());
+    let display = display_task(&task, Some(0), &options);
+    let lines: Vec<&str> = display.lines().collect();
+
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("1. Annotated ["));
+    assert_eq!(lines[1], "   Note: Fixed the flaky test");
+}
+
+#[test]
+fn test_display_task_summary_rounds_task_totals() {
+    let tasks = vec![
+        task_in_project("Short", None, 10, 7 * 60),
+        task_in_project("Exact", None, 5, 15 * 60),
+    ];
+
+    let options = DisplayOptions { round: Some(15), ..Default::default() };
+    let summary = display_task_summary(&tasks, &options);
+
+    assert!(summary.contains("1. Short [⏸️  Paused] - 15m 0s"));
+    assert!(summary.contains("2. Exact [⏸️  Paused] - 15m 0s"));
+    assert!(summary.contains("Total Time: 30m 0s"));
+}
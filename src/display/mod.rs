@@ -1,6 +1,212 @@
-use crate::task::{Task, TaskStatus};
+use crate::settings::Settings;
+use crate::task::{Task, TaskStats, TaskStatus};
+use chrono::{DateTime, Local, NaiveDate, Utc};
+use owo_colors::OwoColorize;
+use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::time::Duration;
 
+/// Keys that task listings can be sorted by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum SortKey {
+    /// Oldest task first
+    Created,
+    /// Highest priority first
+    Priority,
+    /// Shortest total time first
+    Duration,
+    /// Alphabetically by label, ignoring case
+    Label,
+}
+
+/// Attributes that task listings can be grouped by
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum GroupBy {
+    /// One section per project, with tasks without a project last
+    Project,
+}
+
+/// Layouts for `tt list`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ListFormat {
+    /// One short `index. label — duration` line per task, without timestamps or totals
+    Compact,
+    /// Full lines with status and timestamps, followed by totals
+    #[default]
+    Detailed,
+}
+
+/// When statuses are colored
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ColorChoice {
+    /// Only when stdout is a terminal
+    #[default]
+    Auto,
+    /// Always, even when piped
+    Always,
+    /// Never
+    Never,
+}
+
+impl ColorChoice {
+    /// Decides whether to color output written to a terminal (`is_terminal`) or elsewhere
+    pub(crate) fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => is_terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+impl fmt::Display for ColorChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Options controlling how task listings are rendered
+#[derive(Debug, Default, Clone)]
+pub(crate) struct DisplayOptions {
+    /// Show creation times relative to now ("2 hours ago") instead of absolute timestamps
+    pub(crate) relative: bool,
+    /// Order tasks by this key instead of insertion order
+    pub(crate) sort: Option<SortKey>,
+    /// Reverse the order, whether chosen by `sort` or the stored one
+    pub(crate) reverse: bool,
+    /// Split the listing into sections with subtotals
+    pub(crate) group_by: Option<GroupBy>,
+    /// Round each work session up to this many minutes before summing
+    pub(crate) round_sessions: Option<u64>,
+    /// Round each task's total up to this many minutes
+    pub(crate) round: Option<u64>,
+    /// List running and paused tasks ahead of completed ones, unless `sort` is set
+    pub(crate) float_active: bool,
+    /// Show timestamps in UTC instead of the local timezone
+    pub(crate) utc: bool,
+    /// Show statuses as plain text instead of emoji
+    pub(crate) no_emoji: bool,
+    /// Color statuses: green for running, yellow for paused, gray for completed
+    pub(crate) color: bool,
+    /// Layout of each listed task
+    pub(crate) format: ListFormat,
+    /// Stored position of the active task, marked with `*` in listings
+    pub(crate) active: Option<usize>,
+    /// Only show this many of the most recently created tasks; totals still cover them all
+    pub(crate) limit: Option<usize>,
+    /// Shortened stable IDs to show before each label, keyed by full ID; `None` hides them
+    pub(crate) short_ids: Option<HashMap<u64, String>>,
+}
+
+/// Machine-readable view of a task, as printed by `--json`
+///
+/// Kept separate from [`Task`] so the storage format can change without breaking scripts.
+#[derive(Debug, Serialize)]
+pub(crate) struct TaskView {
+    /// Stable ID, unlike the index it doesn't change when other tasks are deleted
+    pub(crate) id: u64,
+    pub(crate) label: String,
+    /// `running`, `paused` or `completed`
+    pub(crate) status: &'static str,
+    pub(crate) total_seconds: u64,
+    /// The same total as an ISO 8601 duration, e.g. `PT1H30M`
+    pub(crate) total_iso8601: String,
+    /// Creation time in RFC 3339 format
+    pub(crate) created_at: String,
+}
+
+impl TaskView {
+    pub(crate) fn new(task: &Task, options: &DisplayOptions) -> Self {
+        let total = task_duration(task, options);
+        Self {
+            id: task.id,
+            label: task.label.clone(),
+            status: task.status.name(),
+            total_seconds: total.as_secs(),
+            total_iso8601: format_duration_iso8601(total),
+            created_at: task.created_at.to_rfc3339(),
+        }
+    }
+}
+
+/// Serializes `value` as indented JSON, or on a single line when `compact` is set
+///
+/// Neither form ends with a newline, so printing either gives exactly one trailing newline.
+pub(crate) fn to_json<T: Serialize + ?Sized>(
+    value: &T,
+    compact: bool,
+) -> Result<String, serde_json::Error> {
+    if compact {
+        serde_json::to_string(value)
+    } else {
+        serde_json::to_string_pretty(value)
+    }
+}
+
+/// Renders the current task as JSON, or `null` when there is no active task
+pub(crate) fn json_current_status(
+    task: Option<&Task>,
+    compact: bool,
+) -> Result<String, serde_json::Error> {
+    let view = task.map(|task| TaskView::new(task, &DisplayOptions::default()));
+    to_json(&view, compact)
+}
+
+/// Renders the given tasks as a JSON array in stored order
+pub(crate) fn json_task_list(
+    tasks: &[(usize, &Task)],
+    options: &DisplayOptions,
+    compact: bool,
+) -> Result<String, serde_json::Error> {
+    let views: Vec<TaskView> = tasks
+        .iter()
+        .map(|(_, task)| TaskView::new(task, options))
+        .collect();
+    to_json(&views, compact)
+}
+
+/// Rounds a duration up to the next multiple of `minutes`
+///
+/// A zero increment leaves the duration unchanged.
+pub(crate) fn round_duration(d: Duration, minutes: u64) -> Duration {
+    let increment = minutes * 60;
+    if increment == 0 {
+        return d;
+    }
+
+    // Any fraction of a second still counts toward the next block
+    let mut seconds = d.as_secs();
+    if d.subsec_nanos() > 0 {
+        seconds += 1;
+    }
+
+    Duration::from_secs(seconds.div_ceil(increment) * increment)
+}
+
+/// Gets the duration to display for a task, applying any rounding options
+pub(crate) fn task_duration(task: &Task, options: &DisplayOptions) -> Duration {
+    let duration = match options.round_sessions {
+        Some(minutes) => task
+            .session_durations()
+            .into_iter()
+            .map(|d| round_duration(d, minutes))
+            .sum(),
+        None => task.total_duration(),
+    };
+
+    match options.round {
+        Some(minutes) => round_duration(duration, minutes),
+        None => duration,
+    }
+}
+
 /// Formats a duration into a human-readable string
 pub(crate) fn format_duration(duration: Duration) -> String {
     let total_seconds = duration.as_secs();
@@ -17,85 +223,951 @@ pub(crate) fn format_duration(duration: Duration) -> String {
     }
 }
 
+/// Formats a duration without spaces or trailing zero units, e.g. "4h10m" or "25m"
+pub(crate) fn format_duration_compact(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+
+    match (hours, minutes) {
+        (0, 0) => format!("{}s", total_seconds),
+        (0, _) => format!("{}m", minutes),
+        (_, 0) => format!("{}h", hours),
+        _ => format!("{}h{}m", hours, minutes),
+    }
+}
+
+/// Formats a duration as an ISO 8601 duration, e.g. "PT1H30M" or "PT45S"
+///
+/// Only hours, minutes and seconds are used, so long totals stay in hours ("PT26H").
+/// Fractions of a second are dropped, and a zero duration is "PT0S".
+pub(crate) fn format_duration_iso8601(duration: Duration) -> String {
+    let total_seconds = duration.as_secs();
+    if total_seconds == 0 {
+        return "PT0S".to_string();
+    }
+
+    let hours = total_seconds / 3600;
+    let minutes = (total_seconds % 3600) / 60;
+    let seconds = total_seconds % 60;
+
+    let mut output = String::from("PT");
+    for (value, unit) in [(hours, 'H'), (minutes, 'M'), (seconds, 'S')] {
+        if value > 0 {
+            output.push_str(&format!("{}{}", value, unit));
+        }
+    }
+    output
+}
+
+/// Formats a completion journal line, e.g. "- 2025-10-30 14:05  Label (1h20m): note"
+///
+/// Line breaks in the note are folded into spaces so each entry stays on one line.
+pub(crate) fn format_journal_entry(task: &Task, note: &str) -> String {
+    let completed_at = task.completed_at.unwrap_or_else(Utc::now);
+    let note = note.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    format!(
+        "- {}  {} ({}): {}",
+        completed_at.format("%Y-%m-%d %H:%M"),
+        task.label,
+        format_duration_compact(task.total_duration()),
+        note
+    )
+}
+
 /// Formats task status with appropriate symbols and colors (if terminal supports it)
-pub(crate) fn format_status(status: &TaskStatus) -> String {
+///
+/// With `no_emoji`, only the plain status name is shown, for terminals that can't render emoji.
+/// With `color`, the text is wrapped in ANSI color codes; the two options are independent.
+pub(crate) fn format_status(status: &TaskStatus, no_emoji: bool, color: bool) -> String {
+    let text = match (status, no_emoji) {
+        (TaskStatus::Running, true) => "Running",
+        (TaskStatus::Paused, true) => "Paused",
+        (TaskStatus::Completed, true) => "Completed",
+        (TaskStatus::Running, false) => "🏃 Running",
+        (TaskStatus::Paused, false) => "⏸️  Paused",
+        (TaskStatus::Completed, false) => "✅ Completed",
+    };
+
+    if !color {
+        return text.to_string();
+    }
+
     match status {
-        TaskStatus::Running => "🏃 Running".to_string(),
-        TaskStatus::Paused => "⏸️  Paused".to_string(),
-        TaskStatus::Completed => "✅ Completed".to_string(),
+        TaskStatus::Running => text.green().to_string(),
+        TaskStatus::Paused => text.yellow().to_string(),
+        TaskStatus::Completed => text.bright_black().to_string(),
+    }
+}
+
+/// Formats a timestamp in the local timezone with its UTC offset, or in UTC when `utc` is set
+///
+/// Tasks are always stored in UTC; this only affects what is shown.
+pub(crate) fn format_timestamp(dt: DateTime<Utc>, utc: bool) -> String {
+    if utc {
+        dt.format("%Y-%m-%d %H:%M:%S UTC").to_string()
+    } else {
+        dt.with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S %Z")
+            .to_string()
     }
 }
 
+/// Formats a timestamp relative to the current time, e.g. "5 minutes ago"
+pub(crate) fn format_relative(dt: DateTime<Utc>) -> String {
+    format_relative_to(dt, Utc::now())
+}
+
+/// Formats `dt` relative to the given reference time `now`
+pub(crate) fn format_relative_to(dt: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    // Timestamps in the future (e.g. after a clock change) are treated as "now"
+    let seconds = now.signed_duration_since(dt).num_seconds().max(0);
+
+    let (amount, unit) = if seconds < 60 {
+        return "just now".to_string();
+    } else if seconds < 3600 {
+        (seconds / 60, "minute")
+    } else if seconds < 86400 {
+        (seconds / 3600, "hour")
+    } else {
+        (seconds / 86400, "day")
+    };
+
+    let plural = if amount == 1 { "" } else { "s" };
+    format!("{} {}{} ago", amount, unit, plural)
+}
+
 /// Displays a single task with formatted information
-pub(crate) fn display_task(task: &Task, index: Option<usize>) -> String {
-    let status = format_status(&task.status);
-    let duration = format_duration(task.total_duration());
-    let created = task.created_at.format("%Y-%m-%d %H:%M:%S UTC");
+pub(crate) fn display_task(task: &Task, index: Option<usize>, options: &DisplayOptions) -> String {
+    let status = format_status(&task.status, options.no_emoji, options.color);
+    let actual = task_duration(task, options);
+    let duration = match task.estimate {
+        Some(estimate) => format!(
+            "{}/{}",
+            format_duration(actual),
+            format_estimate(actual, estimate, options)
+        ),
+        None => format_duration(actual),
+    };
+    // The compact relative form replaces the absolute timestamp entirely
+    let mut created = if options.relative {
+        format_relative(task.created_at)
+    } else {
+        format!(
+            "Created: {}",
+            format_timestamp(task.created_at, options.utc)
+        )
+    };
+    // Tasks completed by older versions have no completion time to show
+    if let Some(completed_at) = task.completed_at.filter(|_| task.is_completed()) {
+        if options.relative {
+            created.push_str(&format!(", completed {}", format_relative(completed_at)));
+        } else {
+            created.push_str(&format!(
+                ", Completed: {}",
+                format_timestamp(completed_at, options.utc)
+            ));
+        }
+    }
 
-    let prefix = if let Some(idx) = index {
+    let mut prefix = if let Some(idx) = index {
         format!("{}. ", idx + 1)
     } else {
         String::new()
     };
+    prefix.push_str(&id_tag(task, options));
 
+    let tags: String = task.tags.iter().map(|tag| format!(" #{}", tag)).collect();
+
+    // Tasks from older versions didn't count their sessions
+    let sessions = match task.session_count {
+        0 => String::new(),
+        1 => " (1 session)".to_string(),
+        count => format!(" ({} sessions)", count),
+    };
+
+    let mut line = format!(
+        "{}{}{} [{}] - {}{} ({})",
+        prefix, task.label, tags, status, duration, sessions, created
+    );
+    if let Some(note) = &task.note {
+        line.push_str(&format!("\n   Note: {}", note));
+    }
+    line
+}
+
+/// Formats an estimate with the share of it used so far, e.g. `2h 0m 0s (75%)`
+///
+/// Tasks past their estimate get an over-budget marker.
+pub(crate) fn format_estimate(
+    actual: Duration,
+    estimate: Duration,
+    options: &DisplayOptions,
+) -> String {
+    let percent = actual.as_secs_f64() / estimate.as_secs_f64().max(1.0) * 100.0;
+    let mut output = format!("{} ({:.0}%)", format_duration(estimate), percent);
+    if actual > estimate {
+        let marker = if options.no_emoji {
+            "[over budget]"
+        } else {
+            "⚠️ over budget"
+        };
+        if options.color {
+            output.push_str(&format!(" {}", marker.red()));
+        } else {
+            output.push_str(&format!(" {}", marker));
+        }
+    }
+    output
+}
+
+/// Draws the progress of `elapsed` toward `goal` as a bar `width` cells wide, e.g.
+/// `[####----] 50%`
+///
+/// Time past the goal shows as a full bar at 100%.
+pub(crate) fn progress_bar(elapsed: Duration, goal: Duration, width: usize) -> String {
+    let fraction = if goal.is_zero() {
+        1.0
+    } else {
+        (elapsed.as_secs_f64() / goal.as_secs_f64()).min(1.0)
+    };
+    // Rounding down keeps the bar from looking finished before the goal is reached
+    let filled = (fraction * width as f64).floor() as usize;
     format!(
-        "{}{} [{}] - {} (Created: {})",
-        prefix, task.label, status, duration, created
+        "[{}{}] {}%",
+        "#".repeat(filled),
+        "-".repeat(width - filled),
+        (fraction * 100.0).floor()
     )
 }
 
-/// Displays current task status
-pub(crate) fn display_current_status(task: Option<&Task>) -> String {
+/// Formats the task's shortened ID as `[id] ` when `list --ids` asked for IDs
+fn id_tag(task: &Task, options: &DisplayOptions) -> String {
+    options
+        .short_ids
+        .as_ref()
+        .and_then(|ids| ids.get(&task.id))
+        .map_or_else(String::new, |id| format!("[{}] ", id))
+}
+
+/// Displays a task as a single `index. label — duration` line, for `list --format compact`
+///
+/// `index` is the task's 0-based stored position.
+pub(crate) fn display_task_compact(task: &Task, index: usize, options: &DisplayOptions) -> String {
+    format!(
+        "{}. {}{} — {}",
+        index + 1,
+        id_tag(task, options),
+        task.label,
+        format_duration(task_duration(task, options))
+    )
+}
+
+/// Warns about running tasks whose live session is longer than `threshold`
+///
+/// A session that long usually means the timer was left on, e.g. overnight.
+pub(crate) fn idle_warnings(tasks: &[Task], threshold: Duration) -> Vec<String> {
+    tasks
+        .iter()
+        .filter(|task| task.current_session_duration() > threshold)
+        .map(|task| {
+            format!(
+                "Warning: '{}' has been running for {} without a break; was the timer left on?",
+                task.label,
+                format_duration(task.current_session_duration())
+            )
+        })
+        .collect()
+}
+
+/// Shortens a label to at most `max` characters, marking the cut with an ellipsis
+///
+/// Counts Unicode scalar values rather than bytes, so multi-byte characters are never split.
+pub(crate) fn truncate_label(label: &str, max: usize) -> String {
+    if label.chars().count() <= max {
+        return label.to_string();
+    }
+
+    let mut truncated: String = label.chars().take(max).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Number of cells in the goal progress bar shown by `status`
+const GOAL_BAR_WIDTH: usize = 10;
+
+/// Displays current task status, optionally truncating the label to `label_max` characters
+///
+/// A note on the task, e.g. one left when pausing, follows on a second line. The session start
+/// is shown in UTC when `utc` is set.
+pub(crate) fn display_current_status(
+    task: Option<&Task>,
+    label_max: Option<usize>,
+    no_emoji: bool,
+    color: bool,
+    utc: bool,
+) -> String {
     match task {
         Some(task) => {
-            let status = format_status(&task.status);
+            let status = format_status(&task.status, no_emoji, color);
             let duration = format_duration(task.total_duration());
+            let label = match label_max {
+                Some(max) => truncate_label(&task.label, max),
+                None => task.label.clone(),
+            };
 
-            format!("Current Task: {} [{}] - {}", task.label, status, duration)
+            let mut line = format!("Current Task: {} [{}] - {}", label, status, duration);
+            // Paused and completed tasks have no live session to date
+            if let (true, Some(started_at)) = (task.is_running(), task.started_at) {
+                line.push_str(&format!(
+                    " (started at {})",
+                    format_session_start(started_at, Local::now(), utc)
+                ));
+            }
+            if let (true, Some(goal)) = (task.is_running(), task.goal) {
+                line.push(' ');
+                line.push_str(&progress_bar(task.total_duration(), goal, GOAL_BAR_WIDTH));
+            }
+            if let Some(note) = &task.note {
+                line.push_str(&format!("\n  Note: {}", note));
+            }
+            line
         },
         None => "No active task".to_string(),
     }
 }
 
+/// Formats when a session started as a local wall-clock time, e.g. `14:32`
+///
+/// Sessions that began before today (relative to `now`) also show the date. With `utc` set the
+/// time is given in UTC, e.g. `05:32 UTC`, and "today" is the current UTC date.
+pub(crate) fn format_session_start(
+    started_at: DateTime<Utc>,
+    now: DateTime<Local>,
+    utc: bool,
+) -> String {
+    if utc {
+        let format = if started_at.date_naive() == now.with_timezone(&Utc).date_naive() {
+            "%H:%M UTC"
+        } else {
+            "%Y-%m-%d %H:%M UTC"
+        };
+        return started_at.format(format).to_string();
+    }
+    let local = started_at.with_timezone(&Local);
+    if local.date_naive() == now.date_naive() {
+        local.format("%H:%M").to_string()
+    } else {
+        local.format("%Y-%m-%d %H:%M").to_string()
+    }
+}
+
+/// Formats the current task as `label|status|total_seconds` for shell prompts
+///
+/// The format never changes with cosmetic options: the status is the lowercase name, without
+/// emoji or color. Backslashes, pipes, tabs and line breaks in the label are escaped (`\\`,
+/// `\|`, `\t`, `\n`) so the line always splits into three fields. With no active task the
+/// line is empty.
+pub(crate) fn display_current_status_porcelain(task: Option<&Task>) -> String {
+    match task {
+        Some(task) => format!(
+            "{}|{}|{}",
+            escape_field(&task.label).replace('|', "\\|"),
+            task.status.name(),
+            task.total_duration().as_secs()
+        ),
+        None => String::new(),
+    }
+}
+
+/// Condenses all tasks into a single line, e.g. for a status bar
+///
+/// `active` is the current task, whose label and time are shown next to the running count.
+pub(crate) fn display_one_line_summary(stats: &TaskStats, active: Option<&Task>) -> String {
+    if stats.total_tasks == 0 {
+        return "No tasks".to_string();
+    }
+
+    let mut running = format!("{} running", stats.running);
+    if let Some(task) = active.filter(|task| task.is_running()) {
+        running.push_str(&format!(
+            " ({} {})",
+            task.label,
+            format_duration_compact(task.total_duration())
+        ));
+    }
+
+    format!(
+        "{} tasks · {} · {} paused · {} completed · total {}",
+        stats.total_tasks,
+        running,
+        stats.paused,
+        stats.completed,
+        format_duration_compact(stats.total_duration)
+    )
+}
+
+/// Displays a detailed multi-line view of a single task
+///
+/// Only the `utc`, `no_emoji` and `color` options apply.
+pub(crate) fn display_task_info(task: &Task, index: usize, options: &DisplayOptions) -> String {
+    let mut output = String::new();
+    output.push_str(&format!("Task {}: {}\n", index, task.label));
+    output.push_str(&format!("  ID:       {}\n", task.id));
+    output.push_str(&format!(
+        "  Status:   {}\n",
+        format_status(&task.status, options.no_emoji, options.color)
+    ));
+    output.push_str(&format!(
+        "  Created:  {}\n",
+        format_timestamp(task.created_at, options.utc)
+    ));
+    output.push_str(&format!(
+        "  Duration: {}\n",
+        format_duration(task.total_duration())
+    ));
+    output.push_str(&format!("  Priority: {}\n", task.priority));
+    if let Some(goal) = task.goal {
+        let reached = if task.total_duration() >= goal {
+            " (reached)"
+        } else {
+            ""
+        };
+        output.push_str(&format!(
+            "  Goal:     {}{}\n",
+            format_duration(goal),
+            reached
+        ));
+    }
+    if let Some(estimate) = task.estimate {
+        output.push_str(&format!(
+            "  Estimate: {}\n",
+            format_estimate(task.total_duration(), estimate, options)
+        ));
+    }
+    if let Some(project) = &task.project {
+        output.push_str(&format!("  Project:  {}\n", project));
+    }
+    if !task.tags.is_empty() {
+        let tags: Vec<String> = task.tags.iter().map(|tag| format!("#{}", tag)).collect();
+        output.push_str(&format!("  Tags:     {}\n", tags.join(" ")));
+    }
+    if let Some(note) = &task.note {
+        output.push_str(&format!("  Note:     {}\n", note));
+    }
+    // Tasks from older versions didn't count their sessions
+    if task.session_count > 0 {
+        output.push_str(&format!(
+            "  Sessions: {} ({} recorded)",
+            task.session_count,
+            task.sessions.len()
+        ));
+    } else {
+        output.push_str(&format!("  Sessions: {} recorded", task.sessions.len()));
+    }
+
+    output
+}
+
+/// Explains how a task's total duration is computed from its components
+pub(crate) fn display_duration_explanation(task: &Task, utc: bool) -> String {
+    let total = task.total_duration();
+    let accumulated = task.accumulated_duration;
+    // Work in whole seconds so the displayed components always add up exactly
+    let live = Duration::from_secs(total.as_secs().saturating_sub(accumulated.as_secs()));
+
+    let mut output = String::new();
+    output.push_str("Duration breakdown:\n");
+    output.push_str(&format!(
+        "  Accumulated (previous sessions): {}s ({})\n",
+        accumulated.as_secs(),
+        format_duration(accumulated)
+    ));
+    for session in &task.sessions {
+        if let Some(skew) = session.clock_skew {
+            output.push_str(&format!(
+                "    (session started {} counted as 0s: the clock moved back {})\n",
+                format_timestamp(session.started_at, utc),
+                format_duration(skew)
+            ));
+        }
+    }
+
+    match (task.is_running(), task.started_at) {
+        (true, Some(started_at)) => {
+            output.push_str(&format!(
+                "  + Live session (started {}): {}s ({})\n",
+                format_timestamp(started_at, utc),
+                live.as_secs(),
+                format_duration(live)
+            ));
+            if started_at > Utc::now() {
+                output.push_str("    (counted as 0s: the clock is behind the session's start)\n");
+            }
+        },
+        _ => output.push_str("  + Live session: none (task is not running)\n"),
+    }
+
+    output.push_str(&format!(
+        "  = Total: {}s ({})",
+        total.as_secs(),
+        format_duration(total)
+    ));
+
+    output
+}
+
+/// Lists stale paused tasks with how long ago each was last worked on
+pub(crate) fn display_stale_tasks(tasks: &[(usize, &Task)]) -> String {
+    if tasks.is_empty() {
+        return "No stale paused tasks".to_string();
+    }
+
+    let mut output = String::from("Stale paused tasks (resume or complete them):");
+    for (index, task) in tasks {
+        output.push_str(&format!(
+            "\n{}. {} - last active {} ({} tracked)",
+            index + 1,
+            task.label,
+            format_relative(task.last_activity()),
+            format_duration(task.total_duration())
+        ));
+    }
+
+    output
+}
+
+/// Lists every effective setting along with the source of its value
+pub(crate) fn display_settings(settings: &Settings) -> String {
+    let rows = [
+        (
+            "store_path",
+            settings.store_path.value.display().to_string(),
+            settings.store_path.source,
+        ),
+        (
+            "default_command",
+            settings.default_command.value.clone(),
+            settings.default_command.source,
+        ),
+        (
+            "strict_labels",
+            settings.strict_labels.value.to_string(),
+            settings.strict_labels.source,
+        ),
+        (
+            "max_tasks",
+            settings.max_tasks.value.to_string(),
+            settings.max_tasks.source,
+        ),
+        (
+            "retain_days",
+            settings.retain_days.value.to_string(),
+            settings.retain_days.source,
+        ),
+        (
+            "stale_after_days",
+            settings
+                .stale_after_days
+                .value
+                .map_or_else(|| "off".to_string(), |days| days.to_string()),
+            settings.stale_after_days.source,
+        ),
+        (
+            "stale_policy",
+            settings.stale_policy.value.to_string(),
+            settings.stale_policy.source,
+        ),
+        (
+            "confirm_long_complete",
+            settings
+                .confirm_long_complete
+                .value
+                .map_or_else(|| "off".to_string(), format_duration),
+            settings.confirm_long_complete.source,
+        ),
+        (
+            "save_retries",
+            settings.save_retries.value.to_string(),
+            settings.save_retries.source,
+        ),
+        (
+            "float_active",
+            settings.float_active.value.to_string(),
+            settings.float_active.source,
+        ),
+        (
+            "idle_warn_hours",
+            match settings.idle_warn_hours.value {
+                0 => "off".to_string(),
+                hours => hours.to_string(),
+            },
+            settings.idle_warn_hours.source,
+        ),
+        (
+            "json",
+            settings.json.value.to_string(),
+            settings.json.source,
+        ),
+        (
+            "json_compact",
+            settings.json_compact.value.to_string(),
+            settings.json_compact.source,
+        ),
+        (
+            "no_emoji",
+            settings.no_emoji.value.to_string(),
+            settings.no_emoji.source,
+        ),
+        (
+            "color",
+            settings.color.value.to_string(),
+            settings.color.source,
+        ),
+        (
+            "round_minutes",
+            settings
+                .round_minutes
+                .value
+                .map_or_else(|| "off".to_string(), |minutes| minutes.to_string()),
+            settings.round_minutes.source,
+        ),
+        ("utc", settings.utc.value.to_string(), settings.utc.source),
+        (
+            // Never print the passphrase itself
+            "encryption",
+            if settings.encrypt_key.value.is_some() {
+                "on"
+            } else {
+                "off"
+            }
+            .to_string(),
+            settings.encrypt_key.source,
+        ),
+    ];
+
+    let mut output = String::from("Effective settings:");
+    for (name, value, source) in rows {
+        output.push_str(&format!("\n  {:<21} = {} ({})", name, value, source));
+    }
+
+    output
+}
+
 /// Creates a summary of all tasks
-pub(crate) fn display_task_summary(tasks: &[Task]) -> String {
+pub(crate) fn display_task_summary(tasks: &[Task], options: &DisplayOptions) -> String {
     if tasks.is_empty() {
         return "No tasks found".to_string();
     }
 
-    let mut output = String::new();
-    output.push_str(&format!("Task Summary ({} tasks):\n", tasks.len()));
-    output.push_str(&"=".repeat(40));
-    output.push('\n');
+    // Keep each task's original position so displayed indices still match delete/rename
+    render_summary(tasks.iter().enumerate().collect(), options)
+}
+
+/// Lists tasks one per line as `index<TAB>label<TAB>status<TAB>seconds`, for piping to scripts
+///
+/// Each task is paired with its 0-based stored position, so the printed index always matches
+/// delete/rename. There is no header or footer, and no line for an empty task list. Tabs,
+/// newlines and backslashes in labels are escaped (`\t`, `\n`, `\\`) so each task stays on
+/// one line with exactly four fields.
+pub(crate) fn display_task_summary_plain(tasks: &[(usize, &Task)]) -> String {
+    tasks
+        .iter()
+        .map(|(index, task)| {
+            format!(
+                "{}\t{}\t{}\t{}",
+                index + 1,
+                escape_field(&task.label),
+                task.status.name(),
+                task.total_duration().as_secs()
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escapes characters that would break a tab-separated line
+fn escape_field(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Creates a summary of a subset of tasks, each paired with its 0-based stored position
+///
+/// The totals in the footer cover only the given tasks.
+pub(crate) fn display_filtered_task_summary(
+    tasks: &[(usize, &Task)],
+    options: &DisplayOptions,
+) -> String {
+    if tasks.is_empty() {
+        return "No matching tasks found".to_string();
+    }
 
-    for (index, task) in tasks.iter().enumerate() {
-        output.push_str(&display_task(task, Some(index)));
-        output.push('\n');
+    render_summary(tasks.to_vec(), options)
+}
+
+/// Creates a summary of the tasks worked on during `today`, each paired with its 0-based
+/// stored position
+///
+/// Tasks still running from an earlier day are listed with their whole time, which a note
+/// below the totals points out.
+pub(crate) fn display_today_summary(
+    tasks: &[(usize, &Task)],
+    today: NaiveDate,
+    options: &DisplayOptions,
+) -> String {
+    if tasks.is_empty() {
+        return "No tasks worked on today".to_string();
+    }
+
+    let mut output = render_summary(tasks.to_vec(), options);
+    let notes: Vec<String> = tasks
+        .iter()
+        .filter(|(_, task)| task.running_since_before(today))
+        .map(|(_, task)| {
+            format!(
+                "Note: '{}' has been running since before today; its time includes earlier days",
+                task.label
+            )
+        })
+        .collect();
+    if !notes.is_empty() {
+        output.push_str("\n\n");
+        output.push_str(&notes.join("\n"));
+    }
+    output
+}
+
+/// Shows the figures as the totals block at the bottom of a listing
+impl fmt::Display for TaskStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Total Time: {}", format_duration(self.total_duration))?;
+        write!(
+            f,
+            "Running: {} | Paused: {} | Completed: {}",
+            self.running, self.paused, self.completed
+        )
     }
+}
+
+/// Adds up time and counts tasks per status, rounding as `options` asks
+pub(crate) fn compute_totals<'a>(
+    tasks: impl IntoIterator<Item = &'a Task>,
+    options: &DisplayOptions,
+) -> TaskStats {
+    TaskStats::collect(tasks, |task| task_duration(task, options))
+}
 
-    // Calculate totals
-    let total_duration: Duration = tasks.iter().map(|t| t.total_duration()).sum();
+/// Shows only the totals block of a listing, without the tasks themselves
+pub(crate) fn display_totals(tasks: &[Task], options: &DisplayOptions) -> String {
+    if tasks.is_empty() {
+        return "No tasks found".to_string();
+    }
 
-    let running_count = tasks.iter().filter(|t| t.is_running()).count();
+    compute_totals(tasks, options).to_string()
+}
 
-    let paused_count = tasks.iter().filter(|t| t.is_paused()).count();
+/// Renders the task listing shared by the full and filtered summaries
+fn render_summary(mut entries: Vec<TaskEntry<'_>>, options: &DisplayOptions) -> String {
+    match options.sort {
+        Some(key) => sort_entries(&mut entries, key, options),
+        None if options.reverse => entries.reverse(),
+        None => {},
+    }
+    // An explicit sort key decides the whole order, so active tasks only float without one
+    if options.float_active && options.sort.is_none() {
+        // Stable, so each half keeps the order chosen above
+        entries.sort_by_key(|(_, task)| task.is_completed());
+    }
+
+    let shown = most_recent(&entries, options.limit);
+
+    if options.format == ListFormat::Compact {
+        return shown
+            .iter()
+            .map(|(index, task)| {
+                mark_active(
+                    display_task_compact(task, *index, options),
+                    *index,
+                    task,
+                    options,
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+    }
+
+    let mut output = String::new();
+    if shown.len() < entries.len() {
+        output.push_str(&format!(
+            "Task Summary (showing {} of {} tasks):\n",
+            shown.len(),
+            entries.len()
+        ));
+    } else {
+        output.push_str(&format!("Task Summary ({} tasks):\n", entries.len()));
+    }
+    output.push_str(&"=".repeat(40));
+    output.push('\n');
+
+    match options.group_by {
+        Some(GroupBy::Project) => {
+            for (project, group) in group_by_project(&shown) {
+                output.push_str(&format!("Project: {}\n", project.unwrap_or("(no project)")));
+                for (index, task) in &group {
+                    output.push_str(&mark_active(
+                        display_task(task, Some(*index), options),
+                        *index,
+                        task,
+                        options,
+                    ));
+                    output.push('\n');
+                }
+                let subtotal: Duration = group.iter().map(|(_, t)| task_duration(t, options)).sum();
+                output.push_str(&format!("  Subtotal: {}\n\n", format_duration(subtotal)));
+            }
+        },
+        None => {
+            for (index, task) in &shown {
+                output.push_str(&mark_active(
+                    display_task(task, Some(*index), options),
+                    *index,
+                    task,
+                    options,
+                ));
+                output.push('\n');
+            }
+        },
+    }
 
-    let completed_count = tasks.iter().filter(|t| t.is_completed()).count();
+    let totals = compute_totals(entries.iter().map(|(_, task)| *task), options);
 
     output.push('\n');
     output.push_str(&"=".repeat(40));
     output.push('\n');
-    output.push_str(&format!(
-        "Total Time: {}\n",
-        format_duration(total_duration)
-    ));
-    output.push_str(&format!(
-        "Running: {} | Paused: {} | Completed: {}",
-        running_count, paused_count, completed_count
-    ));
+    output.push_str(&totals.to_string());
+
+    // Listings without any tags stay as they were
+    if entries.iter().any(|(_, task)| !task.tags.is_empty()) {
+        output.push_str("\n\nTag Totals:");
+        for (tag, total) in tag_totals(&entries, options) {
+            let name = tag.map_or_else(|| "(untagged)".to_string(), |tag| format!("#{}", tag));
+            output.push_str(&format!("\n  {}: {}", name, format_duration(total)));
+        }
+    }
 
     output
 }
 
+/// Keeps the `limit` most recently created entries, in their current order
+///
+/// Without a limit, or with one at least the number of entries, every entry is kept.
+pub(crate) fn most_recent<'a>(
+    entries: &[TaskEntry<'a>],
+    limit: Option<usize>,
+) -> Vec<TaskEntry<'a>> {
+    let Some(limit) = limit.filter(|&limit| limit < entries.len()) else {
+        return entries.to_vec();
+    };
+
+    let mut newest_first: Vec<usize> = (0..entries.len()).collect();
+    newest_first.sort_by_key(|&position| Reverse(entries[position].1.created_at));
+    let mut keep = vec![false; entries.len()];
+    for &position in &newest_first[..limit] {
+        keep[position] = true;
+    }
+
+    entries
+        .iter()
+        .zip(keep)
+        .filter_map(|(entry, keep)| keep.then_some(*entry))
+        .collect()
+}
+
+/// Prefixes the active task's line with `* `
+///
+/// Completed tasks are never active, so they're never marked even if the index matches.
+fn mark_active(line: String, index: usize, task: &Task, options: &DisplayOptions) -> String {
+    if options.active == Some(index) && !task.is_completed() {
+        format!("* {}", line)
+    } else {
+        line
+    }
+}
+
+/// Sums task time per tag, ordered by tag name with untagged tasks last
+///
+/// A task with several tags counts in full toward each of them.
+fn tag_totals<'a>(
+    entries: &[TaskEntry<'a>],
+    options: &DisplayOptions,
+) -> Vec<(Option<&'a str>, Duration)> {
+    let mut totals: BTreeMap<&str, Duration> = BTreeMap::new();
+    let mut untagged = None;
+
+    for (_, task) in entries {
+        let duration = task_duration(task, options);
+        if task.tags.is_empty() {
+            *untagged.get_or_insert(Duration::ZERO) += duration;
+        }
+        for tag in &task.tags {
+            *totals.entry(tag.as_str()).or_default() += duration;
+        }
+    }
+
+    totals
+        .into_iter()
+        .map(|(tag, total)| (Some(tag), total))
+        .chain(untagged.map(|total| (None, total)))
+        .collect()
+}
+
+/// Sorts `(index, task)` entries by the given key, keeping insertion order for ties
+///
+/// Only the entries are reordered; the stored tasks and their indices are untouched. With
+/// `options.reverse`, the order is flipped but ties still keep insertion order.
+fn sort_entries(entries: &mut [TaskEntry<'_>], key: SortKey, options: &DisplayOptions) {
+    entries.sort_by(|(_, a), (_, b)| {
+        let ordering = match key {
+            SortKey::Created => a.created_at.cmp(&b.created_at),
+            SortKey::Priority => Reverse(a.priority).cmp(&Reverse(b.priority)),
+            SortKey::Duration => task_duration(a, options).cmp(&task_duration(b, options)),
+            SortKey::Label => a.label.to_lowercase().cmp(&b.label.to_lowercase()),
+        };
+        if options.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    });
+}
+
+/// A task paired with its position in the stored task list
+type TaskEntry<'a> = (usize, &'a Task);
+
+/// Splits entries into per-project groups, ordered by project name with unassigned tasks last
+///
+/// The relative order of entries inside each group is preserved.
+fn group_by_project<'a>(entries: &[TaskEntry<'a>]) -> Vec<(Option<&'a str>, Vec<TaskEntry<'a>>)> {
+    let mut groups: Vec<(Option<&'a str>, Vec<TaskEntry<'a>>)> = Vec::new();
+
+    for &(index, task) in entries {
+        let project = task.project.as_deref();
+        match groups.iter_mut().find(|(name, _)| *name == project) {
+            Some((_, group)) => group.push((index, task)),
+            None => groups.push((project, vec![(index, task)])),
+        }
+    }
+
+    // Unassigned tasks go last, named projects alphabetically
+    groups.sort_by_key(|(name, _)| (name.is_none(), *name));
+
+    groups
+}
+
 #[cfg(test)]
 mod display_tests;
@@ -0,0 +1,80 @@
+use super::*;
+use chrono::TimeZone;
+
+#[test]
+fn test_parse_duration_single_units() {
+    assert_eq!(parse_duration("45s").unwrap(), Duration::from_secs(45));
+    assert_eq!(parse_duration("90m").unwrap(), Duration::from_secs(90 * 60));
+    assert_eq!(parse_duration("2h").unwrap(), Duration::from_secs(2 * 3600));
+    assert_eq!(parse_duration("1d").unwrap(), Duration::from_secs(86400));
+}
+
+#[test]
+fn test_parse_duration_combined_units() {
+    assert_eq!(parse_duration("1h30m").unwrap(), Duration::from_secs(5400));
+    assert_eq!(
+        parse_duration(" 1h 30m 15s ").unwrap(),
+        Duration::from_secs(5415)
+    );
+}
+
+#[test]
+fn test_parse_duration_rejects_invalid_input() {
+    for input in ["", "   ", "1x", "h", "30", "1h30", "-5m"] {
+        let err = parse_duration(input).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid duration"),
+            "unexpected error for {:?}: {}",
+            input,
+            err
+        );
+    }
+}
+
+#[test]
+fn test_parse_duration_rejects_overflow() {
+    // Too many digits for a u64
+    let err = parse_duration("99999999999999999999s").unwrap_err();
+    assert!(err.to_string().contains("number is too large"));
+
+    // Fits as a number, but not once converted to seconds
+    let err = parse_duration(&format!("{}d", u64::MAX / 1000)).unwrap_err();
+    assert!(err.to_string().contains("duration is too large"));
+
+    // Each component fits, but their sum doesn't
+    let err = parse_duration(&format!("{}s {}s", u64::MAX, 1)).unwrap_err();
+    assert!(err.to_string().contains("duration is too large"));
+
+    assert_eq!(
+        parse_duration(&format!("{}s", u64::MAX)).unwrap(),
+        Duration::from_secs(u64::MAX)
+    );
+}
+
+#[test]
+fn test_parse_time_offset() {
+    let now = Utc.with_ymd_and_hms(2025, 10, 30, 14, 0, 0).unwrap();
+
+    assert_eq!(
+        parse_time("-20m", now).unwrap(),
+        Utc.with_ymd_and_hms(2025, 10, 30, 13, 40, 0).unwrap()
+    );
+    assert_eq!(
+        parse_time("-1h30m", now).unwrap(),
+        Utc.with_ymd_and_hms(2025, 10, 30, 12, 30, 0).unwrap()
+    );
+    assert!(parse_time("-", now).is_err());
+    assert!(parse_time("-20x", now).is_err());
+}
+
+#[test]
+fn test_parse_time_rfc3339() {
+    let now = Utc::now();
+
+    assert_eq!(
+        parse_time("2025-10-30T09:15:00+02:00", now).unwrap(),
+        Utc.with_ymd_and_hms(2025, 10, 30, 7, 15, 0).unwrap()
+    );
+    assert!(parse_time("20m", now).is_err());
+    assert!(parse_time("yesterday", now).is_err());
+}
@@ -0,0 +1,77 @@
+use crate::task::TaskError;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+
+/// Parses a human-readable duration such as `45s`, `90m`, `2h` or `1h30m`
+///
+/// A duration is one or more `<number><unit>` components, where the unit is `d`, `h`, `m`
+/// or `s`. Whitespace between components is allowed.
+pub(crate) fn parse_duration(input: &str) -> Result<Duration, TaskError> {
+    let invalid = |reason: &str| TaskError::InvalidState {
+        message: format!("Invalid duration '{}': {}", input, reason),
+    };
+
+    let mut rest = input.trim();
+    if rest.is_empty() {
+        return Err(invalid("expected a value like 1h30m"));
+    }
+
+    let mut total_seconds: u64 = 0;
+    while !rest.is_empty() {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            return Err(invalid("expected a number"));
+        }
+
+        let amount: u64 = rest[..digits]
+            .parse()
+            .map_err(|_| invalid("number is too large"))?;
+        let unit = rest[digits..]
+            .chars()
+            .next()
+            .ok_or_else(|| invalid("missing unit"))?;
+        let seconds_per_unit = match unit {
+            'd' => 24 * 60 * 60,
+            'h' => 60 * 60,
+            'm' => 60,
+            's' => 1,
+            other => return Err(invalid(&format!("unknown unit '{}'", other))),
+        };
+
+        total_seconds = amount
+            .checked_mul(seconds_per_unit)
+            .and_then(|seconds| total_seconds.checked_add(seconds))
+            .ok_or_else(|| invalid("duration is too large"))?;
+        rest = rest[digits + unit.len_utf8()..].trim_start();
+    }
+
+    Ok(Duration::from_secs(total_seconds))
+}
+
+/// Parses a point in time given as an RFC 3339 timestamp or as an offset before `now`
+///
+/// Offsets are a `-` followed by a duration, e.g. `-20m` for twenty minutes ago.
+pub(crate) fn parse_time(input: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, TaskError> {
+    let input = input.trim();
+    if let Some(offset) = input.strip_prefix('-') {
+        let offset = parse_duration(offset)?;
+        return chrono::Duration::from_std(offset)
+            .ok()
+            .and_then(|offset| now.checked_sub_signed(offset))
+            .ok_or_else(|| TaskError::InvalidState {
+                message: format!("Invalid time '{}': offset is too large", input),
+            });
+    }
+
+    DateTime::parse_from_rfc3339(input)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|_| TaskError::InvalidState {
+            message: format!(
+                "Invalid time '{}': expected an RFC 3339 timestamp or an offset like -20m",
+                input
+            ),
+        })
+}
+
+#[cfg(test)]
+mod duration_tests;
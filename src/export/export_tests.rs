@@ -0,0 +1,212 @@
+use super::*;
+use crate::task::{DateRange, WorkSession};
+use chrono::{NaiveDate, TimeZone, Utc};
+use std::time::Duration;
+
+fn task_created_on(label: &str, day: u32) -> Task {
+    let mut task = Task::new(label.to_string(), Vec::new());
+    task.complete().unwrap();
+    task.created_at = Utc.with_ymd_and_hms(2024, 6, day, 9, 0, 0).unwrap();
+    task
+}
+
+#[test]
+fn test_export_csv_header_and_rows() {
+    let mut task = task_created_on("Write report", 3);
+    task.project = Some("Docs".to_string());
+
+    let output = export_tasks(
+        &[(0, &task)],
+        ExportFormat::Csv,
+        WorklogDate::Created,
+        None,
+        false,
+    )
+    .unwrap();
+    let lines: Vec<&str> = output.lines().collect();
+
+    assert_eq!(
+        lines[0],
+        "index,label,project,status,created_at,duration_seconds"
+    );
+    assert_eq!(
+        lines[1],
+        "1,Write report,Docs,completed,2024-06-03T09:00:00+00:00,0"
+    );
+}
+
+#[test]
+fn test_export_csv_quotes_special_characters() {
+    let task = task_created_on("Fix \"login\", again", 3);
+
+    let output = export_tasks(
+        &[(4, &task)],
+        ExportFormat::Csv,
+        WorklogDate::Created,
+        None,
+        false,
+    )
+    .unwrap();
+
+    assert!(output.contains("5,\"Fix \"\"login\"\", again\",,completed"));
+}
+
+#[test]
+fn test_export_csv_date_range_filter() {
+    // Two weeks of tasks, one every other day
+    let tasks: Vec<Task> = (1..=14)
+        .step_by(2)
+        .map(|day| task_created_on(&format!("Task on day {}", day), day))
+        .collect();
+    let range = DateRange::new(
+        NaiveDate::from_ymd_opt(2024, 6, 1),
+        NaiveDate::from_ymd_opt(2024, 6, 7),
+    )
+    .unwrap();
+
+    let entries: Vec<(usize, &Task)> = tasks
+        .iter()
+        .enumerate()
+        .filter(|(_, task)| range.contains(task.created_at))
+        .collect();
+    let output = export_tasks(
+        &entries,
+        ExportFormat::Csv,
+        WorklogDate::Created,
+        None,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(output.lines().count(), 5);
+    for day in [1, 3, 5, 7] {
+        assert!(output.contains(&format!("Task on day {},", day)));
+    }
+    for day in [9, 11, 13] {
+        assert!(!output.contains(&format!("Task on day {},", day)));
+    }
+}
+
+#[test]
+fn test_export_worklog_groups_by_day() {
+    let mut first = task_created_on("Write report", 4);
+    first.project = Some("Docs".to_string());
+    first.accumulated_duration = Duration::from_secs(5400);
+    let second = task_created_on("Review PR", 3);
+    let mut third = task_created_on("Fix build", 4);
+    third.note = Some("Pinned the toolchain".to_string());
+
+    let output = export_tasks(
+        &[(0, &first), (1, &second), (2, &third)],
+        ExportFormat::Worklog,
+        WorklogDate::Created,
+        None,
+        false,
+    )
+    .unwrap();
+
+    assert_eq!(
+        output,
+        "## 2024-06-03\n\n- Review PR (0s)\n\n\
+         ## 2024-06-04\n\n- Write report (1h 30m 0s) [Docs]\n- Fix build (0s)\n  Pinned the toolchain\n"
+    );
+}
+
+#[test]
+fn test_export_worklog_by_completed_date() {
+    let mut finished = task_created_on("Finished", 3);
+    finished.completed_at = Some(Utc.with_ymd_and_hms(2024, 6, 10, 17, 0, 0).unwrap());
+    let unfinished = Task::new("Unfinished".to_string(), Vec::new());
+
+    let output = export_tasks(
+        &[(0, &finished), (1, &unfinished)],
+        ExportFormat::Worklog,
+        WorklogDate::Completed,
+        None,
+        false,
+    )
+    .unwrap();
+
+    assert!(output.starts_with("## 2024-06-10\n\n- Finished"));
+    assert!(!output.contains("Unfinished"));
+
+    let output = export_tasks(
+        &[(1, &unfinished)],
+        ExportFormat::Worklog,
+        WorklogDate::Completed,
+        None,
+        false,
+    )
+    .unwrap();
+    assert_eq!(output, "No tasks to log\n");
+}
+
+#[test]
+fn test_export_worklog_groups_by_local_day() {
+    let mut task = task_created_on("Late night", 3);
+    // 01:30 UTC on the 4th is still the evening of the 3rd in UTC-5
+    task.created_at = Utc.with_ymd_and_hms(2024, 6, 4, 1, 30, 0).unwrap();
+    let tz = chrono::FixedOffset::west_opt(5 * 60 * 60).unwrap();
+
+    let output = export_worklog_in(
+        &[(0, &task)],
+        WorklogDate::Created,
+        &DisplayOptions::default(),
+        &tz,
+    );
+    assert!(output.starts_with("## 2024-06-03\n\n- Late night"));
+}
+
+#[test]
+fn test_export_rounds_each_session_up() {
+    let mut task = task_created_on("Billable", 3);
+    task.sessions = (0..2)
+        .map(|hour| WorkSession {
+            started_at: Utc.with_ymd_and_hms(2024, 6, 3, 9 + hour, 0, 0).unwrap(),
+            duration: Duration::from_secs(7 * 60),
+            clock_skew: None,
+        })
+        .collect();
+    task.accumulated_duration = Duration::from_secs(14 * 60);
+
+    let csv = export_tasks(
+        &[(0, &task)],
+        ExportFormat::Csv,
+        WorklogDate::Created,
+        Some(15),
+        false,
+    )
+    .unwrap();
+    assert!(csv.lines().nth(1).unwrap().ends_with(",1800"));
+
+    let worklog = export_tasks(
+        &[(0, &task)],
+        ExportFormat::Worklog,
+        WorklogDate::Created,
+        Some(15),
+        false,
+    )
+    .unwrap();
+    assert!(worklog.contains("- Billable (30m 0s)"));
+}
+
+#[test]
+fn test_export_json_reads_back_as_tasks() {
+    let mut task = task_created_on("Portable", 3);
+    task.project = Some("Docs".to_string());
+
+    let output = export_tasks(
+        &[(2, &task)],
+        ExportFormat::Json,
+        WorklogDate::Created,
+        None,
+        false,
+    )
+    .unwrap();
+    let tasks: Vec<Task> = serde_json::from_str(&output).unwrap();
+
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(tasks[0].label, "Portable");
+    assert_eq!(tasks[0].project.as_deref(), Some("Docs"));
+    assert_eq!(tasks[0].created_at, task.created_at);
+}
@@ -0,0 +1,147 @@
+use crate::display::{format_duration, task_duration, to_json, DisplayOptions};
+use crate::task::Task;
+use chrono::{Local, NaiveDate, TimeZone};
+
+/// Output formats supported by `tt export`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ExportFormat {
+    /// Comma-separated values with a header row
+    Csv,
+    /// Markdown journal with one section per day
+    Worklog,
+    /// JSON array of tasks that `tt import` reads back without loss
+    Json,
+}
+
+/// Which date a task is filed under in a worklog
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum WorklogDate {
+    /// The day the task was created
+    #[default]
+    Created,
+    /// The day the task was completed; unfinished tasks are left out
+    Completed,
+}
+
+/// Renders tasks in the given format
+///
+/// Each task is paired with its 0-based position in the stored task list. With
+/// `round_sessions`, CSV and worklog durations round each session up to that many minutes
+/// before summing; JSON keeps the stored times. `compact` puts JSON on a single line and
+/// doesn't affect the other formats.
+pub(crate) fn export_tasks(
+    tasks: &[(usize, &Task)],
+    format: ExportFormat,
+    by: WorklogDate,
+    round_sessions: Option<u64>,
+    compact: bool,
+) -> Result<String, serde_json::Error> {
+    let options = DisplayOptions { round_sessions, ..Default::default() };
+    match format {
+        ExportFormat::Csv => Ok(export_csv(tasks, &options)),
+        ExportFormat::Worklog => Ok(export_worklog(tasks, by, &options)),
+        ExportFormat::Json => export_json(tasks, compact),
+    }
+}
+
+/// Renders tasks as a JSON array in the stored format, without their positions
+fn export_json(tasks: &[(usize, &Task)], compact: bool) -> Result<String, serde_json::Error> {
+    let tasks: Vec<&Task> = tasks.iter().map(|(_, task)| *task).collect();
+    to_json(&tasks, compact)
+}
+
+/// Renders tasks as CSV, one row per task, with durations rounded per `options`
+fn export_csv(tasks: &[(usize, &Task)], options: &DisplayOptions) -> String {
+    let mut output = String::from("index,label,project,status,created_at,duration_seconds\n");
+
+    for (index, task) in tasks {
+        let row = [
+            (index + 1).to_string(),
+            csv_field(&task.label),
+            csv_field(task.project.as_deref().unwrap_or("")),
+            task.status.name().to_string(),
+            task.created_at.to_rfc3339(),
+            task_duration(task, options).as_secs().to_string(),
+        ];
+        output.push_str(&row.join(","));
+        output.push('\n');
+    }
+
+    output
+}
+
+/// Renders tasks as a Markdown journal with a `## YYYY-MM-DD` section per local day and the
+/// task's note, if any, beneath its bullet
+///
+/// Durations are rounded per `options`.
+fn export_worklog(tasks: &[(usize, &Task)], by: WorklogDate, options: &DisplayOptions) -> String {
+    export_worklog_in(tasks, by, options, &Local)
+}
+
+/// Renders a worklog, as [`export_worklog`] does, with the days of the time zone `tz`
+fn export_worklog_in<Tz: TimeZone>(
+    tasks: &[(usize, &Task)],
+    by: WorklogDate,
+    options: &DisplayOptions,
+    tz: &Tz,
+) -> String {
+    let mut days: Vec<(NaiveDate, Vec<&Task>)> = Vec::new();
+
+    for (_, task) in tasks {
+        let date = match by {
+            WorklogDate::Created => task.created_at,
+            WorklogDate::Completed => match task.completed_at {
+                Some(completed_at) => completed_at,
+                None => continue,
+            },
+        };
+        let day = date.with_timezone(tz).date_naive();
+        match days.iter_mut().find(|(d, _)| *d == day) {
+            Some((_, day_tasks)) => day_tasks.push(task),
+            None => days.push((day, vec![task])),
+        }
+    }
+
+    if days.is_empty() {
+        return "No tasks to log\n".to_string();
+    }
+
+    days.sort_by_key(|(day, _)| *day);
+
+    let sections: Vec<String> = days
+        .into_iter()
+        .map(|(day, day_tasks)| {
+            let mut section = format!("## {}\n\n", day.format("%Y-%m-%d"));
+            for task in day_tasks {
+                section.push_str(&format!(
+                    "- {} ({})",
+                    task.label,
+                    format_duration(task_duration(task, options))
+                ));
+                if let Some(project) = &task.project {
+                    section.push_str(&format!(" [{}]", project));
+                }
+                section.push('\n');
+                // Indented so Markdown keeps the note inside the task's bullet
+                if let Some(note) = &task.note {
+                    section.push_str(&format!("  {}\n", note));
+                }
+            }
+            section
+        })
+        .collect();
+
+    sections.join("\n")
+}
+
+/// Quotes a CSV field if it contains a delimiter, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod export_tests;
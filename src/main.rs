@@ -1,86 +1,559 @@
 mod cli;
 mod display;
+mod duration;
+mod export;
+mod report;
+mod settings;
+mod storage;
 mod task;
 
 use anyhow::Result;
+use chrono::{Local, Utc};
 use clap::Parser;
-use cli::{Cli, Commands};
-use display::{display_current_status, display_task_summary};
-use std::process;
-use task::{TaskError, TaskManager};
+use cli::{completion_script, Cli, Commands, ConfigAction};
+use display::{
+    display_current_status, display_current_status_porcelain, display_duration_explanation,
+    display_filtered_task_summary, display_one_line_summary, display_settings, display_stale_tasks,
+    display_task_info, display_task_summary, display_task_summary_plain, display_today_summary,
+    display_totals, format_duration, format_journal_entry, idle_warnings, json_current_status,
+    json_task_list, most_recent, to_json, DisplayOptions,
+};
+use duration::{parse_duration, parse_time};
+use export::{export_tasks, ExportFormat};
+use report::{
+    daily_report, iso_week_report, iso_week_report_json, report_json, week_start, weekly_report,
+    ReportPeriod,
+};
+use settings::{Settings, Source};
+use std::{
+    env,
+    ffi::OsString,
+    fs::{self, OpenOptions},
+    io::{self, BufRead, IsTerminal, Write},
+    path::Path,
+    process,
+    sync::mpsc::{self, RecvTimeoutError},
+    time::Duration,
+};
+use task::{parse_task_file, DateRange, StalePolicy, Task, TaskError, TaskManager, TaskStatus};
 
 fn main() {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    if cli.command.is_none() {
+        // Expanded first, as the default command may bring global flags of its own
+        let args: Vec<OsString> = env::args_os().skip(1).collect();
+        cli = default_command(cli, args, &Settings::load_default_command());
+    }
+    let settings = Settings::load(&cli);
+
+    // Pure utilities: answer without opening (or later saving) the task store
+    if let Some(Commands::ParseDuration { input }) = &cli.command {
+        match describe_duration(input) {
+            Ok(message) => println!("{}", message),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                process::exit(e.downcast_ref().map_or(1, exit_code));
+            },
+        }
+        return;
+    }
+    if let Some(Commands::Completions { shell }) = cli.command {
+        print!("{}", completion_script(shell));
+        return;
+    }
+
+    if settings.store_path.source == Source::Default
+        && let (_, Some(note)) = TaskManager::default_location()
+    {
+        eprintln!("Note: {}", note);
+    }
 
     // Load existing state or create new TaskManager
-    let mut task_manager = match TaskManager::load_or_create() {
-        Ok(manager) => manager,
+    let passphrase = settings.encrypt_key.value.clone();
+    let store_path = settings.store_path.value.clone();
+    let max_tasks = settings.max_tasks.value;
+    let retain_days = settings.retain_days.value;
+    let mut task_manager = match TaskManager::load_or_create(
+        store_path,
+        passphrase,
+        max_tasks,
+        retain_days,
+    ) {
+        Ok((manager, backup)) => {
+            if let Some(backup) = backup {
+                eprintln!(
+                    "Warning: The task file could not be parsed; moved it to {} and started with no tasks",
+                    backup.display()
+                );
+            }
+            manager
+        },
         Err(e) => {
-            eprintln!("Warning: Could not load tasks ({}), starting fresh", e);
-            TaskManager::new()
+            // Starting fresh here would overwrite the existing tasks on save
+            eprintln!("Error: Could not load tasks: {}", e);
+            process::exit(exit_code(&e));
         },
     };
 
-    match handle_command(&mut task_manager, cli.command) {
+    let verbose = cli.verbose;
+    if verbose {
+        let path = task_manager.get_store_path().unwrap_or_default();
+        log(
+            verbose,
+            &format!(
+                "loaded {} tasks from {}",
+                task_manager.task_count(),
+                path.display()
+            ),
+        );
+    }
+    // Only kept for the transition log, so plain runs skip the copy
+    let loaded = verbose.then(|| task_manager.all_tasks().to_vec());
+
+    for repair in task_manager.repair(Utc::now()) {
+        eprintln!("Warning: {}", repair);
+    }
+
+    task_manager.set_strict_labels(settings.strict_labels.value);
+    task_manager.set_save_retries(settings.save_retries.value);
+
+    if let Some(days) = settings.stale_after_days.value {
+        let policy = settings.stale_policy.value;
+        let max_age = Duration::from_secs(days * 24 * 60 * 60);
+        let count = task_manager.prune_stale_tasks(max_age, policy, Utc::now());
+        if count > 0 {
+            let action = match policy {
+                StalePolicy::Evict => "removed",
+                StalePolicy::Complete => "completed",
+            };
+            eprintln!("Note: {} stale paused task(s) {}", count, action);
+        }
+    }
+
+    // Only missing when TT_DEFAULT_COMMAND was invalid
+    let command = cli.command.unwrap_or(Commands::Status {
+        label_max: None,
+        porcelain: false,
+        utc: false,
+    });
+
+    // Taken up front so a command that changes tasks can be undone. Undo itself doesn't
+    // replace the snapshot, as only one level is kept.
+    let snapshot = match command {
+        Commands::Undo => None,
+        _ => task_manager.snapshot().ok(),
+    };
+    // Prompt scripts expect exactly one line, even an empty one
+    let print_empty = matches!(command, Commands::Status { porcelain: true, .. });
+    // A preview must leave the task file exactly as it was
+    let dry_run = matches!(command, Commands::Delete { dry_run: true, .. });
+    let quiet = cli.quiet && !command.prints_output();
+
+    match handle_command(&mut task_manager, command, &settings) {
         Ok(message) => {
+            if let Some(before) = snapshot
+                && task_manager.snapshot().ok().as_ref() != Some(&before)
+                && let Err(e) = task_manager.save_undo_snapshot(&before)
+            {
+                eprintln!("Warning: Could not save undo snapshot: {}", e);
+            }
+
+            announce_reached_goals(&mut task_manager);
+
+            for transition in describe_transitions(
+                loaded.as_deref().unwrap_or_default(),
+                task_manager.all_tasks(),
+            ) {
+                log(verbose, &transition);
+            }
+
             // Save state after successful command
-            if let Err(e) = task_manager.save() {
-                eprintln!("Warning: Could not save tasks: {}", e);
+            if dry_run {
+                log(verbose, "dry run; not saving");
+            } else {
+                match task_manager.save() {
+                    Ok(false) => log(verbose, "tasks unchanged; not saving"),
+                    Ok(true) => {
+                        let path = task_manager.get_store_path().unwrap_or_default();
+                        log(
+                            verbose,
+                            &format!(
+                                "saved {} tasks to {}",
+                                task_manager.task_count(),
+                                path.display()
+                            ),
+                        );
+                    },
+                    Err(e) => eprintln!("Warning: Could not save tasks: {}", e),
+                }
             }
 
-            if !message.is_empty() {
+            if !quiet && (!message.is_empty() || print_empty) {
                 println!("{}", message);
             }
         },
         Err(e) => {
             eprintln!("Error: {}", e);
-            process::exit(1);
+            process::exit(e.downcast_ref().map_or(1, exit_code));
+        },
+    }
+}
+
+/// Maps an error to the process exit code, so scripts can tell failures apart
+///
+/// 10 means there was no active task, 11 that the request itself was invalid (a bad index, or
+/// a task in the wrong state), and 12 that the task file couldn't be read or written. Anything
+/// else exits with 1; 2 is left to clap for usage errors.
+fn exit_code(error: &TaskError) -> i32 {
+    match error {
+        TaskError::NoActiveTask => 10,
+        TaskError::InvalidState { .. }
+        | TaskError::TaskNotFound { .. }
+        | TaskError::TaskAlreadyRunning
+        | TaskError::TaskAlreadyPaused
+        | TaskError::TaskCompleted => 11,
+        TaskError::IoError(_) | TaskError::SerializationError(_) | TaskError::Encryption { .. } => {
+            12
         },
+        _ => 1,
     }
 }
 
-fn handle_command(task_manager: &mut TaskManager, command: Commands) -> Result<String> {
+fn handle_command(
+    task_manager: &mut TaskManager,
+    command: Commands,
+    settings: &Settings,
+) -> Result<String> {
     match command {
-        Commands::Start { label } => {
-            let _task_index = task_manager.start_task(label.clone())?;
+        Commands::Start {
+            label,
+            project,
+            tags,
+            priority,
+            at,
+            force,
+            goal,
+            estimate,
+        } => {
+            let label = label.join(" ");
+            let started_at = match at {
+                Some(at) => parse_time(&at, Utc::now())?,
+                None => Utc::now(),
+            };
+            if !force {
+                task_manager.check_duplicate_label(&label)?;
+            }
+            let task_index = task_manager.start_task_at(label, tags, started_at)?;
+            if let Some(project) = project {
+                task_manager.set_project(task_index + 1, project)?;
+            }
+            if let Some(priority) = priority {
+                task_manager.set_priority(task_index + 1, priority)?;
+            }
+            if let Some(goal) = goal {
+                task_manager.set_goal(task_index + 1, goal)?;
+            }
+            if let Some(estimate) = estimate {
+                task_manager.set_estimate(task_index + 1, estimate)?;
+            }
+            // Report the label as stored, after sanitization
+            let label = &task_manager.all_tasks()[task_index].label;
             Ok(format!("Started task: '{}'", label))
         },
 
-        Commands::Pause => {
+        Commands::Pause { all: true, .. } => match task_manager.pause_all_running()? {
+            0 => Ok("No running tasks to pause".to_string()),
+            count => Ok(format!("Paused {} running task(s)", count)),
+        },
+
+        Commands::Pause { all: false, note } => {
             task_manager.pause_current_task()?;
+            if let Some(note) = note
+                && let Some(index) = task_manager.active_task_number()
+            {
+                task_manager.set_note(index, &note)?;
+            }
             let current_task = task_manager.current_task();
             Ok(format!(
                 "Paused task. {}",
-                display_current_status(current_task)
+                display_current_status(
+                    current_task,
+                    None,
+                    settings.no_emoji.value,
+                    settings.use_color(),
+                    settings.utc.value,
+                )
             ))
         },
 
-        Commands::Resume => {
-            task_manager.resume_current_task()?;
+        Commands::Resume { index } => {
+            match index {
+                Some(index) => task_manager.resume_task(index)?,
+                None => task_manager.resume_current_task()?,
+            }
             let current_task = task_manager.current_task();
             Ok(format!(
                 "Resumed task. {}",
-                display_current_status(current_task)
+                display_current_status(
+                    current_task,
+                    None,
+                    settings.no_emoji.value,
+                    settings.use_color(),
+                    settings.utc.value,
+                )
             ))
         },
 
-        Commands::Status => {
+        Commands::Stop => match task_manager.pause_all_running()? {
+            0 => Ok("Nothing is running; no tasks were paused".to_string()),
+            count => Ok(format!("Paused {} running task(s)", count)),
+        },
+
+        Commands::Status { label_max, porcelain, utc } => {
             let current_task = task_manager.current_task();
-            Ok(display_current_status(current_task))
+            // Prompt scripts run this constantly, so keep stderr quiet too
+            if porcelain {
+                return Ok(display_current_status_porcelain(current_task));
+            }
+            warn_if_left_running(task_manager, settings);
+            if settings.json.value {
+                return Ok(json_current_status(
+                    current_task,
+                    settings.json_compact.value,
+                )?);
+            }
+            Ok(display_current_status(
+                current_task,
+                label_max,
+                settings.no_emoji.value,
+                settings.use_color(),
+                utc || settings.utc.value,
+            ))
         },
 
-        Commands::List => Ok(display_task_summary(task_manager.all_tasks())),
+        Commands::List {
+            relative,
+            sort,
+            reverse,
+            group_by,
+            round_sessions,
+            round,
+            no_float,
+            utc,
+            plain,
+            since,
+            until,
+            format,
+            limit,
+            ids,
+        } => {
+            warn_if_left_running(task_manager, settings);
+            let range = DateRange::new(since, until)?;
+            let tasks: Vec<_> = task_manager
+                .all_tasks()
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| range.contains(task.created_at))
+                .collect();
+            let options = DisplayOptions {
+                relative,
+                sort,
+                reverse,
+                group_by,
+                round_sessions,
+                round: round.or(settings.round_minutes.value),
+                float_active: settings.float_active.value && !no_float,
+                utc: utc || settings.utc.value,
+                no_emoji: settings.no_emoji.value,
+                color: settings.use_color(),
+                format,
+                active: task_manager.active_task_index(),
+                limit,
+                // Shortened across the whole store, so a shown ID never matches a hidden task
+                short_ids: ids.then(|| task_manager.short_ids()),
+            };
+            if settings.json.value {
+                return Ok(json_task_list(
+                    &most_recent(&tasks, limit),
+                    &options,
+                    settings.json_compact.value,
+                )?);
+            }
+            if plain {
+                return Ok(display_task_summary_plain(&most_recent(&tasks, limit)));
+            }
+            if range == DateRange::default() {
+                Ok(display_task_summary(task_manager.all_tasks(), &options))
+            } else {
+                Ok(display_filtered_task_summary(&tasks, &options))
+            }
+        },
+
+        Commands::Filter { status } => {
+            let status: TaskStatus = status.parse()?;
+            let matching: Vec<_> = task_manager
+                .all_tasks()
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| task.status == status)
+                .collect();
+            let options = DisplayOptions {
+                no_emoji: settings.no_emoji.value,
+                color: settings.use_color(),
+                active: task_manager.active_task_index(),
+                ..Default::default()
+            };
+            Ok(display_filtered_task_summary(&matching, &options))
+        },
+
+        Commands::Summary { totals: true } => {
+            // Match the footer `list` would show
+            let options = DisplayOptions {
+                round: settings.round_minutes.value,
+                ..Default::default()
+            };
+            Ok(display_totals(task_manager.all_tasks(), &options))
+        },
+
+        Commands::Summary { totals: false } => Ok(display_one_line_summary(
+            &task_manager.stats(),
+            task_manager.current_task(),
+        )),
+
+        Commands::Total { seconds } => {
+            let total = task_manager.stats().total_duration;
+            Ok(if seconds {
+                total.as_secs().to_string()
+            } else {
+                format_duration(total)
+            })
+        },
+
+        Commands::Today => {
+            let today = Local::now().date_naive();
+            let matching: Vec<_> = task_manager
+                .all_tasks()
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| task.worked_on(today))
+                .collect();
+            let options = DisplayOptions {
+                no_emoji: settings.no_emoji.value,
+                color: settings.use_color(),
+                active: task_manager.active_task_index(),
+                ..Default::default()
+            };
+            Ok(display_today_summary(&matching, today, &options))
+        },
 
-        Commands::Complete => match task_manager.current_task() {
-            Some(task) => {
-                let label = task.label.clone();
-                task_manager.complete_current_task()?;
+        Commands::Complete { index, id, cap_session, note, no_journal } => {
+            let index = index_or_id(task_manager, index, id)?;
+            let task = match index {
+                Some(index) => task_manager.get_task(index)?,
+                None => task_manager.current_task().ok_or(TaskError::NoActiveTask)?,
+            };
+            let label = task.label.clone();
+            let session = task.current_session_duration();
+
+            if cap_session.is_none()
+                && let Some(threshold) = settings.confirm_long_complete.value
+                && session > threshold
+                // Scripts can't answer a prompt, so they always get the full time
+                && io::stdin().is_terminal()
+                && !confirm(&format!(
+                    "This task has run {} — complete with full time?",
+                    format_duration(session)
+                ))
+            {
+                return Ok(
+                    "Complete cancelled; use --cap-session <DURATION> to credit less time"
+                        .to_string(),
+                );
+            }
+
+            let trimmed = match (cap_session, index) {
+                (Some(cap), Some(index)) => task_manager.cap_session(index, cap)?,
+                (Some(cap), None) => task_manager.cap_current_session(cap)?,
+                (None, _) => Duration::ZERO,
+            };
+            // Kept on the task as well, so skipping the journal doesn't lose the note
+            if let Some(note) = &note {
+                let number = match index {
+                    Some(index) => index,
+                    None => task_manager
+                        .active_task_number()
+                        .ok_or(TaskError::NoActiveTask)?,
+                };
+                task_manager.set_note(number, note)?;
+            }
+            let journal_path = task_manager.get_journal_path()?;
+            let task = match index {
+                Some(index) => task_manager.complete_task(index)?,
+                None => task_manager.complete_current_task()?,
+            };
+
+            if let Some(note) = note.filter(|_| !no_journal) {
+                let entry = format_journal_entry(task, &note);
+                // The task is already complete, so a journal failure shouldn't undo it
+                if let Err(e) = append_line(&journal_path, &entry) {
+                    eprintln!(
+                        "Warning: Could not write journal {}: {}",
+                        journal_path.display(),
+                        e
+                    );
+                }
+            }
+
+            if trimmed.is_zero() {
                 Ok(format!("Completed task: '{}'", label))
-            },
-            None => Err(TaskError::NoActiveTask.into()),
+            } else {
+                Ok(format!(
+                    "Completed task: '{}' (discarded {} beyond the session cap)",
+                    label,
+                    format_duration(trimmed)
+                ))
+            }
         },
 
-        Commands::Delete { index, completed } => {
+        Commands::Delete { index, id, completed, dry_run: true, .. } => {
+            let index = index_or_id(task_manager, index, id)?;
+            let targets: Vec<_> = if completed {
+                let targets: Vec<_> = task_manager
+                    .all_tasks()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, task)| task.is_completed())
+                    .collect();
+                if targets.is_empty() {
+                    return Ok("No completed tasks to delete".to_string());
+                }
+                targets
+            } else if let Some(idx) = index {
+                // Fails exactly as the real delete would, assuming any prompt is confirmed
+                let position = task_manager.check_deletable(idx, true)?;
+                vec![(position, &task_manager.all_tasks()[position])]
+            } else {
+                return Err(TaskError::InvalidState {
+                    message: "Please specify a task index or use --completed flag".to_string(),
+                }
+                .into());
+            };
+
+            let mut output = format!("Dry run: would delete {} task(s):", targets.len());
+            for (position, task) in targets {
+                output.push_str(&format!("\n  {}. {}", position + 1, task.label));
+            }
+            Ok(output)
+        },
+
+        Commands::Delete {
+            index,
+            id,
+            completed,
+            dry_run: false,
+            yes,
+        } => {
+            let index = index_or_id(task_manager, index, id)?;
             if completed {
                 // Delete all completed tasks
                 let count = task_manager.delete_completed_tasks()?;
@@ -104,7 +577,20 @@ fn handle_command(task_manager: &mut TaskManager, command: Commands) -> Result<S
                     String::new()
                 };
 
-                task_manager.delete_task(idx)?;
+                // Only the paused active task needs confirming; anything else that can't be
+                // deleted fails below as usual
+                let force = task_manager.check_deletable(idx, false).is_err()
+                    && task_manager.check_deletable(idx, true).is_ok();
+                if force
+                    && !yes
+                    && !confirm(&format!(
+                        "Task '{}' is paused and its time will be discarded. Delete it?",
+                        task_label
+                    ))
+                {
+                    return Ok("Delete cancelled, no tasks were removed".to_string());
+                }
+                task_manager.delete_task(idx, force)?;
 
                 if !task_label.is_empty() {
                     Ok(format!("Task \"{}\" deleted successfully", task_label))
@@ -119,13 +605,649 @@ fn handle_command(task_manager: &mut TaskManager, command: Commands) -> Result<S
             }
         },
 
-        Commands::Rename { index, new_label } => {
-            let old_label = task_manager.rename_task(index, new_label.clone())?;
+        Commands::Clone { index } => {
+            let task_index = task_manager.clone_task(index)?;
+            let label = &task_manager.all_tasks()[task_index].label;
+            Ok(format!(
+                "Started task: '{}' (copied from task {})",
+                label, index
+            ))
+        },
+
+        Commands::Merge { into, from, trim_overlaps } => {
+            let merged = task_manager.merge_tasks(into, from)?;
+            // `into` shifts down by one when an earlier task was removed
+            let into = if from < into { into - 1 } else { into };
+            // Sessions from both tasks may cover the same time
+            let trimmed = resolve_overlaps(task_manager, &[into - 1], trim_overlaps);
+            let task = &task_manager.all_tasks()[into - 1];
+            let mut output = format!(
+                "Merged '{}' into '{}' (added {}, total {})",
+                merged.label,
+                task.label,
+                format_duration(merged.total_duration()),
+                format_duration(task.total_duration())
+            );
+            if let Some(trimmed) = trimmed {
+                output.push_str(&format!("\n{}", trimmed));
+            }
+            Ok(output)
+        },
+
+        Commands::Split { index, ratio, new_label } => {
+            let new_index = task_manager.split_task(index, ratio, &new_label)?;
+            let tasks = task_manager.all_tasks();
+            Ok(format!(
+                "Split {} from '{}' into new task {}: '{}' ({} left)",
+                format_duration(tasks[new_index].total_duration()),
+                tasks[index - 1].label,
+                new_index + 1,
+                tasks[new_index].label,
+                format_duration(tasks[index - 1].total_duration())
+            ))
+        },
+
+        Commands::Restart { index } => {
+            let discarded = task_manager.restart_task(index)?;
+            let label = &task_manager.all_tasks()[index - 1].label;
+            Ok(format!(
+                "Restarted '{}' from zero (discarded {})",
+                label,
+                format_duration(discarded)
+            ))
+        },
+
+        Commands::Rename { index, id, new_label, .. } => {
+            // Without an index or ID, clap guarantees --active was given
+            let index = match index_or_id(task_manager, index, id)? {
+                Some(index) => index,
+                None => task_manager
+                    .active_task_number()
+                    .ok_or(TaskError::NoActiveTask)?,
+            };
+            let old_label = task_manager.rename_task(index, new_label)?;
+            let new_label = &task_manager.all_tasks()[index - 1].label;
             Ok(format!(
                 "Task renamed from \"{}\" to \"{}\"",
                 old_label, new_label
             ))
         },
+
+        Commands::Note { index, text } => {
+            let is_set = task_manager.set_note(index, &text)?;
+            let label = &task_manager.all_tasks()[index - 1].label;
+            if is_set {
+                Ok(format!("Added note to '{}'", label))
+            } else {
+                Ok(format!("Cleared note on '{}'", label))
+            }
+        },
+
+        Commands::SetPriority { index, level } => {
+            task_manager.set_priority(index, level)?;
+            let label = &task_manager.all_tasks()[index - 1].label;
+            Ok(format!("Set priority of '{}' to {}", label, level))
+        },
+
+        Commands::Estimate { index, duration } => {
+            task_manager.set_estimate(index, duration)?;
+            let label = &task_manager.all_tasks()[index - 1].label;
+            Ok(format!(
+                "Set estimate of '{}' to {}",
+                label,
+                format_duration(duration)
+            ))
+        },
+
+        Commands::AddTime { index, duration } => {
+            let duration = parse_duration(&duration)?;
+            task_manager.add_time(index, duration)?;
+            let task = &task_manager.all_tasks()[index - 1];
+            Ok(format!(
+                "Added {} to '{}' (total {})",
+                format_duration(duration),
+                task.label,
+                format_duration(task.total_duration())
+            ))
+        },
+
+        Commands::Reorder { from, to } => {
+            task_manager.reorder_task(from, to)?;
+            let label = &task_manager.all_tasks()[to - 1].label;
+            Ok(format!(
+                "Moved task \"{}\" from position {} to {}",
+                label, from, to
+            ))
+        },
+
+        Commands::Relabel { project, prefix } => {
+            let count = task_manager.prefix_project_labels(&project, &prefix)?;
+            if count == 0 {
+                Ok(format!("No tasks in project '{}'", project))
+            } else {
+                Ok(format!(
+                    "Relabelled {} task(s) in project '{}'",
+                    count, project
+                ))
+            }
+        },
+
+        Commands::Info { query, explain, utc } => {
+            // A number is an index, unless no task has it; anything else is looked up by label
+            let index = match query.trim().parse::<usize>() {
+                Ok(index) => match task_manager.get_task(index) {
+                    Ok(_) => index,
+                    // e.g. `info 2024` for a task labelled "2024 report"
+                    Err(e) => task_manager.find_by_label(&query).map_err(|_| e)? + 1,
+                },
+                Err(_) => task_manager.find_by_label(&query)? + 1,
+            };
+            let task = task_manager.get_task(index)?;
+            let utc = utc || settings.utc.value;
+            let options = DisplayOptions {
+                utc,
+                no_emoji: settings.no_emoji.value,
+                color: settings.use_color(),
+                ..Default::default()
+            };
+            let mut output = display_task_info(task, index, &options);
+            if explain {
+                output.push_str("\n\n");
+                output.push_str(&display_duration_explanation(task, utc));
+            }
+            Ok(output)
+        },
+
+        Commands::Watch => {
+            let Some(task) = task_manager.current_task() else {
+                return Ok("No active task to watch".to_string());
+            };
+            let label = task.label.clone();
+            watch(task_manager, settings)?;
+            Ok(format!("Stopped watching '{}'", label))
+        },
+
+        Commands::Last => {
+            let Some(position) = task_manager.last_worked_on() else {
+                return Ok("No tasks yet".to_string());
+            };
+            let options = DisplayOptions {
+                utc: settings.utc.value,
+                no_emoji: settings.no_emoji.value,
+                color: settings.use_color(),
+                ..Default::default()
+            };
+            Ok(display_task_info(
+                &task_manager.all_tasks()[position],
+                position + 1,
+                &options,
+            ))
+        },
+
+        Commands::Check { fail_if_running } => {
+            let running = task_manager.running_tasks();
+            if running.is_empty() {
+                return Ok("No running tasks".to_string());
+            }
+
+            let listing = running
+                .iter()
+                .map(|(index, task)| format!("{}. {}", index + 1, task.label))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            if fail_if_running {
+                Err(TaskError::InvalidState {
+                    message: format!("{} task(s) still running: {}", running.len(), listing),
+                }
+                .into())
+            } else {
+                Ok(format!("{} task(s) running: {}", running.len(), listing))
+            }
+        },
+
+        Commands::Stale { older_than } => Ok(display_stale_tasks(
+            &task_manager.stale_tasks(older_than, Utc::now()),
+        )),
+
+        Commands::TidySessions { gap, trim_overlaps } => {
+            let mut messages = Vec::new();
+
+            // Overlaps must be resolved before merging, which would otherwise sum them
+            let all: Vec<usize> = (0..task_manager.task_count()).collect();
+            messages.extend(resolve_overlaps(task_manager, &all, trim_overlaps));
+
+            let merged = task_manager.tidy_sessions(Duration::from_secs(gap));
+            if merged == 0 {
+                messages.push("No sessions to merge".to_string());
+            } else {
+                messages.push(format!(
+                    "Merged {} session(s) into their neighbours",
+                    merged
+                ));
+            }
+
+            Ok(messages.join("\n"))
+        },
+
+        Commands::Abort { all, force } => {
+            if !all {
+                return Err(TaskError::InvalidState {
+                    message: "Please use --all to abort every running and paused task".to_string(),
+                }
+                .into());
+            }
+
+            let in_progress = task_manager
+                .all_tasks()
+                .iter()
+                .filter(|task| !task.is_completed())
+                .count();
+            if in_progress == 0 {
+                return Ok("No running or paused tasks to abort".to_string());
+            }
+
+            if !force
+                && !confirm(&format!(
+                    "Abort {} running/paused task(s)? Their time will be discarded.",
+                    in_progress
+                ))
+            {
+                return Ok("Abort cancelled, no tasks were removed".to_string());
+            }
+
+            let count = task_manager.abort_all();
+            Ok(format!("{} in-progress task(s) aborted", count))
+        },
+
+        Commands::Clean { keep_active, force } => {
+            let kept_label = match task_manager.current_task() {
+                Some(task) if keep_active => Some(task.label.clone()),
+                _ => None,
+            };
+            let to_remove = task_manager.task_count() - usize::from(kept_label.is_some());
+            if to_remove == 0 {
+                return Ok("No tasks to clean".to_string());
+            }
+
+            if !force
+                && !confirm(&format!(
+                    "Remove {} task(s)? Their time will be discarded.",
+                    to_remove
+                ))
+            {
+                return Ok("Clean cancelled, no tasks were removed".to_string());
+            }
+
+            let count = task_manager.clean(keep_active);
+            match kept_label {
+                Some(label) => Ok(format!(
+                    "{} task(s) removed, kept active task '{}'",
+                    count, label
+                )),
+                None => Ok(format!("{} task(s) removed", count)),
+            }
+        },
+
+        Commands::Archive { list: true } => {
+            let archived = task_manager.archived_tasks()?;
+            if archived.is_empty() {
+                return Ok("No archived tasks".to_string());
+            }
+            let options = DisplayOptions {
+                no_emoji: settings.no_emoji.value,
+                color: settings.use_color(),
+                ..Default::default()
+            };
+            Ok(display_task_summary(&archived, &options))
+        },
+
+        Commands::Archive { list: false } => match task_manager.archive_completed()? {
+            0 => Ok("No completed tasks to archive".to_string()),
+            count => Ok(format!(
+                "Archived {} completed task(s) to {}",
+                count,
+                task_manager.get_archive_path()?.display()
+            )),
+        },
+
+        Commands::Undo => {
+            if task_manager.undo()? {
+                Ok("Undid the last change".to_string())
+            } else {
+                Ok("Nothing to undo".to_string())
+            }
+        },
+
+        Commands::Export {
+            format,
+            since,
+            until,
+            by,
+            round_sessions,
+            full,
+        } => {
+            if round_sessions.is_some() && format == ExportFormat::Json {
+                return Err(TaskError::InvalidState {
+                    message: "--round-sessions only applies to csv and worklog exports".to_string(),
+                }
+                .into());
+            }
+            if full {
+                if format != ExportFormat::Json {
+                    return Err(TaskError::InvalidState {
+                        message: "--full exports the whole task file as JSON; add --format json"
+                            .to_string(),
+                    }
+                    .into());
+                }
+                return Ok(to_json(&*task_manager, settings.json_compact.value)?);
+            }
+            let range = DateRange::new(since, until)?;
+            let tasks: Vec<_> = task_manager
+                .all_tasks()
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| range.contains(task.created_at))
+                .collect();
+            // The export ends with a newline of its own
+            Ok(export_tasks(
+                &tasks,
+                format,
+                by,
+                round_sessions,
+                settings.json_compact.value,
+            )?
+            .trim_end()
+            .to_string())
+        },
+
+        Commands::Import { path, merge, trim_overlaps } => {
+            // Parse everything before touching the current tasks
+            let content = fs::read_to_string(&path).map_err(TaskError::from)?;
+            let (tasks, active) = parse_task_file(&content)?;
+            let count = tasks.len();
+            task_manager.import_tasks_with_active(tasks, active, merge)?;
+            let all: Vec<usize> = (0..task_manager.task_count()).collect();
+            let trimmed = resolve_overlaps(task_manager, &all, trim_overlaps);
+            let mut output = format!(
+                "Imported {} task(s) from {} ({} stored)",
+                count,
+                path.display(),
+                task_manager.task_count()
+            );
+            if let Some(trimmed) = trimmed {
+                output.push_str(&format!("\n{}", trimmed));
+            }
+            Ok(output)
+        },
+
+        Commands::Report {
+            weekly,
+            week_of,
+            by,
+            round,
+            round_sessions,
+            since,
+            until,
+        } => {
+            let range = DateRange::new(since, until)?;
+            let tasks: Vec<_> = task_manager
+                .all_tasks()
+                .iter()
+                .filter(|task| range.contains(task.created_at))
+                .cloned()
+                .collect();
+            let monday = week_start(week_of.unwrap_or_else(|| Local::now().date_naive()));
+            let round = round.or(settings.round_minutes.value);
+            if by == ReportPeriod::Week {
+                if settings.json.value {
+                    Ok(iso_week_report_json(
+                        &tasks,
+                        round,
+                        round_sessions,
+                        settings.json_compact.value,
+                    )?)
+                } else {
+                    Ok(iso_week_report(&tasks, round, round_sessions))
+                }
+            } else if settings.json.value {
+                Ok(report_json(
+                    &tasks,
+                    monday,
+                    round,
+                    round_sessions,
+                    settings.json_compact.value,
+                )?)
+            } else if weekly {
+                Ok(weekly_report(&tasks, monday, round, round_sessions))
+            } else {
+                Ok(daily_report(&tasks, monday, round, round_sessions))
+            }
+        },
+
+        Commands::Config { action: ConfigAction::Show } => Ok(display_settings(settings)),
+
+        Commands::ParseDuration { input } => describe_duration(&input),
+
+        // The script ends with a newline of its own
+        Commands::Completions { shell } => Ok(completion_script(shell).trim_end().to_string()),
+    }
+}
+
+/// Prints a diagnostic line to stderr when `--verbose` is given
+fn log(verbose: bool, message: &str) {
+    if verbose {
+        eprintln!("[tt] {}", message);
+    }
+}
+
+/// Describes each task that was added, removed or changed status between `before` and `after`
+///
+/// Tasks are matched by creation time, so renamed or moved tasks are still recognized.
+fn describe_transitions(before: &[Task], after: &[Task]) -> Vec<String> {
+    let mut matched = vec![false; before.len()];
+    let mut transitions = Vec::new();
+
+    for (index, task) in after.iter().enumerate() {
+        let previous =
+            (0..before.len()).find(|&i| !matched[i] && before[i].created_at == task.created_at);
+        let Some(previous) = previous else {
+            transitions.push(format!(
+                "added task index {} ('{}', {})",
+                index + 1,
+                task.label,
+                task.status.name()
+            ));
+            continue;
+        };
+
+        matched[previous] = true;
+        let verb = match (&before[previous].status, &task.status) {
+            (old, new) if old == new => continue,
+            (TaskStatus::Completed, TaskStatus::Running) => "restarted",
+            (_, TaskStatus::Running) => "resumed",
+            (_, TaskStatus::Paused) => "paused",
+            (_, TaskStatus::Completed) => "completed",
+        };
+        transitions.push(format!(
+            "{} task index {} ('{}')",
+            verb,
+            index + 1,
+            task.label
+        ));
+    }
+
+    for (task, _) in before.iter().zip(matched).filter(|(_, matched)| !matched) {
+        transitions.push(format!("removed task '{}'", task.label));
+    }
+
+    transitions
+}
+
+/// Prints a notice, with a bell on a terminal, for each task that just reached its goal
+///
+/// The notified flag is saved with the tasks, so each goal is announced once.
+fn announce_reached_goals(task_manager: &mut TaskManager) {
+    for index in task_manager.check_goals() {
+        let task = &task_manager.all_tasks()[index];
+        let bell = if io::stderr().is_terminal() {
+            "\x07"
+        } else {
+            ""
+        };
+        eprintln!(
+            "{}Goal reached: '{}' has passed its {} goal",
+            bell,
+            task.label,
+            format_duration(task.goal.unwrap_or_default())
+        );
+    }
+}
+
+/// Prints a warning for each running task whose session exceeds `TT_IDLE_WARN_HOURS`
+fn warn_if_left_running(task_manager: &TaskManager, settings: &Settings) {
+    let hours = settings.idle_warn_hours.value;
+    if hours == 0 {
+        return;
+    }
+
+    let threshold = Duration::from_secs(hours.saturating_mul(60 * 60));
+    for warning in idle_warnings(task_manager.all_tasks(), threshold) {
+        eprintln!("{}", warning);
+    }
+}
+
+/// Clears the terminal and reprints the active task's status once per second until Ctrl-C
+///
+/// Waiting on the Ctrl-C channel doubles as the sleep between redraws, so the loop stays idle
+/// and still stops at once. Returning normally lets the caller save as usual.
+fn watch(task_manager: &TaskManager, settings: &Settings) -> Result<()> {
+    let (stop, stopped) = mpsc::channel();
+    ctrlc::set_handler(move || {
+        stop.send(()).ok();
+    })?;
+
+    let mut stdout = io::stdout().lock();
+    loop {
+        let status = display_current_status(
+            task_manager.current_task(),
+            None,
+            settings.no_emoji.value,
+            settings.use_color(),
+            settings.utc.value,
+        );
+        writeln!(stdout, "\x1b[2J\x1b[H{}", status)?;
+        stdout.flush()?;
+
+        match stopped.recv_timeout(Duration::from_secs(1)) {
+            Err(RecvTimeoutError::Timeout) => continue,
+            _ => return Ok(()),
+        }
+    }
+}
+
+/// Warns about overlapping sessions on the tasks at `positions` (0-based), or clips them
+/// when `trim` is set
+///
+/// Returns a message about the clipped time if anything was trimmed.
+fn resolve_overlaps(
+    task_manager: &mut TaskManager,
+    positions: &[usize],
+    trim: bool,
+) -> Option<String> {
+    if trim {
+        let clipped: Duration = positions
+            .iter()
+            .map(|&position| task_manager.trim_task_overlaps(position))
+            .sum();
+        return (!clipped.is_zero()).then(|| {
+            format!(
+                "Trimmed {} of overlapping session time",
+                format_duration(clipped)
+            )
+        });
+    }
+
+    for (task_index, i, j) in task_manager.session_overlaps() {
+        if positions.contains(&task_index) {
+            eprintln!(
+                "Warning: task '{}' has overlapping sessions {} and {} (use --trim-overlaps to fix)",
+                task_manager.all_tasks()[task_index].label,
+                i + 1,
+                j + 1
+            );
+        }
+    }
+    None
+}
+
+/// Resolves a task given by 1-based `index` or by stable `id` to its index
+///
+/// Clap makes the two mutually exclusive, so at most one of them is set.
+fn index_or_id(
+    task_manager: &TaskManager,
+    index: Option<usize>,
+    id: Option<u64>,
+) -> Result<Option<usize>, TaskError> {
+    match id {
+        Some(id) => task_manager.find_by_id(id).map(Some),
+        None => Ok(index),
+    }
+}
+
+/// Expands `tt` invoked without a subcommand into the configured default command
+///
+/// `args` are the original arguments, which can only be global flags, and `value` is the
+/// configured default command line, e.g. `list` or `list --relative`. An invalid default
+/// leaves `cli` as it was, without a command.
+fn default_command(cli: Cli, args: Vec<OsString>, value: &str) -> Cli {
+    parse_default_command(args, value).unwrap_or_else(|| {
+        eprintln!(
+            "Warning: Invalid TT_DEFAULT_COMMAND '{}', falling back to status",
+            value
+        );
+        cli
+    })
+}
+
+/// Parses the global flags in `args` followed by a command line such as `list --relative`
+fn parse_default_command(args: Vec<OsString>, value: &str) -> Option<Cli> {
+    let args = std::iter::once(OsString::from("tt"))
+        .chain(args)
+        .chain(value.split_whitespace().map(OsString::from));
+    Cli::try_parse_from(args)
+        .ok()
+        .filter(|cli| cli.command.is_some())
+}
+
+/// Reports how `input` parses as a duration, in total seconds and in `format_duration` form
+fn describe_duration(input: &str) -> Result<String> {
+    let duration = parse_duration(input)?;
+    Ok(format!(
+        "{} seconds ({})",
+        duration.as_secs(),
+        format_duration(duration)
+    ))
+}
+
+/// Appends `line` to the file at `path`, creating the file if needed
+fn append_line(path: &Path, line: &str) -> io::Result<()> {
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", line)
+}
+
+/// Asks the user a yes/no question on stderr and reads the answer from stdin
+///
+/// Anything other than `y`/`yes` (including EOF) counts as "no".
+fn confirm(prompt: &str) -> bool {
+    eprint!("{} [y/N] ", prompt);
+    io::stderr().flush().ok();
+
+    let mut answer = String::new();
+    match io::stdin().lock().read_line(&mut answer) {
+        Ok(_) => matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"),
+        Err(_) => false,
     }
 }
 
@@ -0,0 +1,370 @@
+use crate::display::{
+    format_duration, format_duration_compact, format_duration_iso8601, round_duration,
+    task_duration, to_json, truncate_label, DisplayOptions,
+};
+use crate::task::Task;
+use chrono::{Datelike, Days, Local, NaiveDate, TimeZone};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Day names used for report columns, Monday first
+const WEEKDAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+/// Longest task label shown in the weekly grid before it is truncated
+const LABEL_MAX: usize = 24;
+
+/// Period `tt report` totals time by
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum ReportPeriod {
+    /// Each day of a single week
+    #[default]
+    Day,
+    /// Each ISO week that tasks were created in
+    Week,
+}
+
+/// Gets the Monday of the week containing `date`
+pub(crate) fn week_start(date: NaiveDate) -> NaiveDate {
+    date - Days::new(u64::from(date.weekday().num_days_from_monday()))
+}
+
+/// Renders the time tracked on each day of the week starting on `monday`
+///
+/// Days without any time are left out.
+pub(crate) fn daily_report(
+    tasks: &[Task],
+    monday: NaiveDate,
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+) -> String {
+    let rows = bucket_week(tasks, monday, round, round_sessions);
+    let mut output = format!("Week of {}\n", monday);
+    if rows.is_empty() {
+        output.push_str("No time tracked this week");
+        return output;
+    }
+
+    let day_totals = sum_days(&rows);
+    for (day, total) in day_totals.iter().enumerate() {
+        if total.is_zero() {
+            continue;
+        }
+        output.push_str(&format!(
+            "{} {}  {}\n",
+            WEEKDAYS[day],
+            monday + Days::new(day as u64),
+            format_duration(*total)
+        ));
+    }
+    output.push_str(&format!(
+        "Total: {}",
+        format_duration(day_totals.iter().sum())
+    ));
+
+    output
+}
+
+/// Renders a grid of time per task (rows) and day (columns) for the week starting on `monday`
+///
+/// The last row holds each day's total and the last column each task's total, with the
+/// weekly grand total in the corner. Days without any time show `-`.
+pub(crate) fn weekly_report(
+    tasks: &[Task],
+    monday: NaiveDate,
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+) -> String {
+    let rows = bucket_week(tasks, monday, round, round_sessions);
+    let mut output = format!("Week of {}\n", monday);
+    if rows.is_empty() {
+        output.push_str("No time tracked this week");
+        return output;
+    }
+
+    let mut table: Vec<Vec<String>> = Vec::new();
+    table.push(
+        std::iter::once("Task")
+            .chain(WEEKDAYS)
+            .chain(std::iter::once("Total"))
+            .map(str::to_string)
+            .collect(),
+    );
+    for (task, days) in &rows {
+        table.push(grid_row(truncate_label(&task.label, LABEL_MAX), days));
+    }
+    table.push(grid_row("Total".to_string(), &sum_days(&rows)));
+
+    let widths: Vec<usize> = (0..table[0].len())
+        .map(|column| {
+            table
+                .iter()
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let lines: Vec<String> = table
+        .iter()
+        .map(|row| {
+            row.iter()
+                .zip(&widths)
+                .enumerate()
+                .map(|(column, (cell, &width))| match column {
+                    0 => format!("{:<width$}", cell),
+                    _ => format!("{:>width$}", cell),
+                })
+                .collect::<Vec<_>>()
+                .join("  ")
+        })
+        .collect();
+
+    let (body, totals) = lines.split_at(lines.len() - 1);
+    output.push_str(&body.join("\n"));
+    output.push('\n');
+    output.push_str(&"-".repeat(totals[0].chars().count()));
+    output.push('\n');
+    output.push_str(&totals[0]);
+
+    output
+}
+
+/// Renders the total time of the tasks created in each ISO week, oldest week first
+///
+/// Each week is labelled with its ISO year and number and the date of its Monday. With
+/// `round`, each task's total is rounded up to that many minutes, and with `round_sessions`
+/// each of its sessions is, before they are summed.
+pub(crate) fn iso_week_report(
+    tasks: &[Task],
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+) -> String {
+    let weeks = bucket_iso_weeks(tasks, round, round_sessions);
+    if weeks.is_empty() {
+        return "No time tracked".to_string();
+    }
+
+    let mut output = String::new();
+    for ((year, week), total) in &weeks {
+        output.push_str(&format!(
+            "{}-W{:02} (week of {})  {}\n",
+            year,
+            week,
+            iso_week_monday(*year, *week),
+            format_duration(*total)
+        ));
+    }
+    output.push_str(&format!("Total: {}", format_duration(weeks.values().sum())));
+
+    output
+}
+
+/// One week of an ISO week report, as printed by `--json`
+#[derive(Debug, Serialize)]
+struct IsoWeekView {
+    year: i32,
+    week: u32,
+    week_of: NaiveDate,
+    total_seconds: u64,
+}
+
+/// Renders the per-ISO-week totals as a JSON array, oldest week first
+pub(crate) fn iso_week_report_json(
+    tasks: &[Task],
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+    compact: bool,
+) -> Result<String, serde_json::Error> {
+    let views: Vec<IsoWeekView> = bucket_iso_weeks(tasks, round, round_sessions)
+        .into_iter()
+        .map(|((year, week), total)| IsoWeekView {
+            year,
+            week,
+            week_of: iso_week_monday(year, week),
+            total_seconds: total.as_secs(),
+        })
+        .collect();
+    to_json(&views, compact)
+}
+
+/// Machine-readable weekly report, as printed by `--json`
+#[derive(Debug, Serialize)]
+struct WeekView<'a> {
+    week_of: NaiveDate,
+    tasks: Vec<WeekTaskView<'a>>,
+    /// Seconds tracked on each day, Monday first
+    day_totals: [u64; 7],
+    total_seconds: u64,
+}
+
+/// One task's row in a [`WeekView`]
+#[derive(Debug, Serialize)]
+struct WeekTaskView<'a> {
+    label: &'a str,
+    /// Seconds tracked on each day, Monday first
+    days: [u64; 7],
+    total_seconds: u64,
+    /// The same total as an ISO 8601 duration, e.g. `PT1H30M`
+    total_iso8601: String,
+}
+
+/// Renders the week starting on `monday` as JSON, with per-task and per-day seconds
+pub(crate) fn report_json(
+    tasks: &[Task],
+    monday: NaiveDate,
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+    compact: bool,
+) -> Result<String, serde_json::Error> {
+    let rows = bucket_week(tasks, monday, round, round_sessions);
+    let day_totals = sum_days(&rows);
+
+    let view = WeekView {
+        week_of: monday,
+        tasks: rows
+            .iter()
+            .map(|(task, days)| {
+                let total = days.iter().sum::<Duration>();
+                WeekTaskView {
+                    label: &task.label,
+                    days: days.map(|d| d.as_secs()),
+                    total_seconds: total.as_secs(),
+                    total_iso8601: format_duration_iso8601(total),
+                }
+            })
+            .collect(),
+        day_totals: day_totals.map(|d| d.as_secs()),
+        total_seconds: day_totals.iter().sum::<Duration>().as_secs(),
+    };
+    to_json(&view, compact)
+}
+
+/// Builds one grid row: a label, a cell per day and the row's total
+fn grid_row(label: String, days: &[Duration; 7]) -> Vec<String> {
+    std::iter::once(label)
+        .chain(days.iter().map(|&d| grid_cell(d)))
+        .chain(std::iter::once(grid_cell(days.iter().sum())))
+        .collect()
+}
+
+/// Formats a grid cell compactly, with `-` for no time
+fn grid_cell(duration: Duration) -> String {
+    if duration.is_zero() {
+        "-".to_string()
+    } else {
+        format_duration_compact(duration)
+    }
+}
+
+/// Adds up the rows' time for each day of the week
+fn sum_days(rows: &[(&Task, [Duration; 7])]) -> [Duration; 7] {
+    let mut totals = [Duration::ZERO; 7];
+    for (_, days) in rows {
+        for (total, day) in totals.iter_mut().zip(days) {
+            *total += *day;
+        }
+    }
+    totals
+}
+
+/// Splits each task's time across the days of the week starting on `monday`
+///
+/// A session counts towards the local day it started on. With `round_sessions`, each session
+/// is rounded up to that many minutes before it is added to its day; with `round`, each
+/// task's time on each day is. Tasks without time that week are left out; the rest keep
+/// their stored order.
+fn bucket_week(
+    tasks: &[Task],
+    monday: NaiveDate,
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+) -> Vec<(&Task, [Duration; 7])> {
+    bucket_week_in(tasks, monday, round, round_sessions, &Local)
+}
+
+/// Splits each task's time across the days of the week, as [`bucket_week`] does, using the
+/// days of the time zone `tz`
+fn bucket_week_in<'a, Tz: TimeZone>(
+    tasks: &'a [Task],
+    monday: NaiveDate,
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+    tz: &Tz,
+) -> Vec<(&'a Task, [Duration; 7])> {
+    tasks
+        .iter()
+        .filter_map(|task| {
+            let mut days = [Duration::ZERO; 7];
+            for session in task.dated_sessions() {
+                let offset = session
+                    .started_at
+                    .with_timezone(tz)
+                    .date_naive()
+                    .signed_duration_since(monday)
+                    .num_days();
+                if let Ok(day) = usize::try_from(offset)
+                    && let Some(time) = days.get_mut(day)
+                {
+                    *time += match round_sessions {
+                        Some(minutes) => round_duration(session.duration, minutes),
+                        None => session.duration,
+                    };
+                }
+            }
+            if let Some(minutes) = round {
+                days = days.map(|d| round_duration(d, minutes));
+            }
+            days.iter().any(|d| !d.is_zero()).then_some((task, days))
+        })
+        .collect()
+}
+
+/// Sums each task's total time, rounded as `list` would, under the local ISO week it was
+/// created in
+///
+/// Keyed by ISO year and week number, so weeks sort chronologically and a week spanning
+/// New Year stays in one piece. Weeks without any time are left out.
+fn bucket_iso_weeks(
+    tasks: &[Task],
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+) -> BTreeMap<(i32, u32), Duration> {
+    bucket_iso_weeks_in(tasks, round, round_sessions, &Local)
+}
+
+/// Sums each task's total time by ISO week, as [`bucket_iso_weeks`] does, using the weeks of
+/// the time zone `tz`
+fn bucket_iso_weeks_in<Tz: TimeZone>(
+    tasks: &[Task],
+    round: Option<u64>,
+    round_sessions: Option<u64>,
+    tz: &Tz,
+) -> BTreeMap<(i32, u32), Duration> {
+    let options = DisplayOptions {
+        round_sessions,
+        round,
+        ..Default::default()
+    };
+    let mut weeks = BTreeMap::new();
+    for task in tasks {
+        let total = task_duration(task, &options);
+        if total.is_zero() {
+            continue;
+        }
+        let week = task.created_at.with_timezone(tz).date_naive().iso_week();
+        *weeks
+            .entry((week.year(), week.week()))
+            .or_insert(Duration::ZERO) += total;
+    }
+    weeks
+}
+
+/// Gets the Monday of an ISO week
+fn iso_week_monday(year: i32, week: u32) -> NaiveDate {
+    NaiveDate::from_isoywd_opt(year, week, chrono::Weekday::Mon)
+        .expect("ISO weeks come from real dates")
+}
+
+#[cfg(test)]
+mod report_tests;
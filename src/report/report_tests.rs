@@ -0,0 +1,203 @@
+use super::*;
+use crate::task::WorkSession;
+use chrono::{DateTime, Utc};
+
+fn at(timestamp: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .unwrap()
+        .with_timezone(&Utc)
+}
+
+fn task_with_sessions(label: &str, sessions: &[(&str, u64)]) -> Task {
+    let mut task = Task::new(label.to_string(), Vec::new());
+    task.pause().unwrap();
+    task.created_at = at(sessions[0].0);
+    task.sessions = sessions
+        .iter()
+        .map(|&(started_at, minutes)| WorkSession {
+            started_at: at(started_at),
+            duration: Duration::from_secs(minutes * 60),
+            clock_skew: None,
+        })
+        .collect();
+    task.accumulated_duration = task.sessions.iter().map(|s| s.duration).sum();
+    task
+}
+
+/// Tasks with time on Monday, Wednesday and Friday of the week of 2025-10-27,
+/// plus a session from the week before
+fn fixture() -> Vec<Task> {
+    vec![
+        task_with_sessions(
+            "Write docs",
+            &[("2025-10-27T09:00:00Z", 90), ("2025-10-29T14:00:00Z", 30)],
+        ),
+        task_with_sessions(
+            "Review",
+            &[("2025-10-24T10:00:00Z", 45), ("2025-10-31T16:00:00Z", 120)],
+        ),
+    ]
+}
+
+#[test]
+fn test_week_start() {
+    let monday = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap();
+    assert_eq!(week_start(monday), monday);
+    assert_eq!(
+        week_start(NaiveDate::from_ymd_opt(2025, 11, 2).unwrap()),
+        monday
+    );
+    assert_eq!(
+        week_start(NaiveDate::from_ymd_opt(2025, 10, 29).unwrap()),
+        monday
+    );
+}
+
+#[test]
+fn test_weekly_report_grid() {
+    let monday = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap();
+    let report = weekly_report(&fixture(), monday, None, None);
+    let lines: Vec<&str> = report.lines().collect();
+
+    assert_eq!(
+        lines,
+        vec![
+            "Week of 2025-10-27",
+            "Task          Mon  Tue  Wed  Thu  Fri  Sat  Sun  Total",
+            "Write docs  1h30m    -  30m    -    -    -    -     2h",
+            "Review          -    -    -    -   2h    -    -     2h",
+            "------------------------------------------------------",
+            "Total       1h30m    -  30m    -   2h    -    -     4h",
+        ]
+    );
+}
+
+#[test]
+fn test_daily_report_lists_days_with_time() {
+    let monday = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap();
+    let report = daily_report(&fixture(), monday, None, None);
+
+    assert_eq!(
+        report,
+        "Week of 2025-10-27\n\
+         Mon 2025-10-27  1h 30m 0s\n\
+         Wed 2025-10-29  30m 0s\n\
+         Fri 2025-10-31  2h 0m 0s\n\
+         Total: 4h 0m 0s"
+    );
+}
+
+#[test]
+fn test_reports_without_time_in_week() {
+    let monday = NaiveDate::from_ymd_opt(2025, 11, 3).unwrap();
+
+    assert_eq!(
+        weekly_report(&fixture(), monday, None, None),
+        "Week of 2025-11-03\nNo time tracked this week"
+    );
+    assert_eq!(
+        daily_report(&[], monday, None, None),
+        "Week of 2025-11-03\nNo time tracked this week"
+    );
+}
+
+#[test]
+fn test_report_json() {
+    let monday = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap();
+    let json = report_json(&fixture(), monday, None, None, false).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(value["week_of"], "2025-10-27");
+    assert_eq!(value["tasks"][0]["label"], "Write docs");
+    assert_eq!(
+        value["tasks"][0]["days"],
+        serde_json::json!([5400, 0, 1800, 0, 0, 0, 0])
+    );
+    assert_eq!(value["tasks"][0]["total_iso8601"], "PT2H");
+    assert_eq!(
+        value["day_totals"],
+        serde_json::json!([5400, 0, 1800, 0, 7200, 0, 0])
+    );
+    assert_eq!(value["total_seconds"], 14400);
+}
+
+#[test]
+fn test_weekly_report_rounds_each_day_up() {
+    let monday = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap();
+    let tasks = vec![task_with_sessions(
+        "Billable",
+        &[("2025-10-27T09:00:00Z", 7), ("2025-10-28T09:00:00Z", 15)],
+    )];
+
+    let report = weekly_report(&tasks, monday, Some(15), None);
+    assert!(report.contains("Billable  15m  15m    -    -    -    -    -    30m"));
+
+    let json = report_json(&tasks, monday, Some(15), None, false).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["total_seconds"], 1800);
+}
+
+#[test]
+fn test_reports_round_each_session_up() {
+    let monday = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap();
+    // Two 7 minute sessions on Monday round to 15 minutes each
+    let tasks = vec![task_with_sessions(
+        "Billable",
+        &[("2025-10-27T09:00:00Z", 7), ("2025-10-27T11:00:00Z", 7)],
+    )];
+
+    let report = weekly_report(&tasks, monday, None, Some(15));
+    assert!(report.contains("Billable  30m    -    -    -    -    -    -    30m"));
+
+    let json = report_json(&tasks, monday, None, Some(15), false).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(value["total_seconds"], 1800);
+
+    assert!(iso_week_report(&tasks, None, Some(15)).ends_with("Total: 30m 0s"));
+    // Rounding the day's total instead only gives 15 minutes
+    assert!(daily_report(&tasks, monday, Some(15), None).ends_with("Total: 15m 0s"));
+}
+
+#[test]
+fn test_iso_week_report_totals_by_creation_week() {
+    let mut tasks = fixture();
+    // ISO week 1 of 2025 starts in December 2024
+    tasks.push(task_with_sessions(
+        "Year end",
+        &[("2024-12-31T10:00:00Z", 60)],
+    ));
+
+    assert_eq!(
+        iso_week_report(&tasks, None, None),
+        "2025-W01 (week of 2024-12-30)  1h 0m 0s\n\
+         2025-W43 (week of 2025-10-20)  2h 45m 0s\n\
+         2025-W44 (week of 2025-10-27)  2h 0m 0s\n\
+         Total: 5h 45m 0s"
+    );
+    assert_eq!(iso_week_report(&[], None, None), "No time tracked");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&iso_week_report_json(&tasks, None, None, false).unwrap()).unwrap();
+    assert_eq!(json[1]["week"], 43);
+    assert_eq!(json[1]["week_of"], "2025-10-20");
+    assert_eq!(json[1]["total_seconds"], 9900);
+}
+
+#[test]
+fn test_buckets_use_local_days() {
+    // Sunday 23:30 in UTC-5 is already Monday in UTC
+    let tz = chrono::FixedOffset::west_opt(5 * 60 * 60).unwrap();
+    let tasks = vec![task_with_sessions(
+        "Late night",
+        &[("2025-10-27T04:30:00Z", 60)],
+    )];
+
+    let monday = NaiveDate::from_ymd_opt(2025, 10, 20).unwrap();
+    let rows = bucket_week_in(&tasks, monday, None, None, &tz);
+    assert_eq!(rows[0].1[6], Duration::from_secs(60 * 60));
+    let next_monday = NaiveDate::from_ymd_opt(2025, 10, 27).unwrap();
+    assert!(bucket_week_in(&tasks, next_monday, None, None, &tz).is_empty());
+
+    let weeks = bucket_iso_weeks_in(&tasks, None, None, &tz);
+    assert_eq!(weeks.keys().collect::<Vec<_>>(), vec![&(2025, 43)]);
+}
@@ -0,0 +1,372 @@
+use crate::cli::Cli;
+use crate::display::ColorChoice;
+use crate::duration::parse_duration;
+use crate::task::{StalePolicy, TaskManager, DEFAULT_STORE_NAME, MAX_TASKS, RETAIN_DAYS};
+use age::secrecy::SecretString;
+use clap::ValueEnum;
+use serde::Deserialize;
+use std::fmt;
+use std::fs;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Retries after a failed save, for three attempts in total
+const DEFAULT_SAVE_RETRIES: u32 = 2;
+
+/// Hours a session may run before `status` and `list` suggest the timer was left on
+const DEFAULT_IDLE_WARN_HOURS: u64 = 8;
+
+/// Where the effective value of a setting came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Source {
+    /// Built-in default
+    Default,
+    /// Environment variable with the given name
+    Env(&'static str),
+    /// The `config.toml` file next to the task store
+    Config,
+    /// Command-line flag with the given name
+    Flag(&'static str),
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Default => write!(f, "default"),
+            Source::Env(name) => write!(f, "env: {}", name),
+            Source::Config => write!(f, "config.toml"),
+            Source::Flag(name) => write!(f, "flag: {}", name),
+        }
+    }
+}
+
+/// A resolved setting value together with its source
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Setting<T> {
+    pub(crate) value: T,
+    pub(crate) source: Source,
+}
+
+impl<T> Setting<T> {
+    fn default(value: T) -> Self {
+        Self { value, source: Source::Default }
+    }
+
+    fn env(value: T, name: &'static str) -> Self {
+        Self { value, source: Source::Env(name) }
+    }
+
+    fn config(value: T) -> Self {
+        Self { value, source: Source::Config }
+    }
+
+    fn flag(value: T, name: &'static str) -> Self {
+        Self { value, source: Source::Flag(name) }
+    }
+}
+
+/// Defaults read from `config.toml` in the config directory
+///
+/// Every field is optional; anything left out keeps its built-in default.
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct Config {
+    /// Round each task's total up to this many minutes in `list` and `report`
+    pub(crate) round_minutes: Option<u64>,
+    /// Show statuses as plain text instead of emoji
+    pub(crate) no_emoji: Option<bool>,
+    /// Show timestamps in UTC instead of the local timezone
+    pub(crate) use_utc: Option<bool>,
+    /// Keys that match no setting, kept so they can be reported
+    #[serde(flatten)]
+    unknown: toml::Table,
+}
+
+impl Config {
+    /// Reads the config file at `path`
+    ///
+    /// A missing file means no config. An unreadable or malformed file is reported on stderr
+    /// and ignored, so a bad config never stops a command from running. Unknown keys, e.g.
+    /// typos, are reported one by one while the rest of the file still applies.
+    pub(crate) fn load(path: &Path) -> Self {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                eprintln!("Warning: Ignoring config {}: {}", path.display(), e);
+                return Self::default();
+            },
+        };
+
+        let config = Self::parse(&content).unwrap_or_else(|e| {
+            eprintln!("Warning: Ignoring config {}: {}", path.display(), e);
+            Self::default()
+        });
+        for key in config.unknown_keys() {
+            eprintln!(
+                "Warning: Ignoring unknown key '{}' in config {}",
+                key,
+                path.display()
+            );
+        }
+        config
+    }
+
+    /// Names of the keys in the file that match no setting
+    pub(crate) fn unknown_keys(&self) -> impl Iterator<Item = &str> {
+        self.unknown.keys().map(String::as_str)
+    }
+
+    /// Parses the contents of a config file
+    pub(crate) fn parse(content: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(content)
+    }
+}
+
+/// Fully-resolved settings for a single invocation
+#[derive(Debug, Clone)]
+pub(crate) struct Settings {
+    /// File the task list is stored in
+    pub(crate) store_path: Setting<PathBuf>,
+    /// Command line run when `tt` is invoked without a subcommand
+    pub(crate) default_command: Setting<String>,
+    /// Reject labels with control characters instead of sanitizing them
+    pub(crate) strict_labels: Setting<bool>,
+    /// Number of stored tasks above which old completed tasks are dropped
+    pub(crate) max_tasks: Setting<usize>,
+    /// Days a completed task is kept after creation, even above `max_tasks`
+    pub(crate) retain_days: Setting<u64>,
+    /// Days a paused task may sit idle before it counts as stale; `None` disables the check
+    pub(crate) stale_after_days: Setting<Option<u64>>,
+    /// What to do with stale paused tasks
+    pub(crate) stale_policy: Setting<StalePolicy>,
+    /// Ask before completing a task whose running session is longer than this
+    pub(crate) confirm_long_complete: Setting<Option<Duration>>,
+    /// Times a save is retried after a transient I/O error
+    pub(crate) save_retries: Setting<u32>,
+    /// List running and paused tasks ahead of completed ones
+    pub(crate) float_active: Setting<bool>,
+    /// Warn about sessions running longer than this many hours; 0 disables the warning
+    pub(crate) idle_warn_hours: Setting<u64>,
+    /// Print `status`, `list` and `report` output as JSON
+    pub(crate) json: Setting<bool>,
+    /// Print JSON on a single line instead of indented
+    pub(crate) json_compact: Setting<bool>,
+    /// Round each task's total up to this many minutes unless `--round` is given
+    pub(crate) round_minutes: Setting<Option<u64>>,
+    /// Show timestamps in UTC even without `--utc`
+    pub(crate) utc: Setting<bool>,
+    /// Show statuses as plain text instead of emoji
+    pub(crate) no_emoji: Setting<bool>,
+    /// When to color statuses
+    pub(crate) color: Setting<ColorChoice>,
+    /// Whether stdout is a terminal, which `auto` coloring depends on
+    ///
+    /// Only [`Settings::load`] checks the real stdout, so resolved settings stay predictable.
+    pub(crate) stdout_is_terminal: bool,
+    /// Passphrase used to encrypt the task file at rest
+    pub(crate) encrypt_key: Setting<Option<SecretString>>,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            store_path: Setting::default(TaskManager::default_config_path()),
+            default_command: Setting::default("status".to_string()),
+            strict_labels: Setting::default(false),
+            max_tasks: Setting::default(MAX_TASKS),
+            retain_days: Setting::default(RETAIN_DAYS),
+            stale_after_days: Setting::default(None),
+            stale_policy: Setting::default(StalePolicy::default()),
+            confirm_long_complete: Setting::default(None),
+            save_retries: Setting::default(DEFAULT_SAVE_RETRIES),
+            float_active: Setting::default(true),
+            idle_warn_hours: Setting::default(DEFAULT_IDLE_WARN_HOURS),
+            json: Setting::default(false),
+            json_compact: Setting::default(false),
+            round_minutes: Setting::default(None),
+            utc: Setting::default(false),
+            no_emoji: Setting::default(false),
+            color: Setting::default(ColorChoice::default()),
+            stdout_is_terminal: false,
+            encrypt_key: Setting::default(None),
+        }
+    }
+}
+
+impl Settings {
+    /// Resolves settings from the process environment, the config file and the command line
+    pub(crate) fn load(cli: &Cli) -> Self {
+        let env = |name: &str| std::env::var(name).ok();
+        let config = Config::load(&Self::store_path(&env).value.with_file_name("config.toml"));
+        Self {
+            stdout_is_terminal: std::io::stdout().is_terminal(),
+            ..Self::resolve(cli, env, &config)
+        }
+    }
+
+    /// Resolves settings, looking environment variables up through `env`
+    ///
+    /// Flags take precedence over the environment, which takes precedence over the config
+    /// file, which takes precedence over defaults.
+    pub(crate) fn resolve(
+        cli: &Cli,
+        env: impl Fn(&str) -> Option<String>,
+        config: &Config,
+    ) -> Self {
+        let mut settings = Self {
+            store_path: Self::store_path(&env),
+            default_command: Self::default_command(&env),
+            ..Self::default()
+        };
+
+        if let Some(minutes) = config.round_minutes {
+            settings.round_minutes = Setting::config(Some(minutes));
+        }
+
+        if let Some(no_emoji) = config.no_emoji {
+            settings.no_emoji = Setting::config(no_emoji);
+        }
+
+        if let Some(utc) = config.use_utc {
+            settings.utc = Setting::config(utc);
+        }
+
+        if let Some(max) = parse_env(&env, "TT_MAX_TASKS", |v| {
+            v.parse::<usize>().ok().filter(|&max| max > 0)
+        }) {
+            settings.max_tasks = Setting::env(max, "TT_MAX_TASKS");
+        }
+
+        if let Some(days) = parse_env(&env, "TT_RETAIN_DAYS", |v| v.parse::<u64>().ok()) {
+            settings.retain_days = Setting::env(days, "TT_RETAIN_DAYS");
+        }
+
+        if let Some(days) = parse_env(&env, "TT_STALE_DAYS", |v| v.parse::<u64>().ok()) {
+            settings.stale_after_days = Setting::env(Some(days), "TT_STALE_DAYS");
+        }
+
+        if let Some(policy) = parse_env(&env, "TT_STALE_POLICY", |v| {
+            StalePolicy::from_str(v, true).ok()
+        }) {
+            settings.stale_policy = Setting::env(policy, "TT_STALE_POLICY");
+        }
+        // The policy only says what to do with stale tasks; the age decides which ones are
+        if settings.stale_after_days.value.is_none() && env("TT_STALE_POLICY").is_some() {
+            eprintln!("Warning: TT_STALE_POLICY has no effect unless TT_STALE_DAYS is set");
+        }
+
+        if let Some(threshold) =
+            parse_env(&env, "TT_CONFIRM_LONG_COMPLETE", |v| parse_duration(v).ok())
+        {
+            settings.confirm_long_complete =
+                Setting::env(Some(threshold), "TT_CONFIRM_LONG_COMPLETE");
+        }
+
+        if let Some(retries) = parse_env(&env, "TT_SAVE_RETRIES", |v| v.parse::<u32>().ok()) {
+            settings.save_retries = Setting::env(retries, "TT_SAVE_RETRIES");
+        }
+
+        if let Some(float) = parse_env(&env, "TT_FLOAT_ACTIVE", |v| v.parse::<bool>().ok()) {
+            settings.float_active = Setting::env(float, "TT_FLOAT_ACTIVE");
+        }
+
+        if let Some(hours) = parse_env(&env, "TT_IDLE_WARN_HOURS", |v| v.parse::<u64>().ok()) {
+            settings.idle_warn_hours = Setting::env(hours, "TT_IDLE_WARN_HOURS");
+        }
+
+        if let Some(no_emoji) = parse_env(&env, "TT_NO_EMOJI", parse_flag) {
+            settings.no_emoji = Setting::env(no_emoji, "TT_NO_EMOJI");
+        }
+
+        if let Some(key) = env("TT_ENCRYPT_KEY").filter(|v| !v.is_empty()) {
+            settings.encrypt_key = Setting::env(Some(SecretString::from(key)), "TT_ENCRYPT_KEY");
+        }
+
+        if cli.strict_labels {
+            settings.strict_labels = Setting::flag(true, "--strict-labels");
+        }
+
+        if cli.json {
+            settings.json = Setting::flag(true, "--json");
+        }
+
+        if cli.json_compact {
+            settings.json = Setting::flag(true, "--json-compact");
+            settings.json_compact = Setting::flag(true, "--json-compact");
+        }
+
+        if cli.no_emoji {
+            settings.no_emoji = Setting::flag(true, "--no-emoji");
+        }
+
+        if let Some(color) = cli.color {
+            settings.color = Setting::flag(color, "--color");
+        }
+
+        if let Some(store) = &cli.store {
+            // Relative to the working directory, so it means the same thing after a `cd`
+            let store = std::path::absolute(store).unwrap_or_else(|_| store.clone());
+            settings.store_path = Setting::flag(store, "--store");
+        }
+
+        settings
+    }
+
+    /// Returns true if statuses should be colored
+    pub(crate) fn use_color(&self) -> bool {
+        self.color.value.enabled(self.stdout_is_terminal)
+    }
+
+    /// Gets the command line a bare `tt` runs, from the process environment
+    ///
+    /// Needed before the other settings can be loaded, as the default command may bring
+    /// flags of its own; it doesn't read the config file or warn about anything.
+    pub(crate) fn load_default_command() -> String {
+        Self::default_command(&|name: &str| std::env::var(name).ok()).value
+    }
+
+    /// Resolves the command line run when `tt` is invoked without a subcommand
+    fn default_command(env: &impl Fn(&str) -> Option<String>) -> Setting<String> {
+        match env("TT_DEFAULT_COMMAND").filter(|v| !v.trim().is_empty()) {
+            Some(command) => Setting::env(command, "TT_DEFAULT_COMMAND"),
+            None => Setting::default("status".to_string()),
+        }
+    }
+
+    /// Resolves where the task list is stored, honoring `TT_CONFIG_DIR`
+    fn store_path(env: &impl Fn(&str) -> Option<String>) -> Setting<PathBuf> {
+        match env("TT_CONFIG_DIR") {
+            Some(dir) => Setting::env(PathBuf::from(dir).join(DEFAULT_STORE_NAME), "TT_CONFIG_DIR"),
+            None => Setting::default(TaskManager::default_config_path()),
+        }
+    }
+}
+
+/// Reads and parses an environment variable, warning about (and ignoring) invalid values
+fn parse_env<T>(
+    env: &impl Fn(&str) -> Option<String>,
+    name: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Option<T> {
+    let value = env(name)?;
+    let parsed = parse(value.trim());
+    if parsed.is_none() {
+        eprintln!("Warning: Ignoring invalid {} '{}'", name, value);
+    }
+    parsed
+}
+
+/// Parses an on/off environment value: `true`/`false`, `1`/`0`, `yes`/`no` or `on`/`off`,
+/// ignoring case
+fn parse_flag(value: &str) -> Option<bool> {
+    match value.to_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Some(true),
+        "false" | "0" | "no" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod settings_tests;
@@ -0,0 +1,325 @@
+use super::*;
+use clap::Parser;
+
+fn parse(args: &[&str]) -> Cli {
+    Cli::try_parse_from(std::iter::once("tt").chain(args.iter().copied())).unwrap()
+}
+
+#[test]
+fn test_resolve_uses_defaults_without_env_or_flags() {
+    let settings = Settings::resolve(&parse(&["status"]), |_| None, &Config::default());
+
+    assert_eq!(
+        settings.default_command,
+        Setting::default("status".to_string())
+    );
+    assert_eq!(settings.strict_labels, Setting::default(false));
+    assert_eq!(settings.max_tasks, Setting::default(MAX_TASKS));
+    assert_eq!(settings.store_path.source, Source::Default);
+}
+
+#[test]
+fn test_resolve_reads_environment() {
+    let env = |name: &str| match name {
+        "TT_CONFIG_DIR" => Some("/tmp/tt-settings".to_string()),
+        "TT_DEFAULT_COMMAND" => Some("list --relative".to_string()),
+        _ => None,
+    };
+    let settings = Settings::resolve(&parse(&["status"]), env, &Config::default());
+
+    assert_eq!(
+        settings.store_path,
+        Setting::env(
+            PathBuf::from("/tmp/tt-settings").join("tasks.json"),
+            "TT_CONFIG_DIR"
+        )
+    );
+    assert_eq!(
+        settings.default_command,
+        Setting::env("list --relative".to_string(), "TT_DEFAULT_COMMAND")
+    );
+}
+
+#[test]
+fn test_resolve_ignores_blank_default_command() {
+    let env = |name: &str| (name == "TT_DEFAULT_COMMAND").then(|| "  ".to_string());
+    let settings = Settings::resolve(&parse(&["status"]), env, &Config::default());
+
+    assert_eq!(settings.default_command.source, Source::Default);
+}
+
+#[test]
+fn test_resolve_reads_flags() {
+    let settings = Settings::resolve(
+        &parse(&["--strict-labels", "status"]),
+        |_| None,
+        &Config::default(),
+    );
+
+    assert_eq!(
+        settings.strict_labels,
+        Setting::flag(true, "--strict-labels")
+    );
+}
+
+#[test]
+fn test_resolve_store_flag_overrides_config_dir() {
+    let env = |name: &str| (name == "TT_CONFIG_DIR").then(|| "/tmp/tt-settings".to_string());
+    let settings = Settings::resolve(
+        &parse(&["--store", "/tmp/work/tasks.json", "status"]),
+        env,
+        &Config::default(),
+    );
+    assert_eq!(
+        settings.store_path,
+        Setting::flag(PathBuf::from("/tmp/work/tasks.json"), "--store")
+    );
+
+    // Relative paths are resolved against the working directory
+    let settings = Settings::resolve(&parse(&["--store", "work.json"]), env, &Config::default());
+    assert_eq!(
+        settings.store_path.value,
+        std::env::current_dir().unwrap().join("work.json")
+    );
+}
+
+#[test]
+fn test_source_display() {
+    assert_eq!(Source::Default.to_string(), "default");
+    assert_eq!(
+        Source::Env("TT_CONFIG_DIR").to_string(),
+        "env: TT_CONFIG_DIR"
+    );
+    assert_eq!(
+        Source::Flag("--strict-labels").to_string(),
+        "flag: --strict-labels"
+    );
+}
+
+#[test]
+fn test_resolve_reads_stale_settings() {
+    let env = |name: &str| match name {
+        "TT_STALE_DAYS" => Some("14".to_string()),
+        "TT_STALE_POLICY" => Some("Complete".to_string()),
+        _ => None,
+    };
+    let settings = Settings::resolve(&parse(&["status"]), env, &Config::default());
+
+    assert_eq!(
+        settings.stale_after_days,
+        Setting::env(Some(14), "TT_STALE_DAYS")
+    );
+    assert_eq!(
+        settings.stale_policy,
+        Setting::env(StalePolicy::Complete, "TT_STALE_POLICY")
+    );
+}
+
+#[test]
+fn test_resolve_ignores_invalid_stale_policy() {
+    let env = |name: &str| (name == "TT_STALE_POLICY").then(|| "archive".to_string());
+    let settings = Settings::resolve(&parse(&["status"]), env, &Config::default());
+
+    assert_eq!(settings.stale_policy, Setting::default(StalePolicy::Evict));
+}
+
+#[test]
+fn test_resolve_reads_save_retries() {
+    assert_eq!(Settings::default().save_retries, Setting::default(2));
+
+    let env = |name: &str| (name == "TT_SAVE_RETRIES").then(|| "5".to_string());
+    let settings = Settings::resolve(&parse(&["status"]), env, &Config::default());
+
+    assert_eq!(settings.save_retries, Setting::env(5, "TT_SAVE_RETRIES"));
+}
+
+#[test]
+fn test_resolve_reads_float_active() {
+    let settings = Settings::resolve(&parse(&["list"]), |_| None, &Config::default());
+    assert_eq!(settings.float_active, Setting::default(true));
+
+    let env = |name: &str| (name == "TT_FLOAT_ACTIVE").then(|| "false".to_string());
+    let settings = Settings::resolve(&parse(&["list"]), env, &Config::default());
+    assert_eq!(
+        settings.float_active,
+        Setting::env(false, "TT_FLOAT_ACTIVE")
+    );
+}
+
+#[test]
+fn test_resolve_reads_max_tasks() {
+    let env = |name: &str| (name == "TT_MAX_TASKS").then(|| "50".to_string());
+    let settings = Settings::resolve(&parse(&["list"]), env, &Config::default());
+    assert_eq!(settings.max_tasks, Setting::env(50, "TT_MAX_TASKS"));
+
+    // Zero and non-numbers fall back to the default
+    for value in ["0", "lots"] {
+        let env = |name: &str| (name == "TT_MAX_TASKS").then(|| value.to_string());
+        let settings = Settings::resolve(&parse(&["list"]), env, &Config::default());
+        assert_eq!(settings.max_tasks, Setting::default(MAX_TASKS));
+    }
+}
+
+#[test]
+fn test_resolve_reads_retain_days() {
+    let settings = Settings::resolve(&parse(&["list"]), |_| None, &Config::default());
+    assert_eq!(settings.retain_days, Setting::default(RETAIN_DAYS));
+
+    // Zero turns the retention window off
+    let env = |name: &str| (name == "TT_RETAIN_DAYS").then(|| "0".to_string());
+    let settings = Settings::resolve(&parse(&["list"]), env, &Config::default());
+    assert_eq!(settings.retain_days, Setting::env(0, "TT_RETAIN_DAYS"));
+
+    let env = |name: &str| (name == "TT_RETAIN_DAYS").then(|| "a week".to_string());
+    let settings = Settings::resolve(&parse(&["list"]), env, &Config::default());
+    assert_eq!(settings.retain_days, Setting::default(RETAIN_DAYS));
+}
+
+#[test]
+fn test_resolve_json_compact_implies_json() {
+    let settings = Settings::resolve(&parse(&["list"]), |_| None, &Config::default());
+    assert_eq!(settings.json_compact, Setting::default(false));
+
+    let settings = Settings::resolve(
+        &parse(&["--json-compact", "list"]),
+        |_| None,
+        &Config::default(),
+    );
+    assert_eq!(settings.json, Setting::flag(true, "--json-compact"));
+    assert_eq!(settings.json_compact, Setting::flag(true, "--json-compact"));
+}
+
+#[test]
+fn test_resolve_no_emoji_from_env_and_flag() {
+    let settings = Settings::resolve(&parse(&["list"]), |_| None, &Config::default());
+    assert_eq!(settings.no_emoji, Setting::default(false));
+
+    let env = |name: &str| (name == "TT_NO_EMOJI").then(|| "true".to_string());
+    let settings = Settings::resolve(&parse(&["list"]), env, &Config::default());
+    assert_eq!(settings.no_emoji, Setting::env(true, "TT_NO_EMOJI"));
+
+    for (value, expected) in [("1", true), ("YES", true), ("0", false), ("no", false)] {
+        let env = |name: &str| (name == "TT_NO_EMOJI").then(|| value.to_string());
+        let settings = Settings::resolve(&parse(&["list"]), env, &Config::default());
+        assert_eq!(settings.no_emoji, Setting::env(expected, "TT_NO_EMOJI"));
+    }
+
+    let env = |name: &str| (name == "TT_NO_EMOJI").then(|| "maybe".to_string());
+    let settings = Settings::resolve(&parse(&["list"]), env, &Config::default());
+    assert_eq!(settings.no_emoji, Setting::default(false));
+
+    let settings = Settings::resolve(
+        &parse(&["list", "--no-emoji"]),
+        |_| None,
+        &Config::default(),
+    );
+    assert_eq!(settings.no_emoji, Setting::flag(true, "--no-emoji"));
+}
+
+#[test]
+fn test_resolve_color_from_flag() {
+    let settings = Settings::resolve(&parse(&["list"]), |_| None, &Config::default());
+    assert_eq!(settings.color, Setting::default(ColorChoice::Auto));
+    // Resolved settings never assume a terminal
+    assert!(!settings.use_color());
+
+    let settings = Settings::resolve(
+        &parse(&["list", "--color", "always"]),
+        |_| None,
+        &Config::default(),
+    );
+    assert_eq!(
+        settings.color,
+        Setting::flag(ColorChoice::Always, "--color")
+    );
+    assert!(settings.use_color());
+
+    let settings = Settings {
+        stdout_is_terminal: true,
+        ..Settings::resolve(
+            &parse(&["list", "--color", "never"]),
+            |_| None,
+            &Config::default(),
+        )
+    };
+    assert!(!settings.use_color());
+}
+
+#[test]
+fn test_config_parse() {
+    let config = Config::parse("round_minutes = 15\nuse_utc = true\n").unwrap();
+    assert_eq!(
+        config,
+        Config {
+            round_minutes: Some(15),
+            no_emoji: None,
+            use_utc: Some(true),
+            ..Default::default()
+        }
+    );
+
+    assert_eq!(Config::parse("").unwrap(), Config::default());
+    assert!(Config::parse("round_minutes = \"soon\"").is_err());
+}
+
+#[test]
+fn test_config_parse_keeps_valid_keys_next_to_unknown_ones() {
+    let config = Config::parse("round_minute = 15\nuse_utc = true\n").unwrap();
+    assert_eq!(config.use_utc, Some(true));
+    assert_eq!(config.round_minutes, None);
+    // Typos are reported rather than silently ignored
+    assert_eq!(config.unknown_keys().collect::<Vec<_>>(), ["round_minute"]);
+}
+
+#[test]
+fn test_resolve_applies_config_below_env_and_flags() {
+    let config = Config {
+        round_minutes: Some(15),
+        no_emoji: Some(true),
+        use_utc: Some(true),
+        ..Default::default()
+    };
+
+    let settings = Settings::resolve(&parse(&["list"]), |_| None, &config);
+    assert_eq!(settings.round_minutes, Setting::config(Some(15)));
+    assert_eq!(settings.no_emoji, Setting::config(true));
+    assert_eq!(settings.utc, Setting::config(true));
+
+    let env = |name: &str| (name == "TT_NO_EMOJI").then(|| "false".to_string());
+    let settings = Settings::resolve(&parse(&["list"]), env, &config);
+    assert_eq!(settings.no_emoji, Setting::env(false, "TT_NO_EMOJI"));
+
+    let settings = Settings::resolve(&parse(&["list", "--no-emoji"]), env, &config);
+    assert_eq!(settings.no_emoji, Setting::flag(true, "--no-emoji"));
+}
+
+#[test]
+fn test_config_load_falls_back_to_defaults() {
+    let dir = std::env::temp_dir().join("tt_settings_config_load");
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("config.toml");
+
+    fs::remove_file(&path).ok();
+    assert_eq!(Config::load(&path), Config::default());
+
+    fs::write(&path, "round_minutes = [").unwrap();
+    assert_eq!(Config::load(&path), Config::default());
+
+    fs::write(&path, "no_emoji = true").unwrap();
+    assert_eq!(Config::load(&path).no_emoji, Some(true));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_resolve_reads_idle_warn_hours() {
+    let settings = Settings::resolve(&parse(&["status"]), |_| None, &Config::default());
+    assert_eq!(settings.idle_warn_hours, Setting::default(8));
+
+    let env = |name: &str| (name == "TT_IDLE_WARN_HOURS").then(|| "0".to_string());
+    let settings = Settings::resolve(&parse(&["status"]), env, &Config::default());
+    assert_eq!(
+        settings.idle_warn_hours,
+        Setting::env(0, "TT_IDLE_WARN_HOURS")
+    );
+}
@@ -0,0 +1,51 @@
+use crate::task::TaskError;
+use age::secrecy::SecretString;
+
+/// Header every age-encrypted file starts with, which plain JSON never does
+const ENCRYPTED_MAGIC: &[u8] = b"age-encryption.org/";
+
+/// scrypt work factor (`N = 2^15`) for encrypted saves
+///
+/// age picks about one second of work by default, which every command would pay again on
+/// load. This keeps a command to a fraction of a second while still slowing down guessing.
+const SCRYPT_LOG_N: u8 = 15;
+
+/// Returns true if `data` is an encrypted task file
+pub(crate) fn is_encrypted(data: &[u8]) -> bool {
+    data.starts_with(ENCRYPTED_MAGIC)
+}
+
+/// Encrypts the serialized task list with a passphrase
+pub(crate) fn encrypt(plaintext: &str, passphrase: &SecretString) -> Result<Vec<u8>, TaskError> {
+    let mut recipient = age::scrypt::Recipient::new(passphrase.clone());
+    recipient.set_work_factor(SCRYPT_LOG_N);
+    age::encrypt(&recipient, plaintext.as_bytes()).map_err(|e| TaskError::Encryption {
+        message: format!("Could not encrypt tasks file: {}", e),
+    })
+}
+
+/// Turns the contents of a task file back into JSON, decrypting it if necessary
+///
+/// Plaintext files are returned as-is, so stores written before encryption was enabled keep
+/// loading. An encrypted file without a passphrase, or with the wrong one, is an error.
+pub(crate) fn decode(data: &[u8], passphrase: Option<&SecretString>) -> Result<String, TaskError> {
+    let plaintext = if is_encrypted(data) {
+        let passphrase = passphrase.ok_or_else(|| TaskError::Encryption {
+            message: "Tasks file is encrypted; set TT_ENCRYPT_KEY to open it".to_string(),
+        })?;
+        let identity = age::scrypt::Identity::new(passphrase.clone());
+        age::decrypt(&identity, data).map_err(|_| TaskError::Encryption {
+            message: "Could not decrypt tasks file; check that TT_ENCRYPT_KEY is correct"
+                .to_string(),
+        })?
+    } else {
+        data.to_vec()
+    };
+
+    String::from_utf8(plaintext).map_err(|_| TaskError::Encryption {
+        message: "Tasks file is not valid UTF-8".to_string(),
+    })
+}
+
+#[cfg(test)]
+mod storage_tests;
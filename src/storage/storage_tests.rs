@@ -0,0 +1,45 @@
+use super::*;
+
+fn secret(passphrase: &str) -> SecretString {
+    SecretString::from(passphrase.to_string())
+}
+
+#[test]
+fn test_encrypt_round_trip() {
+    let json = r#"{"tasks":[],"active_task_index":null}"#;
+    let encrypted = encrypt(json, &secret("correct horse")).unwrap();
+
+    assert!(is_encrypted(&encrypted));
+    assert!(!String::from_utf8_lossy(&encrypted).contains("tasks"));
+
+    let decoded = decode(&encrypted, Some(&secret("correct horse"))).unwrap();
+    assert_eq!(decoded, json);
+
+    let err = decode(&encrypted, Some(&secret("wrong horse"))).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("check that TT_ENCRYPT_KEY is correct"));
+
+    let err = decode(&encrypted, None).unwrap_err();
+    assert!(err.to_string().contains("set TT_ENCRYPT_KEY"));
+}
+
+#[test]
+fn test_encrypt_uses_fixed_work_factor() {
+    let encrypted = encrypt("{}", &secret("correct horse")).unwrap();
+    let header = String::from_utf8_lossy(&encrypted);
+    let stanza = header
+        .lines()
+        .find(|line| line.starts_with("-> scrypt "))
+        .unwrap();
+    assert!(stanza.ends_with(&format!(" {}", SCRYPT_LOG_N)));
+}
+
+#[test]
+fn test_decode_plaintext() {
+    let json = r#"{"tasks":[]}"#;
+
+    assert!(!is_encrypted(json.as_bytes()));
+    assert_eq!(decode(json.as_bytes(), None).unwrap(), json);
+    assert_eq!(decode(json.as_bytes(), Some(&secret("key"))).unwrap(), json);
+}
@@ -1,11 +1,23 @@
-use chrono::{DateTime, Utc};
+use crate::display::format_duration;
+use crate::storage;
+use age::secrecy::SecretString;
+use chrono::{DateTime, Local, NaiveDate, TimeZone, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::ffi::{OsStr, OsString};
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
-const MAX_TASKS: usize = 10;
+/// Default number of stored tasks above which old completed tasks are dropped
+pub(crate) const MAX_TASKS: usize = 10;
+
+/// Completed tasks created within this many days are kept even beyond the task limit
+pub(crate) const RETAIN_DAYS: u64 = 7;
+
+/// Name of the task file in the config directory
+pub(crate) const DEFAULT_STORE_NAME: &str = "tasks.json";
 
 /// Represents the current status of a task
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -18,9 +30,198 @@ pub(crate) enum TaskStatus {
     Completed,
 }
 
+impl TaskStatus {
+    /// Plain status name without the symbols used for terminal display
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            TaskStatus::Running => "running",
+            TaskStatus::Paused => "paused",
+            TaskStatus::Completed => "completed",
+        }
+    }
+}
+
+impl std::str::FromStr for TaskStatus {
+    type Err = TaskError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "running" => Ok(TaskStatus::Running),
+            "paused" => Ok(TaskStatus::Paused),
+            "completed" => Ok(TaskStatus::Completed),
+            _ => Err(TaskError::InvalidState {
+                message: format!(
+                    "Invalid status '{}'; expected one of: running, paused, completed",
+                    s
+                ),
+            }),
+        }
+    }
+}
+
+/// A single uninterrupted stretch of work on a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct WorkSession {
+    /// When the session started
+    pub(crate) started_at: DateTime<Utc>,
+    /// How much time the session contributed to the task
+    pub(crate) duration: Duration,
+    /// How far the clock moved back during the session, if it did; such a session counts as 0s
+    #[serde(default)]
+    pub(crate) clock_skew: Option<Duration>,
+}
+
+impl WorkSession {
+    /// Gets the time at which the session's tracked duration ends
+    pub(crate) fn ended_at(&self) -> DateTime<Utc> {
+        self.started_at + chrono::Duration::from_std(self.duration).unwrap_or_default()
+    }
+}
+
+/// Merges sessions separated by less than `gap` into a single session
+///
+/// Sessions are ordered by start time first. A merged session keeps the earliest start and
+/// the sum of the merged durations, so the idle time between them is not counted.
+pub(crate) fn merge_close_sessions(sessions: &[WorkSession], gap: Duration) -> Vec<WorkSession> {
+    let mut sorted = sessions.to_vec();
+    sorted.sort_by_key(|session| session.started_at);
+
+    let mut merged: Vec<WorkSession> = Vec::with_capacity(sorted.len());
+    for session in sorted {
+        if let Some(last) = merged.last_mut() {
+            let idle = session
+                .started_at
+                .signed_duration_since(last.ended_at())
+                .to_std()
+                .unwrap_or(Duration::ZERO);
+
+            if idle < gap {
+                last.duration += session.duration;
+                continue;
+            }
+        }
+        merged.push(session);
+    }
+
+    merged
+}
+
+/// Finds pairs of sessions whose time intervals overlap
+///
+/// Returns index pairs `(i, j)` with `i < j` into the given slice.
+pub(crate) fn detect_overlaps(sessions: &[WorkSession]) -> Vec<(usize, usize)> {
+    let mut overlaps = Vec::new();
+
+    for (i, first) in sessions.iter().enumerate() {
+        for (j, second) in sessions.iter().enumerate().skip(i + 1) {
+            if first.started_at < second.ended_at() && second.started_at < first.ended_at() {
+                overlaps.push((i, j));
+            }
+        }
+    }
+
+    overlaps
+}
+
+/// Removes overlapping time by clipping each later session's start to the earlier one's end
+///
+/// Sessions fully covered by an earlier session are dropped. Returns the trimmed sessions,
+/// ordered by start time, along with the total amount of time that was clipped.
+pub(crate) fn trim_overlaps(sessions: &[WorkSession]) -> (Vec<WorkSession>, Duration) {
+    let mut sorted = sessions.to_vec();
+    sorted.sort_by_key(|session| session.started_at);
+
+    let mut trimmed: Vec<WorkSession> = Vec::with_capacity(sorted.len());
+    let mut clipped = Duration::ZERO;
+
+    for mut session in sorted {
+        if let Some(last) = trimmed.last() {
+            let last_end = last.ended_at();
+            if session.started_at < last_end {
+                let end = session.ended_at();
+                if end <= last_end {
+                    clipped += session.duration;
+                    continue;
+                }
+
+                let overlap = last_end
+                    .signed_duration_since(session.started_at)
+                    .to_std()
+                    .unwrap_or(Duration::ZERO);
+                clipped += overlap;
+                session.duration = session.duration.saturating_sub(overlap);
+                session.started_at = last_end;
+            }
+        }
+        trimmed.push(session);
+    }
+
+    (trimmed, clipped)
+}
+
+/// What happens to paused tasks that have been idle for longer than the stale age
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub(crate) enum StalePolicy {
+    /// Remove stale tasks, discarding their time
+    #[default]
+    Evict,
+    /// Mark stale tasks as completed, keeping their time
+    Complete,
+}
+
+impl std::fmt::Display for StalePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StalePolicy::Evict => write!(f, "evict"),
+            StalePolicy::Complete => write!(f, "complete"),
+        }
+    }
+}
+
+/// How important a task is, used to order listings
+///
+/// Variants are declared from least to most important so they compare in that order.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    clap::ValueEnum,
+)]
+pub(crate) enum Priority {
+    /// Can wait until more important work is done
+    Low,
+    /// The default for new tasks
+    #[default]
+    Normal,
+    /// Should be worked on first
+    High,
+}
+
+impl std::fmt::Display for Priority {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Priority::Low => write!(f, "low"),
+            Priority::Normal => write!(f, "normal"),
+            Priority::High => write!(f, "high"),
+        }
+    }
+}
+
 /// Represents a single task with timing information
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Task {
+    /// Stable identifier that doesn't change when other tasks are deleted or moved
+    ///
+    /// Zero means no ID has been assigned yet, e.g. in a store from an older version.
+    #[serde(default)]
+    pub(crate) id: u64,
     /// User-provided label for the task
     pub(crate) label: String,
     /// Current status of the task
@@ -31,32 +232,113 @@ pub(crate) struct Task {
     pub(crate) started_at: Option<DateTime<Utc>>,
     /// Accumulated duration from all previous sessions
     pub(crate) accumulated_duration: Duration,
+    /// History of finished work sessions
+    #[serde(default)]
+    pub(crate) sessions: Vec<WorkSession>,
+    /// Project the task belongs to, if any
+    #[serde(default)]
+    pub(crate) project: Option<String>,
+    /// When the task was completed (unknown for tasks completed by older versions)
+    #[serde(default)]
+    pub(crate) completed_at: Option<DateTime<Utc>>,
+    /// Free-form tags, e.g. the client the time is billed to
+    #[serde(default)]
+    pub(crate) tags: Vec<String>,
+    /// Free-form note about the work done
+    #[serde(default)]
+    pub(crate) note: Option<String>,
+    /// How important the task is
+    #[serde(default)]
+    pub(crate) priority: Priority,
+    /// Number of times work on the task was started or resumed (0 for tasks from older versions)
+    #[serde(default)]
+    pub(crate) session_count: u32,
+    /// Time the user aims to spend on the task, e.g. a 25 minute pomodoro
+    #[serde(default)]
+    pub(crate) goal: Option<Duration>,
+    /// Whether the user has been told that the goal was reached
+    #[serde(default)]
+    pub(crate) goal_notified: bool,
+    /// How long the user expected the task to take, to compare against the actual time
+    #[serde(default)]
+    pub(crate) estimate: Option<Duration>,
 }
 
 impl Task {
-    /// Creates a new task with the given label and starts it immediately
-    pub(crate) fn new(label: String) -> Self {
-        let now = Utc::now();
+    #[allow(dead_code)]
+    /// Creates a new task with the given label and tags and starts it immediately
+    pub(crate) fn new(label: String, tags: Vec<String>) -> Self {
+        Self::new_at(label, tags, Utc::now())
+    }
+
+    /// Creates a new running task whose work began at `started_at`
+    pub(crate) fn new_at(label: String, tags: Vec<String>, started_at: DateTime<Utc>) -> Self {
         Self {
+            id: 0,
             label,
             status: TaskStatus::Running,
-            created_at: now,
-            started_at: Some(now),
+            created_at: started_at,
+            started_at: Some(started_at),
             accumulated_duration: Duration::ZERO,
+            sessions: Vec::new(),
+            project: None,
+            completed_at: None,
+            tags,
+            note: None,
+            priority: Priority::default(),
+            session_count: 1,
+            goal: None,
+            goal_notified: false,
+            estimate: None,
         }
     }
 
     /// Pauses the task, accumulating the elapsed time since it was started
     pub(crate) fn pause(&mut self) -> Result<(), TaskError> {
+        self.pause_at(Utc::now())
+    }
+
+    /// Pauses the task as of `now`
+    ///
+    /// If the clock moved backward during the session, the session counts as zero, a
+    /// warning is printed and the skew is kept on the session for `info --explain`. A jump
+    /// of more than [`MAX_CLOCK_SKEW`] is an error instead, so the clock can be fixed before
+    /// any time is lost.
+    fn pause_at(&mut self, now: DateTime<Utc>) -> Result<(), TaskError> {
         match self.status {
             TaskStatus::Running => {
                 if let Some(started_at) = self.started_at {
-                    let elapsed = Utc::now()
-                        .signed_duration_since(started_at)
-                        .to_std()
-                        .map_err(|_| TaskError::InvalidDuration)?;
+                    let (elapsed, clock_skew) = match now.signed_duration_since(started_at).to_std()
+                    {
+                        Ok(elapsed) => (elapsed, None),
+                        Err(_) => {
+                            let skew = (started_at - now).to_std().unwrap_or_default();
+                            if skew > MAX_CLOCK_SKEW {
+                                return Err(TaskError::TimeError {
+                                    details: format!(
+                                        "The system clock is {} behind the start of '{}'; \
+                                         fix the clock and try again",
+                                        format_duration(skew),
+                                        self.label
+                                    ),
+                                });
+                            }
+                            eprintln!(
+                                "Warning: The system clock moved back {} while '{}' was \
+                                 running; counting the session as 0s",
+                                format_duration(skew),
+                                self.label
+                            );
+                            (Duration::ZERO, Some(skew))
+                        },
+                    };
 
                     self.accumulated_duration += elapsed;
+                    self.sessions.push(WorkSession {
+                        started_at,
+                        duration: elapsed,
+                        clock_skew,
+                    });
                     self.status = TaskStatus::Paused;
                     self.started_at = None;
                     Ok(())
@@ -77,6 +359,7 @@ impl Task {
             TaskStatus::Paused => {
                 self.status = TaskStatus::Running;
                 self.started_at = Some(Utc::now());
+                self.session_count += 1;
                 Ok(())
             },
             TaskStatus::Running => Err(TaskError::TaskAlreadyRunning),
@@ -91,10 +374,12 @@ impl Task {
             TaskStatus::Running => {
                 self.pause()?;
                 self.status = TaskStatus::Completed;
+                self.completed_at = Some(Utc::now());
                 Ok(())
             },
             TaskStatus::Paused => {
                 self.status = TaskStatus::Completed;
+                self.completed_at = Some(Utc::now());
                 Ok(())
             },
             TaskStatus::Completed => Err(TaskError::TaskCompleted),
@@ -103,17 +388,115 @@ impl Task {
 
     /// Gets the total duration of the task, including current session if running
     pub(crate) fn total_duration(&self) -> Duration {
-        let mut total = self.accumulated_duration;
+        self.accumulated_duration + self.current_session_duration()
+    }
 
-        if let (TaskStatus::Running, Some(started_at)) = (&self.status, self.started_at) {
-            let current_session = Utc::now()
+    /// Gets the time elapsed in the live session, or zero if the task isn't running
+    pub(crate) fn current_session_duration(&self) -> Duration {
+        match (&self.status, self.started_at) {
+            (TaskStatus::Running, Some(started_at)) => Utc::now()
                 .signed_duration_since(started_at)
                 .to_std()
-                .unwrap_or(Duration::ZERO);
-            total += current_session;
+                .unwrap_or(Duration::ZERO),
+            _ => Duration::ZERO,
         }
+    }
 
-        total
+    /// Gets the duration of every session, including the live one if the task is running
+    ///
+    /// Time that isn't covered by a recorded session (e.g. from tasks tracked before session
+    /// history existed) is reported as one extra session so the durations always sum to the
+    /// task's total.
+    pub(crate) fn session_durations(&self) -> Vec<Duration> {
+        let mut durations: Vec<Duration> = self.sessions.iter().map(|s| s.duration).collect();
+
+        let recorded: Duration = durations.iter().sum();
+        let unrecorded = self.accumulated_duration.saturating_sub(recorded);
+        if !unrecorded.is_zero() {
+            durations.push(unrecorded);
+        }
+
+        let live = self
+            .total_duration()
+            .saturating_sub(self.accumulated_duration);
+        if self.is_running() {
+            durations.push(live);
+        }
+
+        durations
+    }
+
+    /// Gets every session with its start time, including the live one if the task is running
+    ///
+    /// Time that isn't covered by a recorded session is reported as one extra session starting
+    /// at the task's creation, mirroring [`Task::session_durations`].
+    pub(crate) fn dated_sessions(&self) -> Vec<WorkSession> {
+        let mut sessions = self.sessions.clone();
+
+        let recorded: Duration = sessions.iter().map(|s| s.duration).sum();
+        let unrecorded = self.accumulated_duration.saturating_sub(recorded);
+        if !unrecorded.is_zero() {
+            sessions.push(WorkSession {
+                started_at: self.created_at,
+                duration: unrecorded,
+                clock_skew: None,
+            });
+        }
+
+        if let (true, Some(started_at)) = (self.is_running(), self.started_at) {
+            let duration = self.current_session_duration();
+            sessions.push(WorkSession { started_at, duration, clock_skew: None });
+        }
+
+        sessions
+    }
+
+    /// Gets the last time the task was worked on
+    ///
+    /// This is now for a running task, otherwise the end of its latest session, falling back
+    /// to its creation time.
+    pub(crate) fn last_activity(&self) -> DateTime<Utc> {
+        if self.is_running() {
+            return Utc::now();
+        }
+
+        self.sessions
+            .iter()
+            .map(WorkSession::ended_at)
+            .chain(std::iter::once(self.created_at))
+            .max()
+            .unwrap_or(self.created_at)
+    }
+
+    /// Returns true if the task was created or worked on during `day` (a local date)
+    ///
+    /// A session counts for every day it spans, so a timer left running overnight counts
+    /// towards today as well.
+    pub(crate) fn worked_on(&self, day: NaiveDate) -> bool {
+        let local_date = |dt: DateTime<Utc>| dt.with_timezone(&Local).date_naive();
+
+        local_date(self.created_at) == day
+            || self
+                .dated_sessions()
+                .iter()
+                .any(|s| local_date(s.started_at) <= day && local_date(s.ended_at()) >= day)
+    }
+
+    /// Returns true if the task is running a session that began before `day` (a local date)
+    pub(crate) fn running_since_before(&self, day: NaiveDate) -> bool {
+        self.is_running()
+            && self
+                .started_at
+                .is_some_and(|started_at| started_at.with_timezone(&Local).date_naive() < day)
+    }
+
+    /// Returns true if the task is paused and hasn't been worked on for longer than `max_age`
+    pub(crate) fn is_stale(&self, max_age: Duration, now: DateTime<Utc>) -> bool {
+        self.is_paused()
+            && now
+                .signed_duration_since(self.last_activity())
+                .to_std()
+                .is_ok_and(|idle| idle > max_age)
     }
 
     /// Returns true if the task is currently running
@@ -133,26 +516,224 @@ impl Task {
 
     /// Sets a new label for the task
     pub(crate) fn set_label(&mut self, new_label: String) -> Result<(), TaskError> {
-        let trimmed = new_label.trim();
+        self.label = validate_label(&new_label, false)?;
+        Ok(())
+    }
+}
+
+/// Replaces control characters in a label with spaces and strips ANSI escape sequences
+pub(crate) fn sanitize_label(label: &str) -> String {
+    let mut sanitized = String::with_capacity(label.len());
+    let mut chars = label.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // CSI sequences (ESC [ ... final byte) cover colors and cursor movement
+            if chars.next_if_eq(&'[').is_some() {
+                for next in chars.by_ref() {
+                    if ('\u{40}'..='\u{7e}').contains(&next) {
+                        break;
+                    }
+                }
+            } else {
+                // Two-character escapes such as ESC c
+                chars.next();
+            }
+        } else if c.is_control() {
+            // Avoid doubling up spaces for sequences like \r\n
+            if !sanitized.ends_with(' ') {
+                sanitized.push(' ');
+            }
+        } else {
+            sanitized.push(c);
+        }
+    }
 
-        if trimmed.is_empty() {
+    sanitized
+}
+
+/// Validates a task label, returning the cleaned label to store
+///
+/// Control characters are replaced by [`sanitize_label`], or rejected outright when `strict`
+/// is set. Empty or whitespace-only labels are always rejected.
+pub(crate) fn validate_label(label: &str, strict: bool) -> Result<String, TaskError> {
+    if strict && label.chars().any(char::is_control) {
+        return Err(TaskError::InvalidState {
+            message: "Task label cannot contain control characters or escape sequences".to_string(),
+        });
+    }
+
+    let sanitized = sanitize_label(label);
+    let trimmed = sanitized.trim();
+
+    if trimmed.is_empty() {
+        return Err(TaskError::InvalidState {
+            message: "Task label cannot be empty or whitespace-only".to_string(),
+        });
+    }
+
+    Ok(trimmed.to_string())
+}
+
+/// Validates task tags, returning the cleaned tags to store
+///
+/// A leading `#` is dropped, so `#client` and `client` are the same tag. Duplicates are
+/// removed; empty tags and tags containing whitespace are rejected.
+pub(crate) fn validate_tags(tags: &[String]) -> Result<Vec<String>, TaskError> {
+    let mut cleaned: Vec<String> = Vec::new();
+
+    for tag in tags {
+        let sanitized = sanitize_label(tag);
+        let tag = sanitized.trim().trim_start_matches('#');
+        if tag.is_empty() {
+            return Err(TaskError::InvalidState {
+                message: "Tags cannot be empty".to_string(),
+            });
+        }
+        if tag.chars().any(char::is_whitespace) {
             return Err(TaskError::InvalidState {
-                message: "Task label cannot be empty or whitespace-only".to_string(),
+                message: format!("Tag '{}' cannot contain whitespace", tag),
             });
         }
+        if !cleaned.iter().any(|t| t == tag) {
+            cleaned.push(tag.to_string());
+        }
+    }
 
-        self.label = trimmed.to_string();
-        Ok(())
+    Ok(cleaned)
+}
+
+/// An inclusive range of local calendar days, open-ended when a bound is missing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct DateRange {
+    since: Option<NaiveDate>,
+    until: Option<NaiveDate>,
+}
+
+impl DateRange {
+    /// Creates a range, rejecting ones whose start is after their end
+    pub(crate) fn new(
+        since: Option<NaiveDate>,
+        until: Option<NaiveDate>,
+    ) -> Result<Self, TaskError> {
+        if let (Some(since), Some(until)) = (since, until)
+            && since > until
+        {
+            return Err(TaskError::InvalidState {
+                message: format!("--since {} is after --until {}", since, until),
+            });
+        }
+
+        Ok(Self { since, until })
+    }
+
+    /// Returns true if the local day of `timestamp` falls within the range
+    pub(crate) fn contains(&self, timestamp: DateTime<Utc>) -> bool {
+        self.contains_in(timestamp, &Local)
+    }
+
+    /// Returns true if the day of `timestamp` in the time zone `tz` falls within the range
+    pub(crate) fn contains_in<Tz: TimeZone>(&self, timestamp: DateTime<Utc>, tz: &Tz) -> bool {
+        let day = timestamp.with_timezone(tz).date_naive();
+        self.since.is_none_or(|since| day >= since) && self.until.is_none_or(|until| day <= until)
+    }
+}
+
+/// Counts of tasks per status and their combined time
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct TaskStats {
+    pub(crate) running: usize,
+    pub(crate) paused: usize,
+    pub(crate) completed: usize,
+    pub(crate) total_tasks: usize,
+    /// Time across all tasks, including live sessions
+    pub(crate) total_duration: Duration,
+}
+
+impl TaskStats {
+    /// Adds up `tasks`, taking each task's time from `duration` (e.g. to apply rounding)
+    pub(crate) fn collect<'a>(
+        tasks: impl IntoIterator<Item = &'a Task>,
+        duration: impl Fn(&Task) -> Duration,
+    ) -> Self {
+        let mut stats = TaskStats::default();
+        for task in tasks {
+            stats.total_tasks += 1;
+            stats.total_duration += duration(task);
+            match task.status {
+                TaskStatus::Running => stats.running += 1,
+                TaskStatus::Paused => stats.paused += 1,
+                TaskStatus::Completed => stats.completed += 1,
+            }
+        }
+        stats
     }
 }
 
 /// Manages multiple tasks and enforces business rules
-#[derive(Debug, Default, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct TaskManager {
     /// List of all tasks
     tasks: Vec<Task>,
     /// Index of the currently active (running or paused) task
     active_task_index: Option<usize>,
+    /// ID given to the next new task; IDs are never reused, even after deletion
+    #[serde(default = "default_next_id")]
+    next_id: u64,
+    /// Reject labels containing control characters instead of sanitizing them
+    #[serde(skip)]
+    strict_labels: bool,
+    /// How many times a failed save is retried after a transient I/O error
+    #[serde(skip)]
+    save_retries: u32,
+    /// Passphrase the task file is encrypted with, if encryption is enabled
+    #[serde(skip)]
+    passphrase: Option<SecretString>,
+    /// Number of stored tasks above which the oldest completed tasks are dropped on load
+    #[serde(skip, default = "default_max_tasks")]
+    max_tasks: usize,
+    /// Completed tasks created within this many days survive cleanup regardless of `max_tasks`
+    #[serde(skip, default = "default_retain_days")]
+    retain_days: u64,
+    /// File tasks are loaded from and saved to; `None` uses [`TaskManager::get_config_path`]
+    #[serde(skip)]
+    store_path: Option<PathBuf>,
+    /// Tasks archived since the last save, added to the archive file once the tasks are saved
+    #[serde(skip)]
+    unsaved_archive: Vec<Task>,
+    /// State last read from or written to the task file, so saving it unchanged can be skipped
+    #[serde(skip)]
+    saved_snapshot: Option<String>,
+}
+
+impl Default for TaskManager {
+    fn default() -> Self {
+        Self {
+            tasks: Vec::new(),
+            active_task_index: None,
+            next_id: default_next_id(),
+            strict_labels: false,
+            save_retries: 0,
+            passphrase: None,
+            max_tasks: MAX_TASKS,
+            retain_days: RETAIN_DAYS,
+            store_path: None,
+            unsaved_archive: Vec::new(),
+            saved_snapshot: None,
+        }
+    }
+}
+
+fn default_next_id() -> u64 {
+    1
+}
+
+fn default_max_tasks() -> usize {
+    MAX_TASKS
+}
+
+fn default_retain_days() -> u64 {
+    RETAIN_DAYS
 }
 
 #[allow(dead_code)]
@@ -162,9 +743,53 @@ impl TaskManager {
         Self::default()
     }
 
+    /// Sets whether labels with control characters are rejected rather than sanitized
+    pub(crate) fn set_strict_labels(&mut self, strict: bool) {
+        self.strict_labels = strict;
+    }
+
+    /// Sets how many times a failed save is retried
+    pub(crate) fn set_save_retries(&mut self, retries: u32) {
+        self.save_retries = retries;
+    }
+
     /// Starts a new task with the given label
     /// If there's already a running task, it will be paused first
     pub(crate) fn start_task(&mut self, label: String) -> Result<usize, TaskError> {
+        self.start_task_with_tags(label, Vec::new())
+    }
+
+    /// Starts a new task with the given tags, pausing the current one if it's running
+    ///
+    /// Returns the 0-based index of the new task.
+    pub(crate) fn start_task_with_tags(
+        &mut self,
+        label: String,
+        tags: Vec<String>,
+    ) -> Result<usize, TaskError> {
+        self.start_task_at(label, tags, Utc::now())
+    }
+
+    /// Starts a new task as if it had been started at `started_at`, e.g. a few minutes ago
+    ///
+    /// Start times in the future are rejected. Returns the 0-based index of the new task.
+    pub(crate) fn start_task_at(
+        &mut self,
+        label: String,
+        tags: Vec<String>,
+        started_at: DateTime<Utc>,
+    ) -> Result<usize, TaskError> {
+        let label = validate_label(&label, self.strict_labels)?;
+        let tags = validate_tags(&tags)?;
+        if started_at > Utc::now() {
+            return Err(TaskError::InvalidState {
+                message: format!(
+                    "Start time {} is in the future",
+                    started_at.format("%Y-%m-%d %H:%M:%S UTC")
+                ),
+            });
+        }
+
         // Pause any currently running task
         if let Some(index) = self.active_task_index
             && self.tasks[index].is_running()
@@ -173,7 +798,8 @@ impl TaskManager {
         }
 
         // Create and add the new task
-        let task = Task::new(label);
+        let mut task = Task::new_at(label, tags, started_at);
+        task.id = self.allocate_id();
         self.tasks.push(task);
         let task_index = self.tasks.len() - 1;
         self.active_task_index = Some(task_index);
@@ -181,6 +807,35 @@ impl TaskManager {
         Ok(task_index)
     }
 
+    /// Rejects starting a task whose label matches a running or paused task exactly
+    ///
+    /// Labels are compared after sanitization, as they would be stored. Completed tasks with
+    /// the same label don't count.
+    pub(crate) fn check_duplicate_label(&self, label: &str) -> Result<(), TaskError> {
+        let label = validate_label(label, self.strict_labels)?;
+        let Some(index) = self
+            .tasks
+            .iter()
+            .position(|task| !task.is_completed() && task.label == label)
+        else {
+            return Ok(());
+        };
+
+        let hint = if self.tasks[index].is_running() {
+            "it is already running".to_string()
+        } else {
+            format!("resume it with `tt resume {}`", index + 1)
+        };
+        Err(TaskError::InvalidState {
+            message: format!(
+                "Task {} is already labelled '{}'; {}, or pass --force to start another",
+                index + 1,
+                label,
+                hint
+            ),
+        })
+    }
+
     /// Pauses the currently active task
     pub(crate) fn pause_current_task(&mut self) -> Result<(), TaskError> {
         match self.active_task_index {
@@ -203,23 +858,105 @@ impl TaskManager {
         }
     }
 
+    /// Resumes a paused task by index (1-based), making it the active task
+    ///
+    /// Any other running task is paused first. Nothing changes if the task can't be resumed.
+    pub(crate) fn resume_task(&mut self, index: usize) -> Result<(), TaskError> {
+        let task_index = self.validate_index(index, "resume")?;
+
+        match self.tasks[task_index].status {
+            TaskStatus::Paused => {},
+            TaskStatus::Running => return Err(TaskError::TaskAlreadyRunning),
+            TaskStatus::Completed => return Err(TaskError::TaskCompleted),
+        }
+
+        if let Some(active) = self.active_task_index
+            && self.tasks[active].is_running()
+        {
+            self.tasks[active].pause()?;
+        }
+
+        self.active_task_index = Some(task_index);
+        self.tasks[task_index].resume()
+    }
+
     /// Completes the currently active task and clears the active task status
-    pub(crate) fn complete_current_task(&mut self) -> Result<(), TaskError> {
+    ///
+    /// Returns the completed task.
+    pub(crate) fn complete_current_task(&mut self) -> Result<&Task, TaskError> {
         match self.active_task_index {
             Some(index) => {
                 self.tasks[index].complete()?;
                 self.active_task_index = None;
-                Ok(())
+                Ok(&self.tasks[index])
             },
             None => Err(TaskError::NoActiveTask),
         }
     }
 
+    /// Completes a running or paused task by index (1-based)
+    ///
+    /// Clears the active task if it was the one completed. Returns the completed task.
+    pub(crate) fn complete_task(&mut self, index: usize) -> Result<&Task, TaskError> {
+        let task_index = self.validate_index(index, "complete")?;
+        self.tasks[task_index].complete()?;
+
+        if self.active_task_index == Some(task_index) {
+            self.active_task_index = None;
+        }
+        Ok(&self.tasks[task_index])
+    }
+
+    /// Limits the active task's live session to at most `cap`, returning the time removed
+    ///
+    /// The session's start is moved forward so it appears to have begun `cap` ago. Paused
+    /// tasks have no live session and are left untouched.
+    pub(crate) fn cap_current_session(&mut self, cap: Duration) -> Result<Duration, TaskError> {
+        let index = self.active_task_index.ok_or(TaskError::NoActiveTask)?;
+        self.cap_session(index + 1, cap)
+    }
+
+    /// Limits the live session of a task by index (1-based) to at most `cap`
+    ///
+    /// Works like [`TaskManager::cap_current_session`] for any task.
+    pub(crate) fn cap_session(
+        &mut self,
+        index: usize,
+        cap: Duration,
+    ) -> Result<Duration, TaskError> {
+        let task_index = self.validate_index(index, "update")?;
+        let task = &mut self.tasks[task_index];
+
+        let excess = task.current_session_duration().saturating_sub(cap);
+        if excess.is_zero() {
+            return Ok(Duration::ZERO);
+        }
+
+        let cap = chrono::Duration::from_std(cap).map_err(|_| TaskError::InvalidDuration)?;
+        task.started_at = Some(Utc::now() - cap);
+        Ok(excess)
+    }
+
     /// Gets a reference to the currently active task
     pub(crate) fn current_task(&self) -> Option<&Task> {
         self.active_task_index.map(|index| &self.tasks[index])
     }
 
+    /// Gets the 0-based position of the currently active task
+    pub(crate) fn active_task_index(&self) -> Option<usize> {
+        self.active_task_index
+    }
+
+    /// Gets the 1-based index of the currently active task
+    pub(crate) fn active_task_number(&self) -> Option<usize> {
+        self.active_task_index.map(|index| index + 1)
+    }
+
+    /// Counts tasks per status and adds up their total time
+    pub(crate) fn stats(&self) -> TaskStats {
+        TaskStats::collect(&self.tasks, Task::total_duration)
+    }
+
     /// Gets all tasks
     pub(crate) fn all_tasks(&self) -> &[Task] {
         &self.tasks
@@ -237,13 +974,203 @@ impl TaskManager {
             .unwrap_or(false)
     }
 
-    /// Rename a task by index (1-based)
-    pub(crate) fn rename_task(
+    /// Assigns a task (1-based index) to a project
+    pub(crate) fn set_project(&mut self, index: usize, project: String) -> Result<(), TaskError> {
+        let task_index = self.validate_index(index, "update")?;
+
+        let project = sanitize_label(&project).trim().to_string();
+        if project.is_empty() {
+            return Err(TaskError::InvalidState {
+                message: "Project name cannot be empty or whitespace-only".to_string(),
+            });
+        }
+
+        self.tasks[task_index].project = Some(project);
+        Ok(())
+    }
+
+    /// Sets the note on a task by index (1-based)
+    ///
+    /// An empty or whitespace-only note clears the existing one. Returns true if a note was set.
+    pub(crate) fn set_note(&mut self, index: usize, text: &str) -> Result<bool, TaskError> {
+        let task_index = self.validate_index(index, "update")?;
+
+        let note = sanitize_label(text).trim().to_string();
+        let is_set = !note.is_empty();
+        self.tasks[task_index].note = is_set.then_some(note);
+        Ok(is_set)
+    }
+
+    /// Sets the priority of a task by index (1-based)
+    pub(crate) fn set_priority(
         &mut self,
         index: usize,
-        new_label: String,
-    ) -> Result<String, TaskError> {
-        // Validate index
+        priority: Priority,
+    ) -> Result<(), TaskError> {
+        let task_index = self.validate_index(index, "update")?;
+        self.tasks[task_index].priority = priority;
+        Ok(())
+    }
+
+    /// Sets the time goal of a task by index (1-based)
+    ///
+    /// The task will be announced again once it reaches the new goal.
+    pub(crate) fn set_goal(&mut self, index: usize, goal: Duration) -> Result<(), TaskError> {
+        let task_index = self.validate_index(index, "update")?;
+        let task = &mut self.tasks[task_index];
+        task.goal = Some(goal);
+        task.goal_notified = false;
+        Ok(())
+    }
+
+    /// Sets or replaces the time estimate of a task by index (1-based)
+    pub(crate) fn set_estimate(
+        &mut self,
+        index: usize,
+        estimate: Duration,
+    ) -> Result<(), TaskError> {
+        let task_index = self.validate_index(index, "update")?;
+        if estimate.is_zero() {
+            return Err(TaskError::InvalidState {
+                message: "Estimate must be greater than zero".to_string(),
+            });
+        }
+
+        self.tasks[task_index].estimate = Some(estimate);
+        Ok(())
+    }
+
+    /// Marks running tasks whose time has reached their goal as notified
+    ///
+    /// Returns the 0-based positions of the tasks that reached their goal since the last check,
+    /// so each goal is announced only once.
+    pub(crate) fn check_goals(&mut self) -> Vec<usize> {
+        let mut reached = Vec::new();
+        for (index, task) in self.tasks.iter_mut().enumerate() {
+            if let Some(goal) = task.goal
+                && task.is_running()
+                && !task.goal_notified
+                && task.total_duration() >= goal
+            {
+                task.goal_notified = true;
+                reached.push(index);
+            }
+        }
+        reached
+    }
+
+    /// Prepends `prefix` to the label of every task in `project`, returning how many changed
+    ///
+    /// All new labels are validated before any task is changed, so a single invalid label
+    /// leaves every task untouched.
+    pub(crate) fn prefix_project_labels(
+        &mut self,
+        project: &str,
+        prefix: &str,
+    ) -> Result<usize, TaskError> {
+        let mut new_labels = Vec::new();
+        for (index, task) in self.tasks.iter().enumerate() {
+            if task.project.as_deref() == Some(project) {
+                let label =
+                    validate_label(&format!("{}{}", prefix, task.label), self.strict_labels)?;
+                new_labels.push((index, label));
+            }
+        }
+
+        for (index, label) in &new_labels {
+            self.tasks[*index].label = label.clone();
+        }
+
+        Ok(new_labels.len())
+    }
+
+    /// Gets a task by index (1-based)
+    pub(crate) fn get_task(&self, index: usize) -> Result<&Task, TaskError> {
+        let task_index = self.validate_index(index, "inspect")?;
+        Ok(&self.tasks[task_index])
+    }
+
+    /// Finds the task with the stable `id`, returning its current 1-based index
+    ///
+    /// An exact match wins. Otherwise `id` may be the leading digits of a single task's ID, as
+    /// shown by [`Self::short_ids`]; leading digits shared by several tasks are an error.
+    pub(crate) fn find_by_id(&self, id: u64) -> Result<usize, TaskError> {
+        if let Some(position) = self.tasks.iter().position(|task| task.id == id) {
+            return Ok(position + 1);
+        }
+
+        let prefix = id.to_string();
+        let matches: Vec<usize> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| task.id.to_string().starts_with(&prefix))
+            .map(|(position, _)| position + 1)
+            .collect();
+        match matches[..] {
+            [index] => Ok(index),
+            [] => Err(TaskError::TaskNotFound { id }),
+            _ => Err(TaskError::InvalidState {
+                message: format!(
+                    "ID {} matches several tasks; use more digits (see `tt list --ids`)",
+                    id
+                ),
+            }),
+        }
+    }
+
+    /// Gives each task's ID in its shortest form that [`Self::find_by_id`] still resolves to
+    /// that task alone, keyed by the full ID
+    ///
+    /// A shortened ID is the leading digits that no other task's ID starts with; when the
+    /// whole ID is the start of another one, such as 1 and 12, the whole ID is kept.
+    pub(crate) fn short_ids(&self) -> HashMap<u64, String> {
+        let ids: Vec<String> = self.tasks.iter().map(|task| task.id.to_string()).collect();
+        let mut short = HashMap::new();
+
+        for (position, id) in ids.iter().enumerate() {
+            let shortest = (1..id.len())
+                .map(|len| &id[..len])
+                .find(|prefix| {
+                    ids.iter()
+                        .enumerate()
+                        .all(|(other, other_id)| other == position || !other_id.starts_with(prefix))
+                })
+                .unwrap_or(id);
+            short.insert(self.tasks[position].id, shortest.to_string());
+        }
+
+        short
+    }
+
+    /// Hands out the next stable task ID
+    fn allocate_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// Gives every task without an ID, or with an ID already used by an earlier task, a new one
+    ///
+    /// Tasks from older stores get IDs in stored order. Imported tasks may carry IDs that
+    /// clash with existing ones, so only the first task keeps a duplicated ID.
+    fn assign_ids(&mut self) {
+        let highest = self.tasks.iter().map(|task| task.id).max().unwrap_or(0);
+        self.next_id = self.next_id.max(highest + 1);
+
+        let mut seen = HashSet::new();
+        for index in 0..self.tasks.len() {
+            let id = self.tasks[index].id;
+            if id == 0 || !seen.insert(id) {
+                let id = self.allocate_id();
+                self.tasks[index].id = id;
+                seen.insert(id);
+            }
+        }
+    }
+
+    /// Validates a 1-based task index and converts it to a 0-based position
+    fn validate_index(&self, index: usize, action: &str) -> Result<usize, TaskError> {
         if index == 0 {
             return Err(TaskError::InvalidState {
                 message: "Task index must be greater than 0".to_string(),
@@ -252,7 +1179,7 @@ impl TaskManager {
 
         if self.tasks.is_empty() {
             return Err(TaskError::InvalidState {
-                message: "No tasks available to rename".to_string(),
+                message: format!("No tasks available to {}", action),
             });
         }
 
@@ -266,17 +1193,130 @@ impl TaskManager {
             });
         }
 
-        let task_index = index - 1; // Convert to 0-based
-        let old_label = self.tasks[task_index].label.clone();
+        Ok(index - 1)
+    }
 
-        // Set the new label (this will validate it's not empty)
-        self.tasks[task_index].set_label(new_label)?;
+    /// Finds the task worked on most recently, returning its 0-based index
+    ///
+    /// A running task counts as worked on now; ties go to the later task.
+    pub(crate) fn last_worked_on(&self) -> Option<usize> {
+        self.tasks
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, task)| task.last_activity())
+            .map(|(index, _)| index)
+    }
 
-        Ok(old_label)
+    /// Finds the task whose label matches `query`, ignoring case, returning its 0-based index
+    ///
+    /// A task whose whole label matches wins; otherwise `query` may be any part of the label.
+    /// Several equally good matches are an error that lists them, so the caller can narrow the
+    /// query down or use an index instead.
+    pub(crate) fn find_by_label(&self, query: &str) -> Result<usize, TaskError> {
+        let query = query.trim();
+        let needle = query.to_lowercase();
+        let matching = |exact: bool| -> Vec<usize> {
+            self.tasks
+                .iter()
+                .enumerate()
+                .filter(|(_, task)| {
+                    let label = task.label.to_lowercase();
+                    if exact {
+                        label == needle
+                    } else {
+                        label.contains(&needle)
+                    }
+                })
+                .map(|(index, _)| index)
+                .collect()
+        };
+
+        let mut candidates = matching(true);
+        if candidates.is_empty() {
+            candidates = matching(false);
+        }
+
+        match candidates.as_slice() {
+            [index] => Ok(*index),
+            [] => Err(TaskError::InvalidState {
+                message: format!("No task label matches '{}'", query),
+            }),
+            _ => {
+                let mut message = format!(
+                    "Several tasks match '{}'; be more specific or use an index:",
+                    query
+                );
+                for index in candidates {
+                    message.push_str(&format!("\n  {}. {}", index + 1, self.tasks[index].label));
+                }
+                Err(TaskError::InvalidState { message })
+            },
+        }
     }
 
-    /// Delete a task by index (1-based)
-    pub(crate) fn delete_task(&mut self, index: usize) -> Result<(), TaskError> {
+    /// Builds the error for an index past the end, listing the valid tasks to pick from
+    fn out_of_bounds_listing_tasks(&self, index: usize) -> TaskError {
+        let mut message = format!(
+            "Task index {} is out of bounds. Valid range: 1-{}\nValid tasks:",
+            index,
+            self.tasks.len()
+        );
+        for (i, task) in self.tasks.iter().enumerate() {
+            message.push_str(&format!("\n  {}. {}", i + 1, task.label));
+        }
+        TaskError::InvalidState { message }
+    }
+
+    /// Moves the task at `from` to position `to` (both 1-based), shifting the tasks in between
+    ///
+    /// The active task keeps pointing at the same task after the move.
+    pub(crate) fn reorder_task(&mut self, from: usize, to: usize) -> Result<(), TaskError> {
+        let from = self.validate_index(from, "reorder")?;
+        let to = self.validate_index(to, "reorder")?;
+
+        let task = self.tasks.remove(from);
+        self.tasks.insert(to, task);
+
+        if let Some(active) = self.active_task_index {
+            self.active_task_index = Some(if active == from {
+                to
+            } else if from < active && active <= to {
+                active - 1
+            } else if to <= active && active < from {
+                active + 1
+            } else {
+                active
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Pauses every running task, returning how many were paused
+    pub(crate) fn pause_all_running(&mut self) -> Result<usize, TaskError> {
+        let mut paused = 0;
+        for task in self.tasks.iter_mut().filter(|task| task.is_running()) {
+            task.pause()?;
+            paused += 1;
+        }
+        Ok(paused)
+    }
+
+    /// Gets all running tasks along with their 0-based index
+    pub(crate) fn running_tasks(&self) -> Vec<(usize, &Task)> {
+        self.tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| task.is_running())
+            .collect()
+    }
+
+    /// Rename a task by index (1-based)
+    pub(crate) fn rename_task(
+        &mut self,
+        index: usize,
+        new_label: String,
+    ) -> Result<String, TaskError> {
         // Validate index
         if index == 0 {
             return Err(TaskError::InvalidState {
@@ -284,15 +1324,276 @@ impl TaskManager {
             });
         }
 
+        if self.tasks.is_empty() {
+            return Err(TaskError::InvalidState {
+                message: "No tasks available to rename".to_string(),
+            });
+        }
+
         if index > self.tasks.len() {
+            return Err(self.out_of_bounds_listing_tasks(index));
+        }
+
+        let task_index = index - 1; // Convert to 0-based
+        let old_label = self.tasks[task_index].label.clone();
+
+        // Set the new label (this will validate it's not empty)
+        let new_label = validate_label(&new_label, self.strict_labels)?;
+        self.tasks[task_index].set_label(new_label)?;
+
+        Ok(old_label)
+    }
+
+    /// Adds time worked without the timer running to a task by index (1-based)
+    ///
+    /// Completed tasks can't be changed.
+    pub(crate) fn add_time(&mut self, index: usize, dur: Duration) -> Result<(), TaskError> {
+        let task_index = self.validate_index(index, "update")?;
+
+        let task = &mut self.tasks[task_index];
+        if task.is_completed() {
+            return Err(TaskError::TaskCompleted);
+        }
+
+        task.accumulated_duration += dur;
+        Ok(())
+    }
+
+    /// Starts a fresh task with the label, tags, project, priority, note, goal and estimate of a
+    /// task by index
+    ///
+    /// The original task is left untouched. Returns the 0-based index of the new task.
+    pub(crate) fn clone_task(&mut self, index: usize) -> Result<usize, TaskError> {
+        let source = &self.tasks[self.validate_index(index, "clone")?];
+        let (label, tags) = (source.label.clone(), source.tags.clone());
+        let (project, priority, note, goal, estimate) = (
+            source.project.clone(),
+            source.priority,
+            source.note.clone(),
+            source.goal,
+            source.estimate,
+        );
+
+        let task_index = self.start_task_with_tags(label, tags)?;
+        let task = &mut self.tasks[task_index];
+        task.project = project;
+        task.priority = priority;
+        task.note = note;
+        task.goal = goal;
+        task.estimate = estimate;
+        Ok(task_index)
+    }
+
+    /// Moves a share (`ratio`, strictly between 0 and 1) of a task's time into a new
+    /// completed task labelled `new_label`
+    ///
+    /// Each recorded session is split in the same proportion, so per-day reports stay
+    /// consistent. The source task (1-based index) must be paused or completed. Returns the
+    /// 0-based index of the new task.
+    pub(crate) fn split_task(
+        &mut self,
+        index: usize,
+        ratio: f64,
+        new_label: &str,
+    ) -> Result<usize, TaskError> {
+        let task_index = self.validate_index(index, "split")?;
+        if !(ratio > 0.0 && ratio < 1.0) {
+            return Err(TaskError::InvalidState {
+                message: format!("Split ratio must be between 0 and 1, got {}", ratio),
+            });
+        }
+        let source = &self.tasks[task_index];
+        if source.is_running() {
             return Err(TaskError::InvalidState {
                 message: format!(
-                    "Task index {} is out of bounds. Valid range: 1-{}",
-                    index,
-                    self.tasks.len()
+                    "Cannot split task '{}' - task is currently running. Please pause it first.",
+                    source.label
                 ),
             });
         }
+        let label = validate_label(new_label, self.strict_labels)?;
+        let id = self.allocate_id();
+
+        let source = &mut self.tasks[task_index];
+        let recorded: Duration = source.sessions.iter().map(|s| s.duration).sum();
+        // Time with no recorded session is split on its own, so the moved total is exactly
+        // the sum of the rounded session shares plus its share of that unrecorded time
+        let mut moved = source
+            .accumulated_duration
+            .saturating_sub(recorded)
+            .mul_f64(ratio);
+        let mut sessions = Vec::new();
+        for session in &mut source.sessions {
+            let share = session.duration.mul_f64(ratio);
+            session.duration -= share;
+            moved += share;
+            sessions.push(WorkSession {
+                started_at: session.started_at,
+                duration: share,
+                clock_skew: session.clock_skew,
+            });
+        }
+        source.accumulated_duration = source.accumulated_duration.saturating_sub(moved);
+
+        let mut task = Task::new_at(label, Vec::new(), source.created_at);
+        task.id = id;
+        task.status = TaskStatus::Completed;
+        task.started_at = None;
+        task.accumulated_duration = moved;
+        task.sessions = sessions;
+        task.session_count = 0;
+        task.completed_at = Some(source.completed_at.unwrap_or_else(Utc::now));
+
+        self.tasks.push(task);
+        Ok(self.tasks.len() - 1)
+    }
+
+    /// Moves all time from task `from` into task `into` (both 1-based), then deletes `from`
+    ///
+    /// The work sessions move along with the time, so reports still place it on the right
+    /// days. Neither task may be running. If `from` was the active task, `into` takes its
+    /// place unless it's completed. Returns the removed `from` task.
+    pub(crate) fn merge_tasks(&mut self, into: usize, from: usize) -> Result<Task, TaskError> {
+        let into_index = self.validate_index(into, "merge")?;
+        let from_index = self.validate_index(from, "merge")?;
+        if into_index == from_index {
+            return Err(TaskError::InvalidState {
+                message: "Cannot merge a task into itself".to_string(),
+            });
+        }
+        for index in [into_index, from_index] {
+            if self.tasks[index].is_running() {
+                return Err(TaskError::InvalidState {
+                    message: format!(
+                        "Cannot merge task '{}' - task is currently running. Please pause it first.",
+                        self.tasks[index].label
+                    ),
+                });
+            }
+        }
+
+        let source = self.tasks.remove(from_index);
+        let into_index = if from_index < into_index {
+            into_index - 1
+        } else {
+            into_index
+        };
+        let moved = source.total_duration();
+
+        let target = &mut self.tasks[into_index];
+        target.accumulated_duration += moved;
+        target.sessions.extend(source.dated_sessions());
+        target.sessions.sort_by_key(|session| session.started_at);
+        target.session_count += source.session_count;
+
+        self.active_task_index = match self.active_task_index {
+            Some(active) if active == from_index => {
+                (!self.tasks[into_index].is_completed()).then_some(into_index)
+            },
+            Some(active) if from_index < active => Some(active - 1),
+            active => active,
+        };
+
+        Ok(source)
+    }
+
+    /// Discards all time tracked on a task by index (1-based), returning the time discarded
+    ///
+    /// Recorded sessions are cleared too. A running task keeps running from now, as its only
+    /// session.
+    pub(crate) fn restart_task(&mut self, index: usize) -> Result<Duration, TaskError> {
+        let task_index = self.validate_index(index, "restart")?;
+        let task = &mut self.tasks[task_index];
+
+        let discarded = task.total_duration();
+        task.accumulated_duration = Duration::ZERO;
+        task.sessions.clear();
+        task.session_count = u32::from(task.is_running());
+        task.goal_notified = false;
+        if task.is_running() {
+            task.started_at = Some(Utc::now());
+        }
+        Ok(discarded)
+    }
+
+    /// Adds tasks from another machine, replacing the current tasks unless `merge` is set
+    ///
+    /// When merging, the active task stays active. Otherwise the last running (or else
+    /// paused) imported task becomes active. Any other running task is paused so only one
+    /// timer runs, and old completed tasks beyond the limit are dropped as on load.
+    pub(crate) fn import_tasks(&mut self, tasks: Vec<Task>, merge: bool) -> Result<(), TaskError> {
+        self.import_tasks_with_active(tasks, None, merge)
+    }
+
+    /// Imports tasks like [`Self::import_tasks`], restoring `active` (a position in `tasks`)
+    /// as the active task unless a merge keeps the current one
+    pub(crate) fn import_tasks_with_active(
+        &mut self,
+        tasks: Vec<Task>,
+        active: Option<usize>,
+        merge: bool,
+    ) -> Result<(), TaskError> {
+        let mut combined = if merge {
+            self.tasks.clone()
+        } else {
+            Vec::new()
+        };
+        let kept_active = if merge { self.active_task_index } else { None };
+        let imported_active = active.map(|index| index + combined.len());
+        combined.extend(tasks);
+
+        let active = kept_active
+            .or(imported_active)
+            .or_else(|| combined.iter().rposition(Task::is_running))
+            .or_else(|| combined.iter().rposition(Task::is_paused));
+        for (index, task) in combined.iter_mut().enumerate() {
+            if Some(index) != active && task.is_running() {
+                task.pause()?;
+            }
+        }
+
+        self.tasks = combined;
+        self.active_task_index = active;
+        self.assign_ids();
+        self.cleanup_old_tasks();
+        Ok(())
+    }
+
+    /// Delete a task by index (1-based)
+    ///
+    /// With `force`, the active task may be deleted while paused; see [`Self::check_deletable`].
+    pub(crate) fn delete_task(&mut self, index: usize, force: bool) -> Result<(), TaskError> {
+        let task_index = self.check_deletable(index, force)?;
+
+        // Remove the task
+        self.tasks.remove(task_index);
+
+        // Update active_task_index
+        if let Some(active_idx) = self.active_task_index {
+            if task_index < active_idx {
+                // Deleted task was before active task, decrement index
+                self.active_task_index = Some(active_idx - 1);
+            } else if task_index == active_idx {
+                // Deleted task was the active task
+                self.active_task_index = None;
+            }
+            // If task_index > active_idx, no change needed
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a task (1-based index) could be deleted, returning its 0-based position
+    ///
+    /// The active task can't be deleted while it is running. It can't be deleted while paused
+    /// either, unless `force` is set because the user confirmed it.
+    pub(crate) fn check_deletable(&self, index: usize, force: bool) -> Result<usize, TaskError> {
+        // Validate index
+        if index == 0 {
+            return Err(TaskError::InvalidState {
+                message: "Task index must be greater than 0".to_string(),
+            });
+        }
 
         if self.tasks.is_empty() {
             return Err(TaskError::InvalidState {
@@ -300,6 +1601,10 @@ impl TaskManager {
             });
         }
 
+        if index > self.tasks.len() {
+            return Err(self.out_of_bounds_listing_tasks(index));
+        }
+
         let task_index = index - 1; // Convert to 0-based
 
         // Check if task is active
@@ -315,32 +1620,17 @@ impl TaskManager {
                         ),
                     });
             }
-            if task.is_paused() {
+            if task.is_paused() && !force {
                 return Err(TaskError::InvalidState {
                         message: format!(
-                            "Cannot delete task '{}' - task is currently paused. Please resume and complete it, or complete it directly.",
+                            "Cannot delete task '{}' - task is currently paused. Complete it first, or confirm the deletion with --yes.",
                             task.label
                         ),
                     });
             }
         }
 
-        // Remove the task
-        self.tasks.remove(task_index);
-
-        // Update active_task_index
-        if let Some(active_idx) = self.active_task_index {
-            if task_index < active_idx {
-                // Deleted task was before active task, decrement index
-                self.active_task_index = Some(active_idx - 1);
-            } else if task_index == active_idx {
-                // Deleted task was the active task
-                self.active_task_index = None;
-            }
-            // If task_index > active_idx, no change needed
-        }
-
-        Ok(())
+        Ok(task_index)
     }
 
     /// Delete all completed tasks
@@ -371,73 +1661,465 @@ impl TaskManager {
         Ok(completed_count)
     }
 
+    /// Merge each task's sessions that are separated by less than `gap`
+    /// Returns the number of sessions that were folded into a neighbour
+    pub(crate) fn tidy_sessions(&mut self, gap: Duration) -> usize {
+        let mut merged_count = 0;
+
+        for task in &mut self.tasks {
+            let merged = merge_close_sessions(&task.sessions, gap);
+            merged_count += task.sessions.len() - merged.len();
+            task.sessions = merged;
+        }
+
+        merged_count
+    }
+
+    /// Lists tasks with overlapping sessions as `(task index, session i, session j)` (0-based)
+    pub(crate) fn session_overlaps(&self) -> Vec<(usize, usize, usize)> {
+        self.tasks
+            .iter()
+            .enumerate()
+            .flat_map(|(task_index, task)| {
+                detect_overlaps(&task.sessions)
+                    .into_iter()
+                    .map(move |(i, j)| (task_index, i, j))
+            })
+            .collect()
+    }
+
+    /// Clip overlapping sessions on every task so no time is counted twice
+    /// The clipped time is also removed from each task's accumulated duration
+    pub(crate) fn trim_session_overlaps(&mut self) -> Duration {
+        (0..self.tasks.len())
+            .map(|position| self.trim_task_overlaps(position))
+            .sum()
+    }
+
+    /// Clip overlapping sessions on the task at `position` (0-based), returning the time clipped
+    pub(crate) fn trim_task_overlaps(&mut self, position: usize) -> Duration {
+        let task = &mut self.tasks[position];
+        let (trimmed, clipped) = trim_overlaps(&task.sessions);
+        task.sessions = trimmed;
+        task.accumulated_duration = task.accumulated_duration.saturating_sub(clipped);
+        clipped
+    }
+
+    /// Remove all running and paused tasks, keeping completed ones
+    pub(crate) fn abort_all(&mut self) -> usize {
+        let before = self.tasks.len();
+        self.tasks.retain(|task| task.is_completed());
+
+        // Completed tasks are never active, so nothing remains to track
+        self.active_task_index = None;
+
+        before - self.tasks.len()
+    }
+
+    /// Remove every task, or every task except the active one when `keep_active` is set
+    ///
+    /// A kept active task ends up as the only task, at index 1.
+    pub(crate) fn clean(&mut self, keep_active: bool) -> usize {
+        let before = self.tasks.len();
+
+        let kept = match self.active_task_index {
+            Some(index) if keep_active => Some(self.tasks.swap_remove(index)),
+            _ => None,
+        };
+
+        self.tasks.clear();
+        self.active_task_index = None;
+        if let Some(task) = kept {
+            self.tasks.push(task);
+            self.active_task_index = Some(0);
+        }
+
+        before - self.tasks.len()
+    }
+
+    /// Fixes timing data that can't have been produced by `tt` itself, e.g. after hand-editing
+    ///
+    /// Returns a description of every repair made, so the caller can warn about them.
+    pub(crate) fn repair(&mut self, now: DateTime<Utc>) -> Vec<String> {
+        let mut repairs = Vec::new();
+
+        for task in &mut self.tasks {
+            match (task.is_running(), task.started_at) {
+                (true, None) => {
+                    task.started_at = Some(now);
+                    repairs.push(format!(
+                        "Task '{}' was running without a start time; restarted its session now",
+                        task.label
+                    ));
+                },
+                (true, Some(started_at)) if started_at > now => {
+                    task.started_at = Some(now);
+                    repairs.push(format!(
+                        "Task '{}' started in the future ({}); restarted its session now",
+                        task.label,
+                        started_at.format("%Y-%m-%d %H:%M:%S UTC")
+                    ));
+                },
+                (false, Some(_)) => {
+                    task.started_at = None;
+                    repairs.push(format!(
+                        "Task '{}' is not running but had a session start time; cleared it",
+                        task.label
+                    ));
+                },
+                _ => {},
+            }
+        }
+
+        if let Some(index) = self.active_task_index
+            && self.tasks.get(index).is_none_or(Task::is_completed)
+        {
+            self.active_task_index = None;
+            repairs.push(format!(
+                "Active task index {} did not point at an in-progress task; cleared it",
+                index + 1
+            ));
+        }
+
+        repairs
+    }
+
     /// Load existing TaskManager from file or create new one
-    pub(crate) fn load_or_create() -> Result<Self, TaskError> {
-        match Self::load_from_file() {
+    ///
+    /// Tasks are kept in `path`. Old completed tasks beyond `max_tasks` are dropped,
+    /// unless they were created within the last `retain_days` days. With a `passphrase`, the
+    /// file is decrypted on load and encrypted on every save. Failing to decrypt is an error rather
+    /// than a reason to start fresh, which would overwrite the encrypted tasks on the next
+    /// save.
+    ///
+    /// A task file that can't be parsed is moved aside to `<file>.corrupt-<timestamp>` before
+    /// starting fresh; its path is returned alongside the manager so the caller can say where
+    /// it went. Only a missing file starts fresh otherwise: one that exists but can't be read
+    /// (e.g. permission denied) is an error, so the next save can't overwrite it.
+    pub(crate) fn load_or_create(
+        path: PathBuf,
+        passphrase: Option<SecretString>,
+        max_tasks: usize,
+        retain_days: u64,
+    ) -> Result<(Self, Option<PathBuf>), TaskError> {
+        let mut backup = None;
+        let mut manager = match Self::load_from_file(&path, passphrase.as_ref()) {
             Ok(mut manager) => {
+                manager.max_tasks = max_tasks;
+                manager.retain_days = retain_days;
                 manager.cleanup_old_tasks();
-                Ok(manager)
+                manager
             },
-            Err(_) => Ok(Self::new()),
-        }
+            Err(TaskError::SerializationError(_)) => {
+                backup = Some(Self::back_up_corrupt_file(&path, Utc::now())?);
+                Self::new()
+            },
+            Err(TaskError::IoError(e)) if e.kind() == std::io::ErrorKind::NotFound => Self::new(),
+            Err(e) => return Err(e),
+        };
+
+        manager.max_tasks = max_tasks;
+        manager.retain_days = retain_days;
+        manager.passphrase = passphrase;
+        manager.store_path = Some(path);
+        Ok((manager, backup))
+    }
+
+    /// Moves an unreadable task file aside so starting fresh doesn't overwrite it
+    fn back_up_corrupt_file(path: &Path, now: DateTime<Utc>) -> Result<PathBuf, TaskError> {
+        let mut name = path.file_name().unwrap_or_default().to_os_string();
+        name.push(format!(".corrupt-{}", now.format("%Y%m%dT%H%M%SZ")));
+        let backup = path.with_file_name(name);
+        fs::rename(path, &backup)?;
+        Ok(backup)
     }
 
     /// Load TaskManager from the JSON file
-    fn load_from_file() -> Result<Self, TaskError> {
-        let path = Self::get_config_path()?;
-        let content = fs::read_to_string(path)?;
-        let manager: TaskManager = serde_json::from_str(&content)?;
+    fn load_from_file(path: &Path, passphrase: Option<&SecretString>) -> Result<Self, TaskError> {
+        let data = fs::read(path)?;
+        let content = storage::decode(&data, passphrase)?;
+        let mut manager: TaskManager = serde_json::from_str(&content)?;
+        // A file that isn't encrypted the way it should be is rewritten on the next save
+        if storage::is_encrypted(&data) == passphrase.is_some() {
+            manager.saved_snapshot = Some(content);
+        }
+        manager.assign_ids();
         Ok(manager)
     }
 
     /// Save current TaskManager state to JSON file
-    pub(crate) fn save(&self) -> Result<(), TaskError> {
-        let path = Self::get_config_path()?;
+    ///
+    /// Nothing is written when the state matches the file, which spares the key derivation of
+    /// an encrypted save; returns whether the file was written. Tasks archived since the last
+    /// save are written to the archive only after the task file is saved, so a failed save
+    /// can't leave a task both in the list and the archive.
+    pub(crate) fn save(&mut self) -> Result<bool, TaskError> {
+        let json = self.snapshot()?;
+        if self.saved_snapshot.as_ref() == Some(&json) {
+            return Ok(false);
+        }
+        self.write_store(&self.get_store_path()?, &json)?;
+        self.saved_snapshot = Some(json);
+
+        if !self.unsaved_archive.is_empty() {
+            let archive = self.archived_tasks()?;
+            self.write_archive(&archive)?;
+            self.unsaved_archive.clear();
+        }
+        Ok(true)
+    }
+
+    /// Serializes the current state, for saving now or restoring later with `undo`
+    pub(crate) fn snapshot(&self) -> Result<String, TaskError> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Saves a snapshot taken before the last change, replacing any earlier one
+    ///
+    /// Only one snapshot is kept, so only the most recent change can be undone.
+    pub(crate) fn save_undo_snapshot(&self, snapshot: &str) -> Result<(), TaskError> {
+        self.write_store(&self.get_undo_path()?, snapshot)
+    }
+
+    /// Restores the state saved by [`TaskManager::save_undo_snapshot`]
+    ///
+    /// The snapshot is consumed, so a second undo has nothing to restore. Returns false if
+    /// there was no snapshot.
+    pub(crate) fn undo(&mut self) -> Result<bool, TaskError> {
+        let path = self.get_undo_path()?;
+        if !path.exists() {
+            return Ok(false);
+        }
+
+        let data = fs::read(&path)?;
+        let content = storage::decode(&data, self.passphrase.as_ref())?;
+        let restored: TaskManager = serde_json::from_str(&content)?;
+        self.tasks = restored.tasks;
+        self.active_task_index = restored.active_task_index;
+        // IDs handed out since the snapshot stay used, so they are never given out twice
+        self.next_id = self.next_id.max(restored.next_id);
+        self.assign_ids();
+        self.unarchive_restored()?;
+
+        fs::remove_file(path)?;
+        Ok(true)
+    }
+
+    /// Takes tasks that are back in the task list out of the archive again
+    ///
+    /// Undoing an archive restores the archived tasks; without this they would also stay in
+    /// the archive and be archived a second time. Tasks are matched by ID and creation time.
+    fn unarchive_restored(&mut self) -> Result<(), TaskError> {
+        let mut archive = self.read_archive_file()?;
+        let before = archive.len();
+        archive.retain(|archived| {
+            archived.id == 0
+                || !self
+                    .tasks
+                    .iter()
+                    .any(|task| task.id == archived.id && task.created_at == archived.created_at)
+        });
+        if archive.len() != before {
+            self.write_archive(&archive)?;
+        }
+        Ok(())
+    }
 
+    /// Moves every completed task to the archive, returning how many were moved
+    ///
+    /// The tasks are added to the end of the archive file, which is created if needed, on
+    /// the next [`Self::save`]. The archive is encrypted like the task file.
+    pub(crate) fn archive_completed(&mut self) -> Result<usize, TaskError> {
+        let completed: Vec<Task> = self
+            .tasks
+            .iter()
+            .filter(|t| t.is_completed())
+            .cloned()
+            .collect();
+        if completed.is_empty() {
+            return Ok(0);
+        }
+        self.unsaved_archive.extend(completed);
+
+        // The active task is never completed, so it only shifts down
+        if let Some(active) = self.active_task_index {
+            let removed_before = self.tasks[..active]
+                .iter()
+                .filter(|t| t.is_completed())
+                .count();
+            self.active_task_index = Some(active - removed_before);
+        }
+        let before = self.tasks.len();
+        self.tasks.retain(|task| !task.is_completed());
+        Ok(before - self.tasks.len())
+    }
+
+    /// Reads the archived tasks, oldest first, including any not saved to the file yet
+    ///
+    /// A missing archive is empty.
+    pub(crate) fn archived_tasks(&self) -> Result<Vec<Task>, TaskError> {
+        let mut archive = self.read_archive_file()?;
+        archive.extend(self.unsaved_archive.iter().cloned());
+        Ok(archive)
+    }
+
+    /// Reads the tasks saved in the archive file
+    fn read_archive_file(&self) -> Result<Vec<Task>, TaskError> {
+        let path = self.get_archive_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let data = fs::read(&path)?;
+        let content = storage::decode(&data, self.passphrase.as_ref())?;
+        Ok(serde_json::from_str(&content)?)
+    }
+
+    /// Replaces the archive file with `archive`
+    fn write_archive(&self, archive: &[Task]) -> Result<(), TaskError> {
+        let json = serde_json::to_string_pretty(archive)?;
+        self.write_store(&self.get_archive_path()?, &json)
+    }
+
+    /// Writes serialized state to `path`, encrypting it if a passphrase is set
+    fn write_store(&self, path: &Path, json: &str) -> Result<(), TaskError> {
         // Ensure the parent directory exists
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent)?;
         }
 
-        // Serialize to JSON
-        let json = serde_json::to_string_pretty(self)?;
+        let contents = match &self.passphrase {
+            Some(passphrase) => storage::encrypt(json, passphrase)?,
+            None => json.as_bytes().to_vec(),
+        };
 
-        // Write to temporary file first for atomicity
-        let temp_path = path.with_extension("tmp");
-        let mut file = fs::File::create(&temp_path)?;
-        file.write_all(json.as_bytes())?;
-        file.sync_all()?;
-
-        // Atomic rename
-        fs::rename(temp_path, path)?;
+        retry_io(self.save_retries, || write_atomically(path, &contents))?;
         Ok(())
     }
 
+    /// Get the path of the undo snapshot, next to the task file
+    fn get_undo_path(&self) -> Result<PathBuf, TaskError> {
+        Ok(companion_path(&self.get_store_path()?, "undo.json"))
+    }
+
+    /// Get the path of the archive of completed tasks, next to the task file
+    pub(crate) fn get_archive_path(&self) -> Result<PathBuf, TaskError> {
+        Ok(companion_path(&self.get_store_path()?, "archive.json"))
+    }
+
+    /// Get the path of the completion journal, next to the task file
+    pub(crate) fn get_journal_path(&self) -> Result<PathBuf, TaskError> {
+        Ok(companion_path(&self.get_store_path()?, "journal.md"))
+    }
+
+    /// Get the path of the task file this manager saves to
+    pub(crate) fn get_store_path(&self) -> Result<PathBuf, TaskError> {
+        match &self.store_path {
+            Some(path) => Ok(path.clone()),
+            None => Self::get_config_path(),
+        }
+    }
+
     /// Get the cross-platform config file path
     fn get_config_path() -> Result<PathBuf, TaskError> {
         // Check for test override first
         if let Ok(test_dir) = std::env::var("TT_CONFIG_DIR") {
-            return Ok(PathBuf::from(test_dir).join("tasks.json"));
+            return Ok(PathBuf::from(test_dir).join(DEFAULT_STORE_NAME));
         }
 
-        let config_dir = dirs::config_dir().ok_or_else(|| {
-            std::io::Error::new(
-                std::io::ErrorKind::NotFound,
-                "Could not find config directory",
-            )
-        })?;
+        Ok(Self::default_config_path())
+    }
+
+    /// Get the platform config file path, ignoring any `TT_CONFIG_DIR` override
+    pub(crate) fn default_config_path() -> PathBuf {
+        Self::default_location().0
+    }
+
+    /// Gets where tasks are stored by default, with a note when that isn't the config directory
+    ///
+    /// Minimal containers may have no config directory; tasks then go to `$HOME/.tt`, or to
+    /// the current directory if `HOME` isn't set either.
+    pub(crate) fn default_location() -> (PathBuf, Option<String>) {
+        default_location_from(dirs::config_dir(), std::env::var_os("HOME"))
+    }
 
-        let tt_dir = config_dir.join("tt");
-        Ok(tt_dir.join("tasks.json"))
+    /// Gets paused tasks idle for longer than `max_age`, least recently active first
+    ///
+    /// Each task is paired with its 0-based index.
+    pub(crate) fn stale_tasks(&self, max_age: Duration, now: DateTime<Utc>) -> Vec<(usize, &Task)> {
+        let mut stale: Vec<(usize, &Task)> = self
+            .tasks
+            .iter()
+            .enumerate()
+            .filter(|(_, task)| task.is_stale(max_age, now))
+            .collect();
+        stale.sort_by_key(|(_, task)| task.last_activity());
+        stale
     }
 
-    /// Remove oldest completed tasks if we have more than 10 total tasks
+    /// Handles paused tasks whose last activity is more than `max_age` before `now`
+    ///
+    /// Depending on `policy`, stale tasks are either removed or marked as completed.
+    /// Returns the number of tasks affected.
+    pub(crate) fn prune_stale_tasks(
+        &mut self,
+        max_age: Duration,
+        policy: StalePolicy,
+        now: DateTime<Utc>,
+    ) -> usize {
+        let is_stale = |task: &Task| task.is_stale(max_age, now);
+
+        match policy {
+            StalePolicy::Complete => {
+                let mut count = 0;
+                for task in self.tasks.iter_mut().filter(|task| is_stale(task)) {
+                    task.status = TaskStatus::Completed;
+                    task.completed_at = Some(now);
+                    count += 1;
+                }
+                // A completed task can no longer be the active one
+                if let Some(index) = self.active_task_index
+                    && self.tasks[index].is_completed()
+                {
+                    self.active_task_index = None;
+                }
+                count
+            },
+            StalePolicy::Evict => {
+                let active_task_id = self.active_task_index;
+                let original_count = self.tasks.len();
+                let mut new_tasks = Vec::with_capacity(original_count);
+                let mut new_active_index = None;
+
+                for (index, task) in std::mem::take(&mut self.tasks).into_iter().enumerate() {
+                    if is_stale(&task) {
+                        continue;
+                    }
+                    if Some(index) == active_task_id {
+                        new_active_index = Some(new_tasks.len());
+                    }
+                    new_tasks.push(task);
+                }
+
+                let removed = original_count - new_tasks.len();
+                self.tasks = new_tasks;
+                self.active_task_index = new_active_index;
+                removed
+            },
+        }
+    }
+
+    /// Remove oldest completed tasks if we have more than `max_tasks` total tasks
+    ///
+    /// Every running and paused task is kept, not just the active one, even if that leaves
+    /// more than `max_tasks`; only completed tasks are ever dropped. Completed tasks created
+    /// within the last `retain_days` days are kept the same way.
     fn cleanup_old_tasks(&mut self) {
-        if self.tasks.len() <= MAX_TASKS {
+        if self.tasks.len() <= self.max_tasks {
             return;
         }
 
-        // Separate active and completed tasks
+        // Separate in-progress (running or paused) and completed tasks
         let active_task_id = self.active_task_index;
         let mut active_tasks = Vec::new();
         let mut completed_tasks = Vec::new();
@@ -453,7 +2135,7 @@ impl TaskManager {
         // Sort completed tasks by creation time (oldest first)
         completed_tasks.sort_by(|a, b| a.1.created_at.cmp(&b.1.created_at));
 
-        // Keep active tasks + newest completed tasks up to MAX_TASKS
+        // Keep active tasks + newest completed tasks up to max_tasks
         let mut new_tasks = Vec::new();
         let mut new_active_index = None;
 
@@ -465,11 +2147,20 @@ impl TaskManager {
             new_tasks.push(task);
         }
 
-        // Add newest completed tasks
-        let remaining_slots = MAX_TASKS.saturating_sub(new_tasks.len());
-        let keep_completed = completed_tasks.len().saturating_sub(remaining_slots);
-
-        for (_, task) in completed_tasks.into_iter().skip(keep_completed) {
+        // Drop the oldest completed tasks that don't fit in the remaining slots, but only those
+        // created before the retention window, so the store may stay above max_tasks
+        let retain_since = Utc::now() - chrono::Duration::days(self.retain_days as i64);
+        let prunable = completed_tasks
+            .iter()
+            .take_while(|(_, task)| task.created_at < retain_since)
+            .count();
+        let remaining_slots = self.max_tasks.saturating_sub(new_tasks.len());
+        let drop_count = completed_tasks
+            .len()
+            .saturating_sub(remaining_slots)
+            .min(prunable);
+
+        for (_, task) in completed_tasks.into_iter().skip(drop_count) {
             new_tasks.push(task);
         }
 
@@ -478,6 +2169,120 @@ impl TaskManager {
     }
 }
 
+/// How far the clock may have moved backward during a session before pausing refuses it
+const MAX_CLOCK_SKEW: Duration = Duration::from_secs(60 * 60);
+
+/// Delay before the first retry of a failed save, doubled for each further retry
+const SAVE_RETRY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Parses tasks to import: a JSON array of tasks, or a whole task file
+///
+/// A whole task file also gives the position of its active task, unless that task is
+/// missing or completed.
+pub(crate) fn parse_task_file(content: &str) -> Result<(Vec<Task>, Option<usize>), TaskError> {
+    let value: serde_json::Value = serde_json::from_str(content)?;
+    // Accept a copied tasks.json (or `export --full`) as well as a bare array
+    if value.get("tasks").is_none() {
+        return Ok((serde_json::from_value(value)?, None));
+    }
+
+    let file: TaskManager = serde_json::from_value(value)?;
+    let active = file.active_task_index.filter(|&index| {
+        file.tasks
+            .get(index)
+            .is_some_and(|task| !task.is_completed())
+    });
+    Ok((file.tasks, active))
+}
+
+/// Picks the default task file from the platform config directory and `HOME`, falling back
+/// in that order
+fn default_location_from(
+    config_dir: Option<PathBuf>,
+    home: Option<OsString>,
+) -> (PathBuf, Option<String>) {
+    if let Some(config_dir) = config_dir {
+        return (config_dir.join("tt").join(DEFAULT_STORE_NAME), None);
+    }
+
+    match home.filter(|home| !home.is_empty()) {
+        Some(home) => {
+            let path = PathBuf::from(home).join(".tt").join(DEFAULT_STORE_NAME);
+            let note = format!(
+                "No config directory found; storing tasks in {}",
+                path.display()
+            );
+            (path, Some(note))
+        },
+        None => (
+            PathBuf::from(DEFAULT_STORE_NAME),
+            Some("No config directory or HOME found; storing tasks in ./tasks.json".to_string()),
+        ),
+    }
+}
+
+/// Builds the path of a file kept next to the task file at `store`, such as its undo snapshot
+///
+/// The default `tasks.json` keeps the plain `name`. Any other task file's companions start
+/// with its stem, so several task files in one directory keep their own: `work.json` gets
+/// `work.undo.json` for a `name` of `undo.json`.
+fn companion_path(store: &Path, name: &str) -> PathBuf {
+    if store.file_name() == Some(OsStr::new(DEFAULT_STORE_NAME)) {
+        return store.with_file_name(name);
+    }
+    let mut file_name = store.file_stem().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(name);
+    store.with_file_name(file_name)
+}
+
+/// Writes `contents` to a temporary file next to `path`, then renames it into place
+fn write_atomically(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let temp_path = path.with_extension("tmp");
+    let mut file = fs::File::create(&temp_path)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+
+    fs::rename(temp_path, path)
+}
+
+/// Returns true for I/O errors that may succeed when retried, e.g. on network filesystems
+fn is_retryable(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind;
+
+    matches!(
+        error.kind(),
+        ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::TimedOut
+            | ErrorKind::ResourceBusy
+            | ErrorKind::StaleNetworkFileHandle
+    )
+}
+
+/// Runs `operation`, retrying up to `retries` more times with exponential backoff
+///
+/// Only transient errors are retried; anything else (such as permission denied) is
+/// returned immediately.
+fn retry_io<T>(
+    retries: u32,
+    mut operation: impl FnMut() -> std::io::Result<T>,
+) -> std::io::Result<T> {
+    let mut backoff = SAVE_RETRY_BACKOFF;
+    let mut attempt = 0;
+
+    loop {
+        match operation() {
+            Err(error) if attempt < retries && is_retryable(&error) => {
+                std::thread::sleep(backoff);
+                backoff *= 2;
+                attempt += 1;
+            },
+            result => return result,
+        }
+    }
+}
+
 #[allow(dead_code)]
 /// Errors that can occur during task operations
 #[derive(thiserror::Error, Debug)]
@@ -509,19 +2314,23 @@ pub(crate) enum TaskError {
 
     /// Task not found with the given identifier
     #[error("Task not found with id: {id}")]
-    TaskNotFound { id: usize },
+    TaskNotFound { id: u64 },
 
     /// I/O error occurred during task operations
-    #[error("I/O error")]
+    #[error("I/O error: {0}")]
     IoError(#[from] std::io::Error),
 
     /// Serialization error
-    #[error("Serialization error")]
+    #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
     /// Time-related error
     #[error("Time calculation error: {details}")]
     TimeError { details: String },
+
+    /// The task file could not be encrypted or decrypted
+    #[error("{message}")]
+    Encryption { message: String },
 }
 
 #[cfg(test)]
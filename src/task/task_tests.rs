@@ -1,10 +1,11 @@
 use super::*;
+use chrono::{FixedOffset, NaiveDateTime};
 use std::thread;
 use std::time::Duration as StdDuration;
 
 #[test]
 fn test_new_task_creation() {
-    let task = Task::new("Test Task".to_string());
+    let task = Task::new("Test Task".to_string(), Vec::new());
 
     assert_eq!(task.label, "Test Task");
     assert_eq!(task.status, TaskStatus::Running);
@@ -17,7 +18,7 @@ fn test_new_task_creation() {
 
 #[test]
 fn test_task_pause() {
-    let mut task = Task::new("Test Task".to_string());
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
 
     // Small delay to ensure measurable duration
     thread::sleep(StdDuration::from_millis(10));
@@ -31,9 +32,33 @@ fn test_task_pause() {
     assert!(!task.is_running());
 }
 
+#[test]
+fn test_task_pause_after_clock_moved_back() {
+    let started_at = Utc::now();
+    let mut task = Task::new_at("Test Task".to_string(), Vec::new(), started_at);
+
+    // A small jump counts the session as zero instead of failing
+    task.pause_at(started_at - chrono::Duration::minutes(10))
+        .unwrap();
+    assert!(task.is_paused());
+    assert_eq!(task.accumulated_duration, Duration::ZERO);
+    assert_eq!(task.sessions[0].duration, Duration::ZERO);
+    assert_eq!(task.sessions[0].clock_skew, Some(Duration::from_secs(600)));
+
+    // A large one is refused, leaving the session running
+    let mut task = Task::new_at("Test Task".to_string(), Vec::new(), started_at);
+    let result = task.pause_at(started_at - chrono::Duration::hours(2));
+    match result.unwrap_err() {
+        TaskError::TimeError { details } => assert!(details.contains("2h 0m 0s behind")),
+        other => panic!("Expected TimeError, got {:?}", other),
+    }
+    assert!(task.is_running());
+    assert_eq!(task.started_at, Some(started_at));
+}
+
 #[test]
 fn test_task_pause_already_paused() {
-    let mut task = Task::new("Test Task".to_string());
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
     task.pause().unwrap();
 
     let result = task.pause();
@@ -46,7 +71,7 @@ fn test_task_pause_already_paused() {
 
 #[test]
 fn test_task_resume() {
-    let mut task = Task::new("Test Task".to_string());
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
     task.pause().unwrap();
 
     let result = task.resume();
@@ -59,7 +84,7 @@ fn test_task_resume() {
 
 #[test]
 fn test_task_resume_already_running() {
-    let mut task = Task::new("Test Task".to_string());
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
 
     let result = task.resume();
     assert!(result.is_err());
@@ -71,7 +96,7 @@ fn test_task_resume_already_running() {
 
 #[test]
 fn test_task_total_duration() {
-    let mut task = Task::new("Test Task".to_string());
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
 
     // Run for a bit
     thread::sleep(StdDuration::from_millis(10));
@@ -167,7 +192,7 @@ fn test_task_manager_resume_current() {
 
 #[test]
 fn test_task_complete() {
-    let mut task = Task::new("Test Task".to_string());
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
     thread::sleep(StdDuration::from_millis(10));
 
     let result = task.complete();
@@ -213,6 +238,8 @@ fn test_serialize_deserialize_task_manager() {
 #[test]
 fn test_cleanup_old_tasks() {
     let mut manager = TaskManager::new();
+    // Every task here was created just now; only the task limit applies
+    manager.retain_days = 0;
 
     // Create 15 tasks (more than the 10 limit)
     for i in 0..15 {
@@ -243,9 +270,111 @@ fn test_cleanup_old_tasks() {
     assert!(has_recent_tasks);
 }
 
+#[test]
+fn test_cleanup_old_tasks_uses_configured_limit() {
+    let mut manager = TaskManager::new();
+    manager.retain_days = 0;
+    for i in 0..15 {
+        manager.start_task(format!("Task {}", i)).unwrap();
+        manager.complete_current_task().unwrap();
+    }
+
+    manager.max_tasks = 20;
+    manager.cleanup_old_tasks();
+    assert_eq!(manager.tasks.len(), 15);
+
+    manager.max_tasks = 3;
+    manager.cleanup_old_tasks();
+    let labels: Vec<&str> = manager.tasks.iter().map(|t| t.label.as_str()).collect();
+    assert_eq!(labels, vec!["Task 12", "Task 13", "Task 14"]);
+}
+
+#[test]
+fn test_cleanup_keeps_every_paused_task() {
+    let mut manager = TaskManager::new();
+    manager.retain_days = 0;
+    for i in 0..5 {
+        manager.start_task(format!("Done {}", i)).unwrap();
+        manager.complete_current_task().unwrap();
+    }
+    for i in 0..8 {
+        manager.start_task(format!("Paused {}", i)).unwrap();
+        manager.pause_current_task().unwrap();
+    }
+    // Only the last paused task is the active one
+    assert_eq!(manager.active_task_index, Some(12));
+
+    manager.cleanup_old_tasks();
+
+    assert_eq!(manager.tasks.len(), 10);
+    assert_eq!(manager.tasks.iter().filter(|t| t.is_paused()).count(), 8);
+    let completed: Vec<&str> = manager
+        .tasks
+        .iter()
+        .filter(|t| t.is_completed())
+        .map(|t| t.label.as_str())
+        .collect();
+    assert_eq!(completed, vec!["Done 3", "Done 4"]);
+    assert_eq!(manager.current_task().unwrap().label, "Paused 7");
+
+    // With more paused tasks than the limit, none of them is dropped
+    manager.max_tasks = 5;
+    manager.cleanup_old_tasks();
+    assert_eq!(manager.tasks.len(), 8);
+    assert!(manager.tasks.iter().all(Task::is_paused));
+}
+
+#[test]
+fn test_cleanup_keeps_recent_completed_tasks() {
+    let mut manager = TaskManager::new();
+    for i in 0..6 {
+        manager.start_task(format!("Old {}", i)).unwrap();
+        manager.complete_current_task().unwrap();
+        let index = manager.tasks.len() - 1;
+        manager.tasks[index].created_at = Utc::now() - chrono::Duration::days(30 - i);
+    }
+    for i in 0..6 {
+        manager.start_task(format!("Recent {}", i)).unwrap();
+        manager.complete_current_task().unwrap();
+        let index = manager.tasks.len() - 1;
+        manager.tasks[index].created_at = Utc::now() - chrono::Duration::days(6 - i);
+    }
+
+    // Only old tasks make room for the limit of 10
+    manager.cleanup_old_tasks();
+    let labels: Vec<&str> = manager.tasks.iter().map(|t| t.label.as_str()).collect();
+    assert_eq!(
+        labels,
+        vec![
+            "Old 2", "Old 3", "Old 4", "Old 5", "Recent 0", "Recent 1", "Recent 2", "Recent 3",
+            "Recent 4", "Recent 5"
+        ]
+    );
+
+    // Recent tasks are kept even when that leaves more than the limit
+    manager.max_tasks = 3;
+    manager.cleanup_old_tasks();
+    assert_eq!(manager.tasks.len(), 6);
+    assert!(manager.tasks.iter().all(|t| t.label.starts_with("Recent")));
+
+    // A shorter window lets the older of them go
+    manager.retain_days = 3;
+    manager.cleanup_old_tasks();
+    let labels: Vec<&str> = manager.tasks.iter().map(|t| t.label.as_str()).collect();
+    assert_eq!(labels, vec!["Recent 3", "Recent 4", "Recent 5"]);
+}
+
+#[test]
+fn test_deserialized_manager_uses_default_limit() {
+    let manager: TaskManager = serde_json::from_str(r#"{ "tasks": [] }"#).unwrap();
+    assert_eq!(manager.max_tasks, MAX_TASKS);
+    assert_eq!(manager.retain_days, RETAIN_DAYS);
+}
+
 #[test]
 fn test_cleanup_preserves_active_task() {
     let mut manager = TaskManager::new();
+    manager.retain_days = 0;
 
     // Create many completed tasks
     for i in 0..12 {
@@ -284,6 +413,49 @@ fn test_get_config_path() {
     assert!(path.to_string_lossy().ends_with("tasks.json"));
 }
 
+#[test]
+fn test_default_location_fallbacks() {
+    let (path, note) = default_location_from(
+        Some(PathBuf::from("/etc/xdg")),
+        Some(OsString::from("/home/ci")),
+    );
+    assert_eq!(path, PathBuf::from("/etc/xdg/tt/tasks.json"));
+    assert!(note.is_none());
+
+    let (path, note) = default_location_from(None, Some(OsString::from("/home/ci")));
+    assert_eq!(path, PathBuf::from("/home/ci/.tt/tasks.json"));
+    assert_eq!(
+        note.unwrap(),
+        "No config directory found; storing tasks in /home/ci/.tt/tasks.json"
+    );
+
+    for home in [None, Some(OsString::new())] {
+        let (path, note) = default_location_from(None, home);
+        assert_eq!(path, PathBuf::from("tasks.json"));
+        assert!(note.unwrap().contains("./tasks.json"));
+    }
+}
+
+#[test]
+fn test_companion_path_uses_store_stem() {
+    // The default task file keeps the plain names
+    let store = PathBuf::from("/home/ci/.config/tt/tasks.json");
+    assert_eq!(
+        companion_path(&store, "archive.json"),
+        PathBuf::from("/home/ci/.config/tt/archive.json")
+    );
+
+    let store = PathBuf::from("/work/client.json");
+    assert_eq!(
+        companion_path(&store, "undo.json"),
+        PathBuf::from("/work/client.undo.json")
+    );
+    assert_eq!(
+        companion_path(&store, "journal.md"),
+        PathBuf::from("/work/client.journal.md")
+    );
+}
+
 #[test]
 fn test_complete_current_task_running() {
     let mut manager = TaskManager::new();
@@ -303,6 +475,51 @@ fn test_complete_current_task_running() {
     assert!(manager.tasks[0].total_duration() > Duration::ZERO);
 }
 
+#[test]
+fn test_complete_task_by_index_keeps_other_active_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Task A".to_string()).unwrap();
+    manager.start_task("Task B".to_string()).unwrap();
+
+    let task = manager.complete_task(1).unwrap();
+    assert_eq!(task.label, "Task A");
+    assert!(manager.tasks[0].is_completed());
+
+    // Task B is still the active, running task
+    assert_eq!(manager.active_task_index, Some(1));
+    assert!(manager.tasks[1].is_running());
+}
+
+#[test]
+fn test_complete_task_by_index_clears_active_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Task A".to_string()).unwrap();
+
+    manager.complete_task(1).unwrap();
+    assert_eq!(manager.active_task_index, None);
+}
+
+#[test]
+fn test_complete_task_by_index_rejects_invalid_or_completed() {
+    let mut manager = TaskManager::new();
+    assert!(matches!(
+        manager.complete_task(1),
+        Err(TaskError::InvalidState { .. })
+    ));
+
+    manager.start_task("Task A".to_string()).unwrap();
+    assert!(matches!(
+        manager.complete_task(2),
+        Err(TaskError::InvalidState { .. })
+    ));
+
+    manager.complete_task(1).unwrap();
+    assert!(matches!(
+        manager.complete_task(1),
+        Err(TaskError::TaskCompleted)
+    ));
+}
+
 #[test]
 fn test_complete_current_task_paused() {
     let mut manager = TaskManager::new();
@@ -379,3 +596,1654 @@ fn test_multiple_tasks_with_completion() {
     assert!(manager.current_task().is_some());
     assert_eq!(manager.current_task().unwrap().label, "Task 3");
 }
+
+#[test]
+fn test_abort_all_removes_only_in_progress_tasks() {
+    let mut manager = TaskManager::new();
+
+    // Completed task
+    manager.start_task("Done Task".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+
+    // Paused task
+    manager.start_task("Paused Task".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+
+    // Another completed task
+    manager.start_task("Also Done".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+
+    // Running task
+    manager.start_task("Running Task".to_string()).unwrap();
+
+    let removed = manager.abort_all();
+    assert_eq!(removed, 2);
+
+    assert_eq!(manager.tasks.len(), 2);
+    assert!(manager.tasks.iter().all(|task| task.is_completed()));
+    assert_eq!(manager.tasks[0].label, "Done Task");
+    assert_eq!(manager.tasks[1].label, "Also Done");
+    assert!(manager.current_task().is_none());
+}
+
+#[test]
+fn test_abort_all_with_nothing_in_progress() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Done Task".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+
+    assert_eq!(manager.abort_all(), 0);
+    assert_eq!(manager.tasks.len(), 1);
+}
+
+#[test]
+fn test_get_task_by_index() {
+    let mut manager = TaskManager::new();
+    manager.start_task("First".to_string()).unwrap();
+    manager.start_task("Second".to_string()).unwrap();
+
+    assert_eq!(manager.get_task(2).unwrap().label, "Second");
+    assert!(manager.get_task(0).is_err());
+
+    let err = manager.get_task(3).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid state: Task index 3 is out of bounds. Valid range: 1-2"
+    );
+}
+
+#[test]
+fn test_sanitize_label_strips_newlines_and_ansi_escapes() {
+    let label = "Fix login\nbug \x1b[31murgent\x1b[0m";
+    assert_eq!(sanitize_label(label), "Fix login bug urgent");
+}
+
+#[test]
+fn test_sanitize_label_collapses_crlf_and_tabs() {
+    assert_eq!(sanitize_label("one\r\ntwo\tthree"), "one two three");
+    assert_eq!(sanitize_label("plain label"), "plain label");
+}
+
+#[test]
+fn test_validate_tags() {
+    let tags = ["#acme", "urgent", "acme"].map(String::from);
+    assert_eq!(validate_tags(&tags).unwrap(), vec!["acme", "urgent"]);
+
+    let result = validate_tags(&["two words".to_string()]);
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("cannot contain whitespace"));
+    assert!(validate_tags(&["#".to_string()]).is_err());
+}
+
+#[test]
+fn test_start_task_with_tags() {
+    let mut manager = TaskManager::new();
+    let index = manager
+        .start_task_with_tags("Client call".to_string(), vec!["#acme".to_string()])
+        .unwrap();
+    assert_eq!(manager.tasks[index].tags, vec!["acme"]);
+
+    // Invalid tags leave the store untouched
+    let result = manager.start_task_with_tags("Other".to_string(), vec![" ".to_string()]);
+    assert!(result.is_err());
+    assert_eq!(manager.tasks.len(), 1);
+    assert!(manager.tasks[0].is_running());
+}
+
+#[test]
+fn test_validate_label_strict_rejects_control_characters() {
+    let result = validate_label("bad\nlabel", true);
+    assert!(result.is_err());
+    match result.unwrap_err() {
+        TaskError::InvalidState { message } => {
+            assert!(message.contains("control characters"));
+        },
+        _ => panic!("Expected InvalidState error"),
+    }
+
+    assert_eq!(
+        validate_label("  good label  ", true).unwrap(),
+        "good label"
+    );
+}
+
+#[test]
+fn test_start_task_sanitizes_label() {
+    let mut manager = TaskManager::new();
+    manager
+        .start_task("Deploy\n\x1b[1mrelease\x1b[0m".to_string())
+        .unwrap();
+
+    assert_eq!(manager.current_task().unwrap().label, "Deploy release");
+}
+
+#[test]
+fn test_start_task_strict_labels_rejects_control_characters() {
+    let mut manager = TaskManager::new();
+    manager.set_strict_labels(true);
+
+    let result = manager.start_task("Deploy\nrelease".to_string());
+    assert!(result.is_err());
+    assert_eq!(manager.task_count(), 0);
+}
+
+fn session_at(started_at: DateTime<Utc>, minutes: u64) -> WorkSession {
+    WorkSession {
+        started_at,
+        duration: Duration::from_secs(minutes * 60),
+        clock_skew: None,
+    }
+}
+
+#[test]
+fn test_pause_records_work_session() {
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
+    thread::sleep(StdDuration::from_millis(10));
+    task.pause().unwrap();
+
+    assert_eq!(task.sessions.len(), 1);
+    assert_eq!(task.sessions[0].duration, task.accumulated_duration);
+    assert_eq!(task.sessions[0].started_at, task.created_at);
+
+    task.resume().unwrap();
+    task.complete().unwrap();
+    assert_eq!(task.sessions.len(), 2);
+}
+
+#[test]
+fn test_merge_close_sessions_merges_short_gaps() {
+    let start = Utc::now() - chrono::Duration::hours(2);
+    // 10 minutes of work, a 30 second break, then 5 more minutes
+    let second_start = start + chrono::Duration::seconds(10 * 60 + 30);
+    let sessions = vec![session_at(start, 10), session_at(second_start, 5)];
+
+    let merged = merge_close_sessions(&sessions, Duration::from_secs(60));
+
+    assert_eq!(merged.len(), 1);
+    assert_eq!(merged[0].started_at, start);
+    assert_eq!(merged[0].duration, Duration::from_secs(15 * 60));
+}
+
+#[test]
+fn test_merge_close_sessions_keeps_long_gaps() {
+    let start = Utc::now() - chrono::Duration::hours(2);
+    // 10 minutes of work, a 5 minute break, then 5 more minutes
+    let second_start = start + chrono::Duration::minutes(15);
+    let sessions = vec![session_at(second_start, 5), session_at(start, 10)];
+
+    let merged = merge_close_sessions(&sessions, Duration::from_secs(60));
+
+    assert_eq!(merged.len(), 2);
+    // Output is ordered by start time
+    assert_eq!(merged[0].started_at, start);
+    assert_eq!(merged[1].started_at, second_start);
+}
+
+#[test]
+fn test_tidy_sessions_counts_merged_sessions() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Choppy Task".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.resume_current_task().unwrap();
+    manager.pause_current_task().unwrap();
+    manager.resume_current_task().unwrap();
+    manager.pause_current_task().unwrap();
+
+    assert_eq!(manager.tasks[0].sessions.len(), 3);
+    let accumulated = manager.tasks[0].accumulated_duration;
+
+    let merged = manager.tidy_sessions(Duration::from_secs(60));
+
+    assert_eq!(merged, 2);
+    assert_eq!(manager.tasks[0].sessions.len(), 1);
+    assert_eq!(manager.tasks[0].sessions[0].duration, accumulated);
+}
+
+#[test]
+fn test_deserialize_task_without_sessions() {
+    let json = r#"{
+        "tasks": [{
+            "label": "Old Task",
+            "status": "Paused",
+            "created_at": "2025-10-30T14:30:15Z",
+            "started_at": null,
+            "accumulated_duration": { "secs": 90, "nanos": 0 }
+        }],
+        "active_task_index": 0
+    }"#;
+
+    let manager: TaskManager = serde_json::from_str(json).unwrap();
+    assert_eq!(manager.tasks[0].label, "Old Task");
+    assert!(manager.tasks[0].sessions.is_empty());
+    assert!(manager.tasks[0].tags.is_empty());
+    assert_eq!(manager.tasks[0].priority, Priority::Normal);
+}
+
+#[test]
+fn test_detect_overlaps() {
+    let start = Utc::now() - chrono::Duration::hours(3);
+    let sessions = vec![
+        // 0-30m
+        session_at(start, 30),
+        // 20m-50m overlaps the first
+        session_at(start + chrono::Duration::minutes(20), 30),
+        // 60m-70m is clear of both
+        session_at(start + chrono::Duration::minutes(60), 10),
+    ];
+
+    assert_eq!(detect_overlaps(&sessions), vec![(0, 1)]);
+    assert!(detect_overlaps(&sessions[2..]).is_empty());
+}
+
+#[test]
+fn test_trim_overlaps_clips_later_session() {
+    let start = Utc::now() - chrono::Duration::hours(3);
+    let sessions = vec![
+        session_at(start + chrono::Duration::minutes(20), 30),
+        session_at(start, 30),
+    ];
+
+    let (trimmed, clipped) = trim_overlaps(&sessions);
+
+    assert_eq!(clipped, Duration::from_secs(10 * 60));
+    assert_eq!(trimmed.len(), 2);
+    assert_eq!(trimmed[0].started_at, start);
+    assert_eq!(trimmed[1].started_at, start + chrono::Duration::minutes(30));
+    assert_eq!(trimmed[1].duration, Duration::from_secs(20 * 60));
+    assert!(detect_overlaps(&trimmed).is_empty());
+}
+
+#[test]
+fn test_trim_overlaps_drops_contained_session() {
+    let start = Utc::now() - chrono::Duration::hours(3);
+    let sessions = vec![
+        session_at(start, 60),
+        session_at(start + chrono::Duration::minutes(10), 5),
+    ];
+
+    let (trimmed, clipped) = trim_overlaps(&sessions);
+
+    assert_eq!(trimmed.len(), 1);
+    assert_eq!(clipped, Duration::from_secs(5 * 60));
+}
+
+#[test]
+fn test_trim_session_overlaps_reduces_accumulated_duration() {
+    let start = Utc::now() - chrono::Duration::hours(3);
+    let mut manager = TaskManager::new();
+    manager.start_task("Imported Task".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.tasks[0].sessions = vec![
+        session_at(start, 30),
+        session_at(start + chrono::Duration::minutes(20), 30),
+    ];
+    manager.tasks[0].accumulated_duration = Duration::from_secs(60 * 60);
+
+    assert_eq!(manager.session_overlaps(), vec![(0, 0, 1)]);
+
+    let clipped = manager.trim_session_overlaps();
+
+    assert_eq!(clipped, Duration::from_secs(10 * 60));
+    assert_eq!(
+        manager.tasks[0].accumulated_duration,
+        Duration::from_secs(50 * 60)
+    );
+    assert!(manager.session_overlaps().is_empty());
+}
+
+#[test]
+fn test_running_tasks() {
+    let mut manager = TaskManager::new();
+    assert!(manager.running_tasks().is_empty());
+
+    manager.start_task("First".to_string()).unwrap();
+    manager.start_task("Second".to_string()).unwrap();
+
+    let running = manager.running_tasks();
+    assert_eq!(running.len(), 1);
+    assert_eq!(running[0].0, 1);
+    assert_eq!(running[0].1.label, "Second");
+}
+
+#[test]
+fn test_session_durations_include_unrecorded_and_live_time() {
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
+    task.pause().unwrap();
+    let recorded = task.sessions[0].duration;
+
+    // Time added without a session (e.g. from an older store) shows up separately
+    task.accumulated_duration += Duration::from_secs(60);
+    assert_eq!(
+        task.session_durations(),
+        vec![recorded, Duration::from_secs(60)]
+    );
+
+    task.resume().unwrap();
+    let durations = task.session_durations();
+    assert_eq!(durations.len(), 3);
+}
+
+#[test]
+fn test_dated_sessions_date_unrecorded_time_at_creation() {
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
+    task.pause().unwrap();
+    task.accumulated_duration += Duration::from_secs(60);
+    task.resume().unwrap();
+
+    let sessions = task.dated_sessions();
+    assert_eq!(sessions.len(), 3);
+    assert_eq!(sessions[1].started_at, task.created_at);
+    assert_eq!(sessions[1].duration, Duration::from_secs(60));
+    assert_eq!(Some(sessions[2].started_at), task.started_at);
+}
+
+/// Creates a manager holding one paused task that was last worked on `days_ago` days ago,
+/// followed by a running task
+fn manager_with_paused_task_idle_for(days_ago: i64) -> TaskManager {
+    let mut manager = TaskManager::new();
+    manager.start_task("Forgotten Task".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+
+    let long_ago = Utc::now() - chrono::Duration::days(days_ago);
+    manager.tasks[0].created_at = long_ago;
+    manager.tasks[0].sessions[0].started_at = long_ago;
+
+    manager.start_task("Current Task".to_string()).unwrap();
+    manager
+}
+
+#[test]
+fn test_last_activity() {
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
+    let before_pause = Utc::now();
+    task.pause().unwrap();
+    assert!(task.last_activity() >= before_pause);
+
+    task.sessions.clear();
+    assert_eq!(task.last_activity(), task.created_at);
+
+    task.resume().unwrap();
+    assert!(task.last_activity() >= before_pause);
+}
+
+#[test]
+fn test_prune_stale_tasks_complete_policy() {
+    let mut manager = manager_with_paused_task_idle_for(10);
+    let accumulated = manager.tasks[0].accumulated_duration;
+
+    let count = manager.prune_stale_tasks(
+        StdDuration::from_secs(7 * 86400),
+        StalePolicy::Complete,
+        Utc::now(),
+    );
+
+    assert_eq!(count, 1);
+    assert_eq!(manager.tasks.len(), 2);
+    assert!(manager.tasks[0].is_completed());
+    assert_eq!(manager.tasks[0].accumulated_duration, accumulated);
+    assert_eq!(manager.current_task().unwrap().label, "Current Task");
+}
+
+#[test]
+fn test_prune_stale_tasks_evict_policy() {
+    let mut manager = manager_with_paused_task_idle_for(10);
+
+    let count = manager.prune_stale_tasks(
+        StdDuration::from_secs(7 * 86400),
+        StalePolicy::Evict,
+        Utc::now(),
+    );
+
+    assert_eq!(count, 1);
+    assert_eq!(manager.tasks.len(), 1);
+    assert_eq!(manager.active_task_index, Some(0));
+    assert_eq!(manager.current_task().unwrap().label, "Current Task");
+}
+
+#[test]
+fn test_prune_stale_tasks_keeps_recent_tasks() {
+    let mut manager = manager_with_paused_task_idle_for(3);
+
+    let count = manager.prune_stale_tasks(
+        StdDuration::from_secs(7 * 86400),
+        StalePolicy::Evict,
+        Utc::now(),
+    );
+
+    assert_eq!(count, 0);
+    assert_eq!(manager.tasks.len(), 2);
+    assert!(manager.tasks[0].is_paused());
+}
+
+#[test]
+fn test_reorder_task_moves_task_and_active_index() {
+    let mut manager = TaskManager::new();
+    for label in ["Task A", "Task B", "Task C", "Task D"] {
+        manager.start_task(label.to_string()).unwrap();
+    }
+    // Make Task B the active task
+    manager.pause_current_task().unwrap();
+    manager.active_task_index = Some(1);
+
+    manager.reorder_task(1, 3).unwrap();
+    let labels: Vec<&str> = manager.tasks.iter().map(|t| t.label.as_str()).collect();
+    assert_eq!(labels, ["Task B", "Task C", "Task A", "Task D"]);
+    assert_eq!(manager.current_task().unwrap().label, "Task B");
+
+    manager.reorder_task(1, 4).unwrap();
+    let labels: Vec<&str> = manager.tasks.iter().map(|t| t.label.as_str()).collect();
+    assert_eq!(labels, ["Task C", "Task A", "Task D", "Task B"]);
+    assert_eq!(manager.current_task().unwrap().label, "Task B");
+
+    manager.reorder_task(4, 2).unwrap();
+    manager.reorder_task(1, 3).unwrap();
+    let labels: Vec<&str> = manager.tasks.iter().map(|t| t.label.as_str()).collect();
+    assert_eq!(labels, ["Task B", "Task A", "Task C", "Task D"]);
+    assert_eq!(manager.current_task().unwrap().label, "Task B");
+}
+
+#[test]
+fn test_reorder_task_invalid_indices() {
+    let mut manager = TaskManager::new();
+    assert!(manager.reorder_task(1, 1).is_err());
+
+    manager.start_task("Task A".to_string()).unwrap();
+    manager.start_task("Task B".to_string()).unwrap();
+
+    let err = manager.reorder_task(0, 1).unwrap_err();
+    assert!(err.to_string().contains("must be greater than 0"));
+    let err = manager.reorder_task(1, 3).unwrap_err();
+    assert!(err.to_string().contains("out of bounds"));
+    assert_eq!(manager.tasks[0].label, "Task A");
+}
+
+#[test]
+fn test_date_range_contains() {
+    let day = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+    let at = |ts: &str| {
+        DateTime::parse_from_rfc3339(ts)
+            .unwrap()
+            .with_timezone(&Utc)
+    };
+
+    let range = DateRange::new(Some(day("2024-06-01")), Some(day("2024-06-07"))).unwrap();
+    assert!(range.contains_in(at("2024-06-01T00:00:00Z"), &Utc));
+    assert!(range.contains_in(at("2024-06-07T23:59:59Z"), &Utc));
+    assert!(!range.contains_in(at("2024-05-31T23:59:59Z"), &Utc));
+    assert!(!range.contains_in(at("2024-06-08T00:00:00Z"), &Utc));
+
+    // Days are taken in the given time zone, not in UTC
+    let tokyo = FixedOffset::east_opt(9 * 3600).unwrap();
+    assert!(range.contains_in(at("2024-05-31T20:00:00Z"), &tokyo));
+    assert!(!range.contains_in(at("2024-06-07T20:00:00Z"), &tokyo));
+    let local = |ts: &str| {
+        NaiveDateTime::parse_from_str(ts, "%Y-%m-%d %H:%M")
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap()
+            .with_timezone(&Utc)
+    };
+    assert!(range.contains(local("2024-06-01 00:30")));
+    assert!(range.contains(local("2024-06-07 23:30")));
+    assert!(!range.contains(local("2024-06-08 00:30")));
+
+    let open_start = DateRange::new(None, Some(day("2024-06-07"))).unwrap();
+    assert!(open_start.contains(at("2000-01-01T12:00:00Z")));
+    assert!(DateRange::default().contains(Utc::now()));
+}
+
+#[test]
+fn test_date_range_rejects_inverted_bounds() {
+    let day = |d: &str| NaiveDate::parse_from_str(d, "%Y-%m-%d").unwrap();
+
+    let err = DateRange::new(Some(day("2024-06-07")), Some(day("2024-06-01"))).unwrap_err();
+    assert!(err.to_string().contains("is after --until"));
+}
+
+#[test]
+fn test_clean_removes_all_tasks() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Task A".to_string()).unwrap();
+    manager.start_task("Task B".to_string()).unwrap();
+
+    assert_eq!(manager.clean(false), 2);
+    assert!(manager.tasks.is_empty());
+    assert!(manager.current_task().is_none());
+}
+
+#[test]
+fn test_clean_keep_active() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Task A".to_string()).unwrap();
+    manager.start_task("Active Task".to_string()).unwrap();
+    manager.start_task("Task C".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.active_task_index = Some(1);
+
+    assert_eq!(manager.clean(true), 2);
+    assert_eq!(manager.tasks.len(), 1);
+    assert_eq!(manager.active_task_index, Some(0));
+    assert_eq!(manager.current_task().unwrap().label, "Active Task");
+
+    // Without an active task there is nothing to keep
+    manager.complete_current_task().unwrap();
+    assert_eq!(manager.clean(true), 1);
+    assert!(manager.tasks.is_empty());
+}
+
+#[test]
+fn test_cap_current_session() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Long Task".to_string()).unwrap();
+    manager.tasks[0].started_at = Some(Utc::now() - chrono::Duration::hours(3));
+
+    let trimmed = manager
+        .cap_current_session(StdDuration::from_secs(3600))
+        .unwrap();
+
+    assert!(trimmed >= StdDuration::from_secs(2 * 3600));
+    let session = manager.tasks[0].current_session_duration();
+    assert!(session >= StdDuration::from_secs(3600) && session < StdDuration::from_secs(3601));
+
+    // A session already under the cap is left alone
+    let trimmed = manager
+        .cap_current_session(StdDuration::from_secs(7200))
+        .unwrap();
+    assert!(trimmed.is_zero());
+}
+
+#[test]
+fn test_prefix_project_labels() {
+    let mut manager = TaskManager::new();
+    for (label, project) in [
+        ("Landing page", Some("Web")),
+        ("Mobile menu", None),
+        ("Contact form", Some("Web")),
+        ("Release notes", Some("Docs")),
+    ] {
+        let index = manager.start_task(label.to_string()).unwrap();
+        if let Some(project) = project {
+            manager.set_project(index + 1, project.to_string()).unwrap();
+        }
+    }
+
+    let count = manager.prefix_project_labels("Web", "[web] ").unwrap();
+
+    assert_eq!(count, 2);
+    let labels: Vec<&str> = manager.tasks.iter().map(|t| t.label.as_str()).collect();
+    assert_eq!(
+        labels,
+        [
+            "[web] Landing page",
+            "Mobile menu",
+            "[web] Contact form",
+            "Release notes"
+        ]
+    );
+    assert_eq!(manager.prefix_project_labels("Missing", "x ").unwrap(), 0);
+}
+
+#[test]
+fn test_prefix_project_labels_validates_before_changing() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Landing page".to_string()).unwrap();
+    manager.set_project(1, "Web".to_string()).unwrap();
+    manager.set_strict_labels(true);
+
+    let err = manager
+        .prefix_project_labels("Web", "\x1b[31m")
+        .unwrap_err();
+
+    assert!(err.to_string().contains("control characters"));
+    assert_eq!(manager.tasks[0].label, "Landing page");
+}
+
+#[test]
+fn test_repair_future_start_time() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Synced Task".to_string()).unwrap();
+    manager.tasks[0].started_at = Some(Utc::now() + chrono::Duration::hours(5));
+    manager.tasks[0].accumulated_duration = StdDuration::from_secs(600);
+
+    let now = Utc::now();
+    let repairs = manager.repair(now);
+
+    assert_eq!(repairs.len(), 1);
+    assert!(repairs[0].contains("started in the future"));
+    assert_eq!(manager.tasks[0].started_at, Some(now));
+    let total = manager.tasks[0].total_duration();
+    assert!(total >= StdDuration::from_secs(600) && total < StdDuration::from_secs(610));
+}
+
+#[test]
+fn test_repair_inconsistent_state() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Paused Task".to_string()).unwrap();
+    manager.start_task("Running Task".to_string()).unwrap();
+    manager.tasks[0].started_at = Some(Utc::now());
+    manager.tasks[1].started_at = None;
+    manager.active_task_index = Some(5);
+
+    let now = Utc::now();
+    let repairs = manager.repair(now);
+
+    assert_eq!(repairs.len(), 3);
+    assert_eq!(manager.tasks[0].started_at, None);
+    assert_eq!(manager.tasks[1].started_at, Some(now));
+    assert_eq!(manager.active_task_index, None);
+
+    // A consistent store needs no repairs
+    assert!(manager.repair(Utc::now()).is_empty());
+}
+
+#[test]
+fn test_complete_records_completion_time() {
+    let mut task = Task::new("Test Task".to_string(), Vec::new());
+    assert!(task.completed_at.is_none());
+
+    let before = Utc::now();
+    task.complete().unwrap();
+
+    assert!(task.completed_at.is_some_and(|at| at >= before));
+}
+
+#[test]
+fn test_stale_tasks_sorted_oldest_first() {
+    let mut manager = TaskManager::new();
+    for label in ["Two Days Idle", "Fresh", "Five Days Idle", "Running"] {
+        manager.start_task(label.to_string()).unwrap();
+    }
+    for (index, days) in [(0, 2), (2, 5)] {
+        let long_ago = Utc::now() - chrono::Duration::days(days);
+        manager.tasks[index].created_at = long_ago;
+        manager.tasks[index].sessions[0].started_at = long_ago;
+    }
+
+    let stale = manager.stale_tasks(StdDuration::from_secs(86400), Utc::now());
+
+    let found: Vec<(usize, &str)> = stale.iter().map(|(i, t)| (*i, t.label.as_str())).collect();
+    assert_eq!(found, [(2, "Five Days Idle"), (0, "Two Days Idle")]);
+}
+
+/// A writer that fails with `kind` a given number of times before succeeding
+fn flaky_writer(
+    failures: u32,
+    kind: std::io::ErrorKind,
+) -> (
+    impl FnMut() -> std::io::Result<()>,
+    std::rc::Rc<std::cell::Cell<u32>>,
+) {
+    let calls = std::rc::Rc::new(std::cell::Cell::new(0));
+    let counter = calls.clone();
+    let writer = move || {
+        counter.set(counter.get() + 1);
+        if counter.get() <= failures {
+            Err(std::io::Error::from(kind))
+        } else {
+            Ok(())
+        }
+    };
+    (writer, calls)
+}
+
+#[test]
+fn test_retry_io_succeeds_within_budget() {
+    let (writer, calls) = flaky_writer(2, std::io::ErrorKind::TimedOut);
+
+    assert!(retry_io(2, writer).is_ok());
+    assert_eq!(calls.get(), 3);
+}
+
+#[test]
+fn test_retry_io_gives_up_after_budget() {
+    let (writer, calls) = flaky_writer(2, std::io::ErrorKind::StaleNetworkFileHandle);
+
+    let err = retry_io(1, writer).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::StaleNetworkFileHandle);
+    assert_eq!(calls.get(), 2);
+}
+
+#[test]
+fn test_retry_io_fails_fast_on_permission_denied() {
+    let (writer, calls) = flaky_writer(1, std::io::ErrorKind::PermissionDenied);
+
+    let err = retry_io(5, writer).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    assert_eq!(calls.get(), 1);
+}
+
+#[test]
+fn test_add_time() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Forgot the timer".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    let before = manager.tasks[0].accumulated_duration;
+
+    manager.add_time(1, Duration::from_secs(90 * 60)).unwrap();
+    assert_eq!(
+        manager.tasks[0].accumulated_duration,
+        before + Duration::from_secs(90 * 60)
+    );
+
+    let result = manager.add_time(2, Duration::from_secs(60));
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Task index 2 is out of bounds. Valid range: 1-1"));
+    assert!(manager.add_time(0, Duration::from_secs(60)).is_err());
+
+    manager.resume_current_task().unwrap();
+    manager.complete_current_task().unwrap();
+    assert!(matches!(
+        manager.add_time(1, Duration::from_secs(60)),
+        Err(TaskError::TaskCompleted)
+    ));
+}
+
+#[test]
+fn test_resume_task_by_index() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Task A".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.start_task("Task B".to_string()).unwrap();
+
+    manager.resume_task(1).unwrap();
+    assert_eq!(manager.active_task_index, Some(0));
+    assert!(manager.tasks[0].is_running());
+    assert!(manager.tasks[1].is_paused());
+
+    assert!(matches!(
+        manager.resume_task(1),
+        Err(TaskError::TaskAlreadyRunning)
+    ));
+    assert!(manager
+        .resume_task(3)
+        .unwrap_err()
+        .to_string()
+        .contains("Task index 3 is out of bounds"));
+}
+
+#[test]
+fn test_resume_task_rejects_completed_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Done".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    manager.start_task("Running".to_string()).unwrap();
+
+    assert!(matches!(
+        manager.resume_task(1),
+        Err(TaskError::TaskCompleted)
+    ));
+    // The running task is left alone
+    assert_eq!(manager.active_task_index, Some(1));
+    assert!(manager.tasks[1].is_running());
+}
+
+#[test]
+fn test_set_note() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Annotated".to_string()).unwrap();
+
+    assert!(manager.set_note(1, "  Fixed the\tflaky test  ").unwrap());
+    assert_eq!(
+        manager.tasks[0].note.as_deref(),
+        Some("Fixed the flaky test")
+    );
+
+    // Blank text clears the note instead of storing it
+    assert!(!manager.set_note(1, "   ").unwrap());
+    assert!(manager.tasks[0].note.is_none());
+
+    assert!(manager.set_note(2, "Nope").is_err());
+}
+
+#[test]
+fn test_start_task_at_backdates_task() {
+    let mut manager = TaskManager::new();
+    let started_at = Utc::now() - chrono::Duration::minutes(20);
+
+    let index = manager
+        .start_task_at("Backdated".to_string(), Vec::new(), started_at)
+        .unwrap();
+
+    let task = &manager.tasks[index];
+    assert_eq!(task.created_at, started_at);
+    assert_eq!(task.started_at, Some(started_at));
+    assert!(task.total_duration() >= Duration::from_secs(20 * 60));
+}
+
+#[test]
+fn test_start_task_at_rejects_future_time() {
+    let mut manager = TaskManager::new();
+    let started_at = Utc::now() + chrono::Duration::minutes(5);
+
+    let result = manager.start_task_at("Too soon".to_string(), Vec::new(), started_at);
+    assert!(matches!(result, Err(TaskError::InvalidState { .. })));
+    assert!(manager.tasks.is_empty());
+}
+
+#[test]
+fn test_merge_tasks_moves_time_and_sessions() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Docs".to_string()).unwrap();
+    manager.start_task("Docs again".to_string()).unwrap();
+    manager.start_task("Active".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.tasks[0].accumulated_duration = Duration::from_secs(600);
+    manager.tasks[1].accumulated_duration = Duration::from_secs(300);
+
+    let merged = manager.merge_tasks(1, 2).unwrap();
+
+    assert_eq!(merged.label, "Docs again");
+    assert_eq!(manager.tasks.len(), 2);
+    assert_eq!(manager.tasks[0].total_duration(), Duration::from_secs(900));
+    // The moved time keeps a dated session, so reports still count it
+    let recorded: Duration = manager.tasks[0]
+        .dated_sessions()
+        .iter()
+        .map(|s| s.duration)
+        .sum();
+    assert_eq!(recorded, Duration::from_secs(900));
+    assert_eq!(manager.tasks[0].session_count, 2);
+    // The active task shifted down with the removal
+    assert_eq!(manager.active_task_index, Some(1));
+    assert_eq!(manager.current_task().unwrap().label, "Active");
+}
+
+#[test]
+fn test_split_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Meeting".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    let started_at = manager.tasks[0].created_at;
+    manager.tasks[0].sessions = vec![WorkSession {
+        started_at,
+        duration: Duration::from_secs(2400),
+        clock_skew: None,
+    }];
+    manager.tasks[0].accumulated_duration = Duration::from_secs(3600);
+
+    let index = manager.split_task(1, 0.25, "Code review").unwrap();
+
+    assert_eq!(index, 1);
+    assert_eq!(manager.tasks[0].total_duration(), Duration::from_secs(2700));
+    let split = &manager.tasks[1];
+    assert_eq!(split.label, "Code review");
+    assert!(split.is_completed());
+    assert_eq!(split.total_duration(), Duration::from_secs(900));
+    assert_eq!(split.sessions[0].duration, Duration::from_secs(600));
+    // Both halves still account for all of their time in dated sessions
+    for task in &manager.tasks {
+        let recorded: Duration = task.dated_sessions().iter().map(|s| s.duration).sum();
+        assert_eq!(recorded, task.total_duration());
+    }
+    // The paused task stays active
+    assert_eq!(manager.active_task_index, Some(0));
+}
+
+#[test]
+fn test_split_task_session_sums_match_totals() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Meeting".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    let started_at = manager.tasks[0].created_at;
+    manager.tasks[0].sessions = [6_703_703_951_359, 6_378_387_906_922]
+        .into_iter()
+        .map(|nanos| WorkSession {
+            started_at,
+            duration: Duration::from_nanos(nanos),
+            clock_skew: None,
+        })
+        .collect();
+    manager.tasks[0].accumulated_duration = Duration::from_nanos(13_082_091_858_281);
+
+    manager.split_task(1, 1.0 / 3.0, "Other").unwrap();
+
+    // Rounding each session's share must not leave the totals out of step with the sessions
+    for task in &manager.tasks {
+        let recorded: Duration = task.sessions.iter().map(|s| s.duration).sum();
+        assert_eq!(recorded, task.accumulated_duration);
+    }
+    assert_eq!(
+        manager.tasks[0].accumulated_duration + manager.tasks[1].accumulated_duration,
+        Duration::from_nanos(13_082_091_858_281)
+    );
+}
+
+#[test]
+fn test_split_task_rejects_bad_ratio_and_running_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Meeting".to_string()).unwrap();
+
+    let err = manager.split_task(1, 0.5, "Other").unwrap_err();
+    assert!(err.to_string().contains("currently running"));
+
+    manager.pause_current_task().unwrap();
+    for ratio in [0.0, 1.0, -0.5, 1.5, f64::NAN] {
+        let err = manager.split_task(1, ratio, "Other").unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Split ratio must be between 0 and 1"));
+    }
+    assert!(manager.split_task(1, 0.5, "  ").is_err());
+    assert!(manager.split_task(2, 0.5, "Other").is_err());
+    assert_eq!(manager.tasks.len(), 1);
+}
+
+#[test]
+fn test_merge_tasks_active_source_hands_over_to_target() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Target".to_string()).unwrap();
+    manager.start_task("Source".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+
+    manager.merge_tasks(1, 2).unwrap();
+    assert_eq!(manager.active_task_index, Some(0));
+
+    // A completed target can't become the active task
+    manager.start_task("Source".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.tasks[0].complete().unwrap();
+    manager.merge_tasks(1, 2).unwrap();
+    assert_eq!(manager.active_task_index, None);
+}
+
+#[test]
+fn test_merge_tasks_rejects_running_or_same_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Paused".to_string()).unwrap();
+    manager.start_task("Running".to_string()).unwrap();
+
+    assert!(matches!(
+        manager.merge_tasks(1, 2),
+        Err(TaskError::InvalidState { .. })
+    ));
+    assert!(manager.merge_tasks(2, 1).is_err());
+    assert!(manager.merge_tasks(1, 1).is_err());
+    assert!(manager.merge_tasks(1, 3).is_err());
+    assert_eq!(manager.tasks.len(), 2);
+}
+
+#[test]
+fn test_check_duplicate_label() {
+    let mut manager = TaskManager::new();
+    assert!(manager.check_duplicate_label("emails").is_ok());
+
+    manager.start_task("emails".to_string()).unwrap();
+    let err = manager.check_duplicate_label("emails").unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("Task 1 is already labelled 'emails'; it is already running"));
+    // Only exact matches count
+    assert!(manager.check_duplicate_label("Emails").is_ok());
+    assert!(manager.check_duplicate_label("emails later").is_ok());
+
+    manager.pause_current_task().unwrap();
+    let err = manager.check_duplicate_label("emails").unwrap_err();
+    assert!(err.to_string().contains("resume it with `tt resume 1`"));
+
+    // A completed task with the same label doesn't block a new start
+    manager.complete_current_task().unwrap();
+    assert!(manager.check_duplicate_label("emails").is_ok());
+}
+
+#[test]
+fn test_check_goals_notifies_once() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Pomodoro".to_string()).unwrap();
+    manager.set_goal(1, Duration::from_secs(25 * 60)).unwrap();
+    assert!(manager.check_goals().is_empty());
+
+    manager.tasks[0].accumulated_duration = Duration::from_secs(26 * 60);
+    assert_eq!(manager.check_goals(), vec![0]);
+    assert!(manager.tasks[0].goal_notified);
+    // Already announced
+    assert!(manager.check_goals().is_empty());
+
+    // Restarting the time arms the goal again
+    manager.restart_task(1).unwrap();
+    assert!(!manager.tasks[0].goal_notified);
+    assert!(manager.set_goal(2, Duration::ZERO).is_err());
+}
+
+#[test]
+fn test_check_goals_ignores_tasks_not_running() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Paused".to_string()).unwrap();
+    manager.set_goal(1, Duration::ZERO).unwrap();
+    manager.pause_current_task().unwrap();
+    assert!(manager.check_goals().is_empty());
+
+    manager.resume_current_task().unwrap();
+    assert_eq!(manager.check_goals(), vec![0]);
+}
+
+#[test]
+fn test_session_count() {
+    let mut task = Task::new("Fragmented".to_string(), Vec::new());
+    assert_eq!(task.session_count, 1);
+
+    task.pause().unwrap();
+    assert_eq!(task.session_count, 1);
+    task.resume().unwrap();
+    task.pause().unwrap();
+    task.resume().unwrap();
+    assert_eq!(task.session_count, 3);
+
+    // Completing a running task ends its session without starting another
+    task.complete().unwrap();
+    assert_eq!(task.session_count, 3);
+    assert!(task.resume().is_err());
+    assert_eq!(task.session_count, 3);
+}
+
+#[test]
+fn test_session_count_defaults_to_zero_for_old_tasks() {
+    let json = r#"{
+        "label": "Old",
+        "status": "Paused",
+        "created_at": "2024-06-01T09:00:00Z",
+        "started_at": null,
+        "accumulated_duration": { "secs": 60, "nanos": 0 }
+    }"#;
+    let task: Task = serde_json::from_str(json).unwrap();
+    assert_eq!(task.session_count, 0);
+}
+
+#[test]
+fn test_restart_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Mistracked".to_string()).unwrap();
+    manager.tasks[0].accumulated_duration = Duration::from_secs(3600);
+    manager.tasks[0].sessions.push(WorkSession {
+        started_at: Utc::now() - chrono::Duration::hours(2),
+        duration: Duration::from_secs(3600),
+        clock_skew: None,
+    });
+    manager.tasks[0].started_at = Some(Utc::now() - chrono::Duration::minutes(10));
+
+    let discarded = manager.restart_task(1).unwrap();
+
+    assert!(discarded >= Duration::from_secs(3600 + 600));
+    let task = &manager.tasks[0];
+    assert!(task.is_running());
+    assert!(task.sessions.is_empty());
+    assert_eq!(task.session_count, 1);
+    assert!(task.total_duration() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_restart_paused_task_stays_paused() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Paused".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.tasks[0].accumulated_duration = Duration::from_secs(90);
+
+    assert_eq!(manager.restart_task(1).unwrap(), Duration::from_secs(90));
+    assert!(manager.tasks[0].is_paused());
+    assert_eq!(manager.tasks[0].total_duration(), Duration::ZERO);
+    assert_eq!(manager.tasks[0].session_count, 0);
+
+    assert!(manager.restart_task(2).is_err());
+}
+
+#[test]
+fn test_worked_on_uses_creation_and_sessions() {
+    let now = Utc::now();
+    let today = now.with_timezone(&Local).date_naive();
+    let three_days_ago = now - chrono::Duration::days(3);
+
+    let mut task = Task::new_at("Old".to_string(), Vec::new(), three_days_ago);
+    task.pause().unwrap();
+    // Only an hour of work, three days ago
+    task.sessions = vec![WorkSession {
+        started_at: three_days_ago,
+        duration: StdDuration::from_secs(3600),
+        clock_skew: None,
+    }];
+    task.accumulated_duration = StdDuration::from_secs(3600);
+    assert!(!task.worked_on(today));
+    assert!(task.worked_on(three_days_ago.with_timezone(&Local).date_naive()));
+
+    task.resume().unwrap();
+    assert!(task.worked_on(today));
+    assert!(!task.running_since_before(today));
+}
+
+#[test]
+fn test_worked_on_counts_session_left_running_overnight() {
+    let now = Utc::now();
+    let today = now.with_timezone(&Local).date_naive();
+    let task = Task::new_at(
+        "Overnight".to_string(),
+        Vec::new(),
+        now - chrono::Duration::days(2),
+    );
+
+    assert!(task.worked_on(today));
+    assert!(task.worked_on(today - chrono::Duration::days(1)));
+    assert!(task.running_since_before(today));
+}
+
+#[test]
+fn test_import_tasks_replace_picks_active_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Old".to_string()).unwrap();
+
+    let mut paused = Task::new("Imported paused".to_string(), Vec::new());
+    paused.pause().unwrap();
+    let running = Task::new("Imported running".to_string(), Vec::new());
+    let also_running = Task::new("Imported also running".to_string(), Vec::new());
+
+    manager
+        .import_tasks(vec![paused, running, also_running], false)
+        .unwrap();
+
+    assert_eq!(manager.tasks.len(), 3);
+    // Only the last running task keeps its timer going
+    assert_eq!(
+        manager.current_task().unwrap().label,
+        "Imported also running"
+    );
+    assert!(manager.tasks[1].is_paused());
+    assert_eq!(manager.running_tasks().len(), 1);
+}
+
+#[test]
+fn test_import_tasks_merge_keeps_active_task() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Current".to_string()).unwrap();
+
+    let imported = Task::new("Imported running".to_string(), Vec::new());
+    manager.import_tasks(vec![imported], true).unwrap();
+
+    assert_eq!(manager.tasks.len(), 2);
+    assert_eq!(manager.current_task().unwrap().label, "Current");
+    assert!(manager.tasks[1].is_paused());
+}
+
+#[test]
+fn test_import_tasks_applies_task_limit() {
+    let mut manager = TaskManager::new();
+    manager.retain_days = 0;
+    let tasks = (0..15)
+        .map(|i| {
+            let mut task = Task::new(format!("Done {}", i), Vec::new());
+            task.complete().unwrap();
+            task
+        })
+        .collect();
+
+    manager.import_tasks(tasks, false).unwrap();
+    assert_eq!(manager.tasks.len(), MAX_TASKS);
+}
+
+#[test]
+fn test_parse_task_file() {
+    let task = Task::new("Portable".to_string(), Vec::new());
+    let array = serde_json::to_string(&vec![task]).unwrap();
+    let (tasks, active) = parse_task_file(&array).unwrap();
+    assert_eq!(tasks[0].label, "Portable");
+    assert_eq!(active, None);
+
+    let store = format!(r#"{{ "tasks": {}, "active_task_index": 0 }}"#, array);
+    let (tasks, active) = parse_task_file(&store).unwrap();
+    assert_eq!(tasks.len(), 1);
+    assert_eq!(active, Some(0));
+
+    // An out-of-range active index is dropped rather than trusted
+    let store = format!(r#"{{ "tasks": {}, "active_task_index": 3 }}"#, array);
+    assert_eq!(parse_task_file(&store).unwrap().1, None);
+
+    assert!(matches!(
+        parse_task_file("[{"),
+        Err(TaskError::SerializationError(_))
+    ));
+    assert!(matches!(
+        parse_task_file(r#"[{ "label": "No status" }]"#),
+        Err(TaskError::SerializationError(_))
+    ));
+}
+
+#[test]
+fn test_clone_task() {
+    let mut manager = TaskManager::new();
+    manager
+        .start_task_with_tags("Weekly sync".to_string(), vec!["acme".to_string()])
+        .unwrap();
+    manager.set_priority(1, Priority::High).unwrap();
+    manager.set_note(1, "Agenda in the shared doc").unwrap();
+    manager.complete_current_task().unwrap();
+
+    let index = manager.clone_task(1).unwrap();
+
+    assert_eq!(index, 1);
+    assert_eq!(manager.active_task_index, Some(1));
+    let clone = &manager.tasks[1];
+    assert!(clone.is_running());
+    assert_eq!(clone.label, "Weekly sync");
+    assert_eq!(clone.tags, vec!["acme"]);
+    assert_eq!(clone.priority, Priority::High);
+    assert_eq!(clone.note.as_deref(), Some("Agenda in the shared doc"));
+    assert!(clone.sessions.is_empty());
+
+    // The original keeps its completed state
+    assert!(manager.tasks[0].is_completed());
+}
+
+#[test]
+fn test_clone_task_invalid_index() {
+    let mut manager = TaskManager::new();
+    assert!(manager.clone_task(1).is_err());
+
+    manager.start_task("Only".to_string()).unwrap();
+    assert!(manager.clone_task(0).is_err());
+    assert!(manager.clone_task(2).is_err());
+    assert_eq!(manager.tasks.len(), 1);
+}
+
+#[test]
+fn test_set_priority() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Urgent fix".to_string()).unwrap();
+    assert_eq!(manager.tasks[0].priority, Priority::Normal);
+
+    manager.set_priority(1, Priority::High).unwrap();
+    assert_eq!(manager.tasks[0].priority, Priority::High);
+
+    assert!(manager.set_priority(2, Priority::Low).is_err());
+}
+
+#[test]
+fn test_set_estimate() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Migration".to_string()).unwrap();
+
+    manager.set_estimate(1, Duration::from_secs(3600)).unwrap();
+    manager.set_estimate(1, Duration::from_secs(7200)).unwrap();
+    assert_eq!(manager.tasks[0].estimate, Some(Duration::from_secs(7200)));
+
+    assert!(manager.set_estimate(1, Duration::ZERO).is_err());
+    assert!(manager.set_estimate(2, Duration::from_secs(60)).is_err());
+
+    // Clones keep the estimate
+    manager.complete_current_task().unwrap();
+    let index = manager.clone_task(1).unwrap();
+    assert_eq!(
+        manager.tasks[index].estimate,
+        Some(Duration::from_secs(7200))
+    );
+}
+
+#[test]
+fn test_priority_ordering() {
+    assert!(Priority::Low < Priority::Normal);
+    assert!(Priority::Normal < Priority::High);
+    assert_eq!(Priority::High.to_string(), "high");
+}
+
+#[test]
+fn test_pause_all_running() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Paused".to_string()).unwrap();
+    manager.start_task("Running".to_string()).unwrap();
+    // Simulate a second running task, e.g. from a hand-edited store
+    manager.tasks[0].resume().unwrap();
+
+    assert_eq!(manager.pause_all_running().unwrap(), 2);
+    assert!(manager.tasks.iter().all(Task::is_paused));
+    assert_eq!(manager.active_task_index, Some(1));
+
+    assert_eq!(manager.pause_all_running().unwrap(), 0);
+}
+
+#[test]
+fn test_load_or_create_backs_up_corrupt_file() {
+    let dir = std::env::temp_dir().join("tt_task_corrupt_load");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json");
+
+    // A missing file just starts fresh
+    let (manager, backup) =
+        TaskManager::load_or_create(path.clone(), None, 10, RETAIN_DAYS).unwrap();
+    assert_eq!(manager.task_count(), 0);
+    assert!(backup.is_none());
+
+    fs::write(&path, "{ not json").unwrap();
+    let (manager, backup) =
+        TaskManager::load_or_create(path.clone(), None, 10, RETAIN_DAYS).unwrap();
+    assert_eq!(manager.task_count(), 0);
+
+    let backup = backup.unwrap();
+    assert!(!path.exists());
+    assert!(backup
+        .file_name()
+        .unwrap()
+        .to_string_lossy()
+        .starts_with("tasks.json.corrupt-"));
+    assert_eq!(fs::read_to_string(&backup).unwrap(), "{ not json");
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_load_or_create_fails_on_unreadable_file() {
+    let dir = std::env::temp_dir().join("tt_task_unreadable_load");
+    fs::remove_dir_all(&dir).ok();
+    // A directory where the task file should be can't be read, but isn't missing either
+    let path = dir.join("tasks.json");
+    fs::create_dir_all(&path).unwrap();
+
+    let result = TaskManager::load_or_create(path.clone(), None, 10, RETAIN_DAYS);
+    assert!(matches!(result, Err(TaskError::IoError(_))));
+    assert!(path.is_dir());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_save_skips_unchanged_state() {
+    let dir = std::env::temp_dir().join("tt_task_unchanged_save");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json");
+
+    // A store that doesn't exist yet is always written
+    let (mut manager, _) =
+        TaskManager::load_or_create(path.clone(), None, 10, RETAIN_DAYS).unwrap();
+    manager.start_task("Work".to_string()).unwrap();
+    assert!(manager.save().unwrap());
+    assert!(!manager.save().unwrap());
+
+    let (mut manager, _) =
+        TaskManager::load_or_create(path.clone(), None, 10, RETAIN_DAYS).unwrap();
+    assert!(!manager.save().unwrap());
+    manager.pause_current_task().unwrap();
+    assert!(manager.save().unwrap());
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_task_ids_survive_deletion() {
+    let mut manager = TaskManager::new();
+    for label in ["First", "Second", "Third"] {
+        manager.start_task(label.to_string()).unwrap();
+        manager.complete_current_task().unwrap();
+    }
+    let ids: Vec<u64> = manager.tasks.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![1, 2, 3]);
+
+    manager.delete_task(1, false).unwrap();
+    assert_eq!(manager.find_by_id(3).unwrap(), 2);
+    assert!(matches!(
+        manager.find_by_id(1),
+        Err(TaskError::TaskNotFound { id: 1 })
+    ));
+
+    // A deleted task's ID is not handed out again
+    let index = manager.start_task("Fourth".to_string()).unwrap();
+    assert_eq!(manager.tasks[index].id, 4);
+    let index = manager.split_task(2, 0.5, "Half").unwrap();
+    assert_eq!(manager.tasks[index].id, 5);
+}
+
+#[test]
+fn test_load_assigns_ids_to_old_store() {
+    let dir = std::env::temp_dir().join("tt_task_id_migration");
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("tasks.json");
+
+    let mut old = TaskManager::new();
+    old.start_task("Older".to_string()).unwrap();
+    old.complete_current_task().unwrap();
+    old.start_task("Newer".to_string()).unwrap();
+    old.pause_current_task().unwrap();
+    // Drop the IDs, as a store written before they existed wouldn't have them
+    let mut json: serde_json::Value = serde_json::from_str(&old.snapshot().unwrap()).unwrap();
+    json.as_object_mut().unwrap().remove("next_id");
+    for task in json["tasks"].as_array_mut().unwrap() {
+        task.as_object_mut().unwrap().remove("id");
+    }
+    fs::write(&path, json.to_string()).unwrap();
+
+    let (mut manager, _) =
+        TaskManager::load_or_create(path.clone(), None, 10, RETAIN_DAYS).unwrap();
+    let ids: Vec<u64> = manager.tasks.iter().map(|t| t.id).collect();
+    assert_eq!(ids, vec![1, 2]);
+    let index = manager.start_task("Next".to_string()).unwrap();
+    assert_eq!(manager.tasks[index].id, 3);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_merge_import_reassigns_clashing_ids() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Local".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+
+    let mut other = TaskManager::new();
+    other.start_task("Remote A".to_string()).unwrap();
+    other.complete_current_task().unwrap();
+    other.start_task("Remote B".to_string()).unwrap();
+    other.complete_current_task().unwrap();
+
+    manager.import_tasks(other.tasks, true).unwrap();
+    let ids: Vec<(&str, u64)> = manager
+        .tasks
+        .iter()
+        .map(|t| (t.label.as_str(), t.id))
+        .collect();
+    assert_eq!(ids, vec![("Local", 1), ("Remote A", 3), ("Remote B", 2)]);
+}
+
+#[test]
+fn test_archive_completed_appends_to_archive_file() {
+    let dir = std::env::temp_dir().join("tt_task_archive");
+    fs::remove_dir_all(&dir).ok();
+    let mut manager = TaskManager::new();
+    manager.store_path = Some(dir.join("tasks.json"));
+
+    assert_eq!(manager.archive_completed().unwrap(), 0);
+    assert!(manager.archived_tasks().unwrap().is_empty());
+
+    manager.start_task("Done first".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    manager.start_task("Still going".to_string()).unwrap();
+    manager.start_task("Done second".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    manager.resume_task(2).unwrap();
+
+    assert_eq!(manager.archive_completed().unwrap(), 2);
+    assert_eq!(manager.task_count(), 1);
+    assert_eq!(manager.current_task().unwrap().label, "Still going");
+    assert_eq!(manager.active_task_index, Some(0));
+
+    // The archive file is only written once the task file is saved
+    assert!(!manager.get_archive_path().unwrap().exists());
+    manager.save().unwrap();
+    assert_eq!(manager.archived_tasks().unwrap().len(), 2);
+    assert!(manager.unsaved_archive.is_empty());
+
+    // A second archive adds to the end of the existing file
+    manager.start_task("Done third".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    assert_eq!(manager.archive_completed().unwrap(), 1);
+
+    let labels: Vec<_> = manager
+        .archived_tasks()
+        .unwrap()
+        .into_iter()
+        .map(|task| task.label)
+        .collect();
+    assert_eq!(labels, ["Done first", "Done second", "Done third"]);
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn test_stats_with_mixed_task_states() {
+    let mut manager = TaskManager::new();
+    assert_eq!(manager.stats(), TaskStats::default());
+
+    let mut done = Task::new("Done".to_string(), Vec::new());
+    done.complete().unwrap();
+    done.accumulated_duration = Duration::from_secs(3600);
+    let mut paused = Task::new("Paused".to_string(), Vec::new());
+    paused.pause().unwrap();
+    paused.accumulated_duration = Duration::from_secs(600);
+    let mut also_paused = paused.clone();
+    also_paused.label = "Also paused".to_string();
+    manager
+        .import_tasks(vec![done, paused, also_paused], false)
+        .unwrap();
+    manager.start_task("Running".to_string()).unwrap();
+
+    let stats = manager.stats();
+    assert_eq!(stats.running, 1);
+    assert_eq!(stats.paused, 2);
+    assert_eq!(stats.completed, 1);
+    assert_eq!(stats.total_tasks, 4);
+    // The live session has barely started
+    assert!(stats.total_duration >= Duration::from_secs(4800));
+    assert!(stats.total_duration < Duration::from_secs(4810));
+}
+
+#[test]
+fn test_task_stats_collect_uses_given_durations() {
+    let tasks = vec![
+        Task::new("One".to_string(), Vec::new()),
+        Task::new("Two".to_string(), Vec::new()),
+    ];
+
+    let stats = TaskStats::collect(&tasks, |_| Duration::from_secs(90));
+    assert_eq!(stats.total_tasks, 2);
+    assert_eq!(stats.running, 2);
+    assert_eq!(stats.total_duration, Duration::from_secs(180));
+}
+
+#[test]
+fn test_last_worked_on() {
+    let mut manager = TaskManager::new();
+    assert_eq!(manager.last_worked_on(), None);
+
+    let mut older = Task::new_at(
+        "Older".to_string(),
+        Vec::new(),
+        Utc::now() - chrono::Duration::hours(3),
+    );
+    older.complete().unwrap();
+    let mut newer = Task::new_at(
+        "Newer".to_string(),
+        Vec::new(),
+        Utc::now() - chrono::Duration::hours(2),
+    );
+    newer
+        .pause_at(Utc::now() - chrono::Duration::minutes(90))
+        .unwrap();
+    // Worked on long after the other task, then completed
+    older.sessions = vec![WorkSession {
+        started_at: Utc::now() - chrono::Duration::minutes(30),
+        duration: Duration::from_secs(600),
+        clock_skew: None,
+    }];
+    manager.import_tasks(vec![older, newer], false).unwrap();
+
+    // A completed task still counts, by when its last session ended
+    assert_eq!(manager.last_worked_on(), Some(0));
+
+    manager.resume_task(2).unwrap();
+    assert_eq!(manager.last_worked_on(), Some(1));
+}
+
+#[test]
+fn test_find_by_label() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Docs".to_string()).unwrap();
+    manager.start_task("Write docs".to_string()).unwrap();
+    manager.start_task("Review docs".to_string()).unwrap();
+
+    // A whole-label match beats partial ones
+    assert_eq!(manager.find_by_label("docs").unwrap(), 0);
+    assert_eq!(manager.find_by_label(" write ").unwrap(), 1);
+
+    let err = manager.find_by_label("W").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid state: Several tasks match 'W'; be more specific or use an index:\n  \
+         2. Write docs\n  3. Review docs"
+    );
+    let err = manager.find_by_label("deploy").unwrap_err();
+    assert!(err.to_string().contains("No task label matches 'deploy'"));
+}
+
+#[test]
+fn test_delete_paused_active_task_needs_force() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Kept".to_string()).unwrap();
+    manager.start_task("Dropped".to_string()).unwrap();
+
+    // Running tasks can't be deleted, even with force
+    assert!(manager.delete_task(2, true).is_err());
+
+    manager.pause_current_task().unwrap();
+    let err = manager.delete_task(2, false).unwrap_err();
+    assert!(err.to_string().contains("currently paused"));
+
+    manager.delete_task(2, true).unwrap();
+    assert_eq!(manager.task_count(), 1);
+    assert_eq!(manager.active_task_index, None);
+}
+
+#[test]
+fn test_out_of_bounds_lists_valid_tasks() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Write docs".to_string()).unwrap();
+    manager.start_task("Review".to_string()).unwrap();
+
+    let expected = "Invalid state: Task index 12 is out of bounds. Valid range: 1-2\n\
+                    Valid tasks:\n  1. Write docs\n  2. Review";
+    let err = manager.check_deletable(12, false).unwrap_err();
+    assert_eq!(err.to_string(), expected);
+    let err = manager.rename_task(12, "New".to_string()).unwrap_err();
+    assert_eq!(err.to_string(), expected);
+}
+
+#[test]
+fn test_short_ids_and_id_prefixes() {
+    let mut manager = TaskManager::new();
+    for (label, id) in [("A", 1), ("B", 12), ("C", 345), ("D", 3467)] {
+        manager.start_task(label.to_string()).unwrap();
+        manager.complete_current_task().unwrap();
+        manager.tasks.last_mut().unwrap().id = id;
+    }
+
+    let short = manager.short_ids();
+    assert_eq!(short[&1], "1");
+    assert_eq!(short[&12], "12");
+    assert_eq!(short[&345], "345");
+    assert_eq!(short[&3467], "346");
+
+    // Each shortened ID finds its own task
+    for (position, task) in manager.tasks.iter().enumerate() {
+        assert_eq!(
+            manager
+                .find_by_id(short[&task.id].parse().unwrap())
+                .unwrap(),
+            position + 1
+        );
+    }
+    assert!(matches!(
+        manager.find_by_id(34),
+        Err(TaskError::InvalidState { .. })
+    ));
+    assert!(matches!(
+        manager.find_by_id(5),
+        Err(TaskError::TaskNotFound { id: 5 })
+    ));
+}
@@ -1,11 +1,21 @@
 use super::*;
+use crate::task::{Priority, Task, WorkSession};
 
 #[test]
 fn test_handle_start_command() {
     let mut manager = TaskManager::new();
-    let command = Commands::Start { label: "Test Task".to_string() };
+    let command = Commands::Start {
+        label: vec!["Test Task".to_string()],
+        project: None,
+        tags: Vec::new(),
+        priority: None,
+        at: None,
+        force: false,
+        goal: None,
+        estimate: None,
+    };
 
-    let result = handle_command(&mut manager, command);
+    let result = handle_command(&mut manager, command, &Settings::default());
     assert!(result.is_ok());
     assert!(result.unwrap().contains("Started task: 'Test Task'"));
     assert_eq!(manager.task_count(), 1);
@@ -16,19 +26,56 @@ fn test_handle_pause_command() {
     let mut manager = TaskManager::new();
     manager.start_task("Test Task".to_string()).unwrap();
 
-    let command = Commands::Pause;
-    let result = handle_command(&mut manager, command);
+    let command = Commands::Pause { all: false, note: None };
+    let result = handle_command(&mut manager, command, &Settings::default());
 
     assert!(result.is_ok());
     assert!(result.unwrap().contains("Paused task"));
 }
 
+#[test]
+fn test_handle_pause_command_with_note() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Test Task".to_string()).unwrap();
+
+    let command = Commands::Pause {
+        all: false,
+        note: Some("Stopped at step 3".to_string()),
+    };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert!(output.ends_with("\n  Note: Stopped at step 3"));
+    assert_eq!(
+        manager.current_task().unwrap().note.as_deref(),
+        Some("Stopped at step 3")
+    );
+
+    // The note stays visible after resuming, until a new one replaces it
+    manager.resume_current_task().unwrap();
+    let command = Commands::Status {
+        label_max: None,
+        porcelain: false,
+        utc: false,
+    };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert!(output.contains("Note: Stopped at step 3"));
+
+    let command = Commands::Pause {
+        all: false,
+        note: Some("Step 4 next".to_string()),
+    };
+    handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(
+        manager.current_task().unwrap().note.as_deref(),
+        Some("Step 4 next")
+    );
+}
+
 #[test]
 fn test_handle_pause_command_no_active_task() {
     let mut manager = TaskManager::new();
-    let command = Commands::Pause;
+    let command = Commands::Pause { all: false, note: None };
 
-    let result = handle_command(&mut manager, command);
+    let result = handle_command(&mut manager, command, &Settings::default());
     assert!(result.is_err());
     // Check that anyhow error contains the TaskError::NoActiveTask message
     assert!(result
@@ -37,14 +84,45 @@ fn test_handle_pause_command_no_active_task() {
         .contains("No active task to operate on"));
 }
 
+#[test]
+fn test_handle_pause_all_command() {
+    let mut manager = TaskManager::new();
+    let command = Commands::Pause { all: true, note: None };
+    let result = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(result, "No running tasks to pause");
+
+    manager.start_task("Test Task".to_string()).unwrap();
+    let command = Commands::Pause { all: true, note: None };
+    let result = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(result, "Paused 1 running task(s)");
+    assert!(!manager.has_running_task());
+}
+
+#[test]
+fn test_handle_split_command() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Meeting".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+
+    let command = Commands::Split {
+        index: 1,
+        ratio: 0.5,
+        new_label: "Planning".to_string(),
+    };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert!(output.starts_with("Split "));
+    assert!(output.contains("from 'Meeting' into new task 2: 'Planning'"));
+    assert_eq!(manager.task_count(), 2);
+}
+
 #[test]
 fn test_handle_resume_command() {
     let mut manager = TaskManager::new();
     manager.start_task("Test Task".to_string()).unwrap();
     manager.pause_current_task().unwrap();
 
-    let command = Commands::Resume;
-    let result = handle_command(&mut manager, command);
+    let command = Commands::Resume { index: None };
+    let result = handle_command(&mut manager, command, &Settings::default());
 
     assert!(result.is_ok());
     assert!(result.unwrap().contains("Resumed task"));
@@ -55,8 +133,12 @@ fn test_handle_status_command() {
     let mut manager = TaskManager::new();
     manager.start_task("Test Task".to_string()).unwrap();
 
-    let command = Commands::Status;
-    let result = handle_command(&mut manager, command);
+    let command = Commands::Status {
+        label_max: None,
+        porcelain: false,
+        utc: false,
+    };
+    let result = handle_command(&mut manager, command, &Settings::default());
 
     assert!(result.is_ok());
     let status = result.unwrap();
@@ -67,9 +149,13 @@ fn test_handle_status_command() {
 #[test]
 fn test_handle_status_command_no_active_task() {
     let mut manager = TaskManager::new();
-    let command = Commands::Status;
+    let command = Commands::Status {
+        label_max: None,
+        porcelain: false,
+        utc: false,
+    };
 
-    let result = handle_command(&mut manager, command);
+    let result = handle_command(&mut manager, command, &Settings::default());
     assert!(result.is_ok());
     assert_eq!(result.unwrap(), "No active task");
 }
@@ -80,8 +166,23 @@ fn test_handle_list_command() {
     manager.start_task("Task 1".to_string()).unwrap();
     manager.start_task("Task 2".to_string()).unwrap();
 
-    let command = Commands::List;
-    let result = handle_command(&mut manager, command);
+    let command = Commands::List {
+        relative: false,
+        sort: None,
+        reverse: false,
+        group_by: None,
+        round_sessions: None,
+        round: None,
+        no_float: false,
+        utc: false,
+        plain: false,
+        since: None,
+        until: None,
+        format: display::ListFormat::Detailed,
+        limit: None,
+        ids: false,
+    };
+    let result = handle_command(&mut manager, command, &Settings::default());
 
     assert!(result.is_ok());
     let list = result.unwrap();
@@ -90,13 +191,64 @@ fn test_handle_list_command() {
     assert!(list.contains("Task 2"));
 }
 
+#[test]
+fn test_handle_list_command_date_range() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Task 1".to_string()).unwrap();
+    let today = Local::now().date_naive();
+    let day = |s: &str| Some(s.parse::<chrono::NaiveDate>().unwrap());
+
+    let list = |since, until| Commands::List {
+        relative: false,
+        sort: None,
+        reverse: false,
+        group_by: None,
+        round_sessions: None,
+        round: None,
+        no_float: false,
+        utc: false,
+        plain: false,
+        since,
+        until,
+        format: display::ListFormat::Detailed,
+        limit: None,
+        ids: false,
+    };
+
+    let output = handle_command(&mut manager, list(Some(today), None), &Settings::default());
+    assert!(output.unwrap().contains("1. Task 1"));
+
+    let output = handle_command(
+        &mut manager,
+        list(None, day("2020-01-31")),
+        &Settings::default(),
+    );
+    assert_eq!(output.unwrap(), "No matching tasks found");
+
+    let result = handle_command(
+        &mut manager,
+        list(day("2020-02-01"), day("2020-01-01")),
+        &Settings::default(),
+    );
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("--since 2020-02-01 is after --until 2020-01-01"));
+}
+
 #[test]
 fn test_handle_complete_command() {
     let mut manager = TaskManager::new();
     manager.start_task("Test Task".to_string()).unwrap();
 
-    let command = Commands::Complete;
-    let result = handle_command(&mut manager, command);
+    let command = Commands::Complete {
+        index: None,
+        id: None,
+        cap_session: None,
+        note: None,
+        no_journal: false,
+    };
+    let result = handle_command(&mut manager, command, &Settings::default());
 
     assert!(result.is_ok());
     assert!(result.unwrap().contains("Completed task: 'Test Task'"));
@@ -113,8 +265,14 @@ fn test_handle_complete_command() {
 fn test_handle_complete_command_no_active_task() {
     let mut manager = TaskManager::new();
 
-    let command = Commands::Complete;
-    let result = handle_command(&mut manager, command);
+    let command = Commands::Complete {
+        index: None,
+        id: None,
+        cap_session: None,
+        note: None,
+        no_journal: false,
+    };
+    let result = handle_command(&mut manager, command, &Settings::default());
 
     assert!(result.is_err());
     assert!(result
@@ -129,8 +287,14 @@ fn test_handle_complete_command_paused_task() {
     manager.start_task("Test Task".to_string()).unwrap();
     manager.pause_current_task().unwrap();
 
-    let command = Commands::Complete;
-    let result = handle_command(&mut manager, command);
+    let command = Commands::Complete {
+        index: None,
+        id: None,
+        cap_session: None,
+        note: None,
+        no_journal: false,
+    };
+    let result = handle_command(&mut manager, command, &Settings::default());
 
     assert!(result.is_ok());
     assert!(result.unwrap().contains("Completed task: 'Test Task'"));
@@ -141,3 +305,698 @@ fn test_handle_complete_command_paused_task() {
     // Task should be completed
     assert!(manager.all_tasks()[0].is_completed());
 }
+
+#[test]
+fn test_handle_abort_all_command_forced() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Done Task".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    manager.start_task("Paused Task".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.start_task("Running Task".to_string()).unwrap();
+
+    let command = Commands::Abort { all: true, force: true };
+    let result = handle_command(&mut manager, command, &Settings::default());
+
+    assert!(result.is_ok());
+    assert_eq!(result.unwrap(), "2 in-progress task(s) aborted");
+    assert_eq!(manager.task_count(), 1);
+    assert!(manager.all_tasks()[0].is_completed());
+    assert!(manager.current_task().is_none());
+}
+
+#[test]
+fn test_handle_abort_command_requires_all_flag() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Running Task".to_string()).unwrap();
+
+    let command = Commands::Abort { all: false, force: true };
+    let result = handle_command(&mut manager, command, &Settings::default());
+
+    assert!(result.is_err());
+    assert!(result.unwrap_err().to_string().contains("--all"));
+    assert_eq!(manager.task_count(), 1);
+}
+
+#[test]
+fn test_handle_info_command_with_explain() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Test Task".to_string()).unwrap();
+
+    let command = Commands::Info {
+        query: "1".to_string(),
+        explain: true,
+        utc: false,
+    };
+    let result = handle_command(&mut manager, command, &Settings::default());
+
+    assert!(result.is_ok());
+    let output = result.unwrap();
+    assert!(output.contains("Task 1: Test Task"));
+    assert!(output.contains("Duration breakdown:"));
+    assert!(output.contains("Live session (started"));
+}
+
+#[test]
+fn test_handle_info_command_invalid_index() {
+    let mut manager = TaskManager::new();
+
+    let command = Commands::Info {
+        query: "1".to_string(),
+        explain: false,
+        utc: false,
+    };
+    let result = handle_command(&mut manager, command, &Settings::default());
+
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("No tasks available to inspect"));
+}
+
+#[test]
+fn test_handle_info_command_by_label() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Write docs".to_string()).unwrap();
+    manager.start_task("Review docs".to_string()).unwrap();
+
+    let info = |query: &str| Commands::Info {
+        query: query.to_string(),
+        explain: false,
+        utc: false,
+    };
+    let output = handle_command(&mut manager, info("REVIEW"), &Settings::default()).unwrap();
+    assert!(output.starts_with("Task 2: Review docs\n"));
+    assert!(output.contains("  Sessions: 1 (0 recorded)"));
+
+    let err = handle_command(&mut manager, info("docs"), &Settings::default()).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Invalid state: Several tasks match 'docs'; be more specific or use an index:\n  \
+         1. Write docs\n  2. Review docs"
+    );
+}
+
+#[test]
+fn test_handle_info_command_numeric_label() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Write docs".to_string()).unwrap();
+    manager.start_task("2024 report".to_string()).unwrap();
+
+    let info = |query: &str| Commands::Info {
+        query: query.to_string(),
+        explain: false,
+        utc: false,
+    };
+    // An index that exists still wins
+    let output = handle_command(&mut manager, info("1"), &Settings::default()).unwrap();
+    assert!(output.starts_with("Task 1: Write docs\n"));
+
+    // Out of range, so the number is looked up as a label instead
+    let output = handle_command(&mut manager, info("2024"), &Settings::default()).unwrap();
+    assert!(output.starts_with("Task 2: 2024 report\n"));
+
+    // Matching neither reports the bad index
+    let err = handle_command(&mut manager, info("7"), &Settings::default()).unwrap_err();
+    assert!(err.to_string().contains("out of bounds"));
+}
+
+#[test]
+fn test_handle_tidy_sessions_command() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Test Task".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.resume_current_task().unwrap();
+    manager.pause_current_task().unwrap();
+
+    let command = Commands::TidySessions { gap: 60, trim_overlaps: false };
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert_eq!(result.unwrap(), "Merged 1 session(s) into their neighbours");
+
+    let command = Commands::TidySessions { gap: 60, trim_overlaps: false };
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert_eq!(result.unwrap(), "No sessions to merge");
+}
+
+#[test]
+fn test_handle_tidy_sessions_trim_overlaps() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Test Task".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.resume_current_task().unwrap();
+    manager.pause_current_task().unwrap();
+
+    // Make the second session start before the first one ends
+    let mut tasks_json = serde_json::to_value(&manager).unwrap();
+    let first_start = tasks_json["tasks"][0]["sessions"][0]["started_at"].clone();
+    tasks_json["tasks"][0]["sessions"][0]["duration"] =
+        serde_json::json!({ "secs": 600, "nanos": 0 });
+    tasks_json["tasks"][0]["sessions"][1]["started_at"] = first_start;
+    tasks_json["tasks"][0]["sessions"][1]["duration"] =
+        serde_json::json!({ "secs": 900, "nanos": 0 });
+    let mut manager: TaskManager = serde_json::from_value(tasks_json).unwrap();
+
+    let command = Commands::TidySessions { gap: 60, trim_overlaps: true };
+    let result = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert!(result.contains("Trimmed 10m 0s of overlapping session time"));
+    assert!(result.contains("Merged 1 session(s) into their neighbours"));
+    assert_eq!(manager.all_tasks()[0].sessions.len(), 1);
+}
+
+#[test]
+fn test_parse_default_command() {
+    let parse = |value: &str| parse_default_command(Vec::new(), value).and_then(|cli| cli.command);
+    assert!(matches!(
+        parse("list --relative"),
+        Some(Commands::List { relative: true, .. })
+    ));
+    assert!(matches!(
+        parse("status"),
+        Some(Commands::Status {
+            label_max: None,
+            porcelain: false,
+            utc: false
+        })
+    ));
+    assert!(parse("bogus").is_none());
+    assert!(parse("--strict-labels").is_none());
+}
+
+#[test]
+fn test_default_command_keeps_global_flags() {
+    let args = vec![
+        OsString::from("--store"),
+        OsString::from("/tmp/x.json"),
+        OsString::from("--quiet"),
+    ];
+    let cli = Cli::try_parse_from(["tt", "--store", "/tmp/x.json", "--quiet"]).unwrap();
+    let cli = default_command(cli, args.clone(), "--json list --relative");
+    assert_eq!(cli.store, Some(std::path::PathBuf::from("/tmp/x.json")));
+    assert!(cli.quiet && cli.json);
+    assert!(matches!(
+        cli.command,
+        Some(Commands::List { relative: true, .. })
+    ));
+
+    // An invalid default keeps the original flags, for status to use
+    let cli = Cli::try_parse_from(["tt", "--store", "/tmp/x.json", "--quiet"]).unwrap();
+    let cli = default_command(cli, args, "bogus");
+    assert_eq!(cli.store, Some(std::path::PathBuf::from("/tmp/x.json")));
+    assert!(cli.command.is_none());
+}
+
+#[test]
+fn test_handle_start_command_rejects_duplicate_label() {
+    let mut manager = TaskManager::new();
+    manager.start_task("emails".to_string()).unwrap();
+    manager.start_task("Standup".to_string()).unwrap();
+
+    let start = |force| Commands::Start {
+        label: vec!["emails".to_string()],
+        project: None,
+        tags: Vec::new(),
+        priority: None,
+        at: None,
+        force,
+        goal: None,
+        estimate: None,
+    };
+
+    let result = handle_command(&mut manager, start(false), &Settings::default());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("resume it with `tt resume 1`, or pass --force"));
+    assert_eq!(manager.task_count(), 2);
+
+    let result = handle_command(&mut manager, start(true), &Settings::default());
+    assert!(result.unwrap().contains("Started task: 'emails'"));
+    assert_eq!(manager.task_count(), 3);
+}
+
+#[test]
+fn test_handle_start_command_with_project() {
+    let mut manager = TaskManager::new();
+    let command = Commands::Start {
+        label: vec!["Landing page".to_string()],
+        project: Some("Web".to_string()),
+        tags: Vec::new(),
+        priority: None,
+        at: None,
+        force: false,
+        goal: None,
+        estimate: None,
+    };
+
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert!(result.is_ok());
+    assert_eq!(manager.all_tasks()[0].project.as_deref(), Some("Web"));
+}
+
+#[test]
+fn test_handle_start_and_set_priority() {
+    let mut manager = TaskManager::new();
+    let command = Commands::Start {
+        label: vec!["Hotfix".to_string()],
+        project: None,
+        tags: Vec::new(),
+        priority: Some(Priority::High),
+        at: None,
+        force: false,
+        goal: None,
+        estimate: None,
+    };
+    handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(manager.all_tasks()[0].priority, Priority::High);
+
+    let command = Commands::SetPriority { index: 1, level: Priority::Low };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert_eq!(output, "Set priority of 'Hotfix' to low");
+    assert_eq!(manager.all_tasks()[0].priority, Priority::Low);
+
+    let command = Commands::Estimate {
+        index: 1,
+        duration: Duration::from_secs(2 * 60 * 60),
+    };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(output, "Set estimate of 'Hotfix' to 2h 0m 0s");
+}
+
+#[test]
+fn test_handle_clone_command() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Standup".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+
+    let command = Commands::Clone { index: 1 };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert_eq!(output, "Started task: 'Standup' (copied from task 1)");
+    assert_eq!(manager.current_task().unwrap().label, "Standup");
+}
+
+#[test]
+fn test_handle_merge_command() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Split work".to_string()).unwrap();
+    manager.start_task("Split work (oops)".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+
+    let command = Commands::Merge { into: 1, from: 2, trim_overlaps: false };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert!(output.starts_with("Merged 'Split work (oops)' into 'Split work' (added "));
+    assert_eq!(manager.task_count(), 1);
+}
+
+/// A paused task with one session of `minutes` starting `start`
+fn task_with_session(label: &str, start: chrono::DateTime<Utc>, minutes: u64) -> Task {
+    let mut task = Task::new_at(label.to_string(), Vec::new(), start);
+    task.pause().unwrap();
+    let duration = Duration::from_secs(minutes * 60);
+    task.sessions = vec![WorkSession {
+        started_at: start,
+        duration,
+        clock_skew: None,
+    }];
+    task.accumulated_duration = duration;
+    task
+}
+
+#[test]
+fn test_handle_merge_command_trims_overlaps() {
+    let start = Utc::now() - chrono::Duration::hours(3);
+    let tasks = || {
+        vec![
+            task_with_session("Laptop", start, 30),
+            task_with_session("Desktop", start + chrono::Duration::minutes(20), 30),
+        ]
+    };
+
+    // Without the flag the overlap is only reported
+    let mut manager = TaskManager::new();
+    manager.import_tasks(tasks(), false).unwrap();
+    let command = Commands::Merge { into: 1, from: 2, trim_overlaps: false };
+    handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(manager.session_overlaps(), vec![(0, 0, 1)]);
+
+    let mut manager = TaskManager::new();
+    manager.import_tasks(tasks(), false).unwrap();
+    let command = Commands::Merge { into: 1, from: 2, trim_overlaps: true };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert!(output.ends_with("\nTrimmed 10m 0s of overlapping session time"));
+    assert!(manager.session_overlaps().is_empty());
+    let task = &manager.all_tasks()[0];
+    assert_eq!(task.total_duration(), Duration::from_secs(50 * 60));
+    assert_eq!(
+        task.sessions[1].started_at,
+        start + chrono::Duration::minutes(30)
+    );
+}
+
+#[test]
+fn test_handle_restart_command_reports_discarded_time() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Reset me".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+    manager.add_time(1, Duration::from_secs(20 * 60)).unwrap();
+
+    let command = Commands::Restart { index: 1 };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert!(output.starts_with("Restarted 'Reset me' from zero (discarded 20m "));
+    assert_eq!(manager.all_tasks()[0].total_duration(), Duration::ZERO);
+}
+
+#[test]
+fn test_handle_check_command_fail_if_running() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Forgotten Timer".to_string()).unwrap();
+
+    let command = Commands::Check { fail_if_running: true };
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert!(result.is_err());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("1 task(s) still running: 1. Forgotten Timer"));
+
+    manager.complete_current_task().unwrap();
+
+    let command = Commands::Check { fail_if_running: true };
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert_eq!(result.unwrap(), "No running tasks");
+}
+
+#[test]
+fn test_handle_config_show_command() {
+    let mut manager = TaskManager::new();
+
+    let command = Commands::Config { action: ConfigAction::Show };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert!(output.starts_with("Effective settings:"));
+    assert!(output.contains("default_command       = status (default)"));
+    assert!(output.contains("strict_labels         = false (default)"));
+    assert!(output.contains("max_tasks             = 10 (default)"));
+}
+
+#[test]
+fn test_handle_list_command_floats_active_tasks() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Done Task".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    manager.start_task("Running Task".to_string()).unwrap();
+
+    let list = |no_float| Commands::List {
+        relative: false,
+        sort: None,
+        reverse: false,
+        group_by: None,
+        round_sessions: None,
+        round: None,
+        no_float,
+        utc: false,
+        plain: false,
+        since: None,
+        until: None,
+        format: display::ListFormat::Detailed,
+        limit: None,
+        ids: false,
+    };
+
+    let floated = handle_command(&mut manager, list(false), &Settings::default()).unwrap();
+    assert!(floated.find("2. Running Task").unwrap() < floated.find("1. Done Task").unwrap());
+
+    let stored = handle_command(&mut manager, list(true), &Settings::default()).unwrap();
+    assert!(stored.find("1. Done Task").unwrap() < stored.find("2. Running Task").unwrap());
+}
+
+#[test]
+fn test_handle_list_command_sort_disables_floating() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Done Task".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    manager.start_task("Running Task".to_string()).unwrap();
+
+    let command = Commands::List {
+        relative: false,
+        sort: Some(display::SortKey::Created),
+        reverse: false,
+        group_by: None,
+        round_sessions: None,
+        round: None,
+        no_float: false,
+        utc: false,
+        plain: false,
+        since: None,
+        until: None,
+        format: display::ListFormat::Detailed,
+        limit: None,
+        ids: false,
+    };
+
+    let sorted = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert!(sorted.find("1. Done Task").unwrap() < sorted.find("2. Running Task").unwrap());
+}
+
+#[test]
+fn test_handle_parse_duration_command() {
+    let mut manager = TaskManager::new();
+
+    let command = Commands::ParseDuration { input: "1h30m".to_string() };
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert_eq!(result.unwrap(), "5400 seconds (1h 30m 0s)");
+
+    let command = Commands::ParseDuration { input: "1x".to_string() };
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Invalid duration '1x'"));
+}
+
+#[test]
+fn test_handle_filter_command() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Done Task".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    manager.start_task("Running Task".to_string()).unwrap();
+
+    let command = Commands::Filter { status: "Completed".to_string() };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert!(output.contains("Task Summary (1 tasks)"));
+    assert!(output.contains("1. Done Task"));
+    assert!(!output.contains("Running Task"));
+    assert!(output.contains("Running: 0 | Paused: 0 | Completed: 1"));
+
+    let command = Commands::Filter { status: "paused".to_string() };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(output, "No matching tasks found");
+}
+
+#[test]
+fn test_handle_total_command() {
+    let mut manager = TaskManager::new();
+    let total = |manager: &mut TaskManager, seconds| {
+        handle_command(manager, Commands::Total { seconds }, &Settings::default()).unwrap()
+    };
+    assert_eq!(total(&mut manager, false), "0s");
+    assert_eq!(total(&mut manager, true), "0");
+
+    let mut first = Task::new("First".to_string(), Vec::new());
+    first.complete().unwrap();
+    first.sessions.clear();
+    first.accumulated_duration = Duration::from_secs(3600);
+    let mut second = first.clone();
+    second.label = "Second".to_string();
+    second.accumulated_duration = Duration::from_secs(125);
+    manager.import_tasks(vec![first, second], false).unwrap();
+
+    assert_eq!(total(&mut manager, false), "1h 2m 5s");
+    assert_eq!(total(&mut manager, true), "3725");
+}
+
+#[test]
+fn test_handle_last_command() {
+    let mut manager = TaskManager::new();
+    let output = handle_command(&mut manager, Commands::Last, &Settings::default()).unwrap();
+    assert_eq!(output, "No tasks yet");
+
+    manager.start_task("Earlier".to_string()).unwrap();
+    manager.start_task("Wrap up".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    let output = handle_command(&mut manager, Commands::Last, &Settings::default()).unwrap();
+    assert!(output.starts_with("Task 2: Wrap up"));
+}
+
+#[test]
+fn test_handle_watch_without_active_task() {
+    let mut manager = TaskManager::new();
+    let output = handle_command(&mut manager, Commands::Watch, &Settings::default()).unwrap();
+    assert_eq!(output, "No active task to watch");
+
+    manager.start_task("Done".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    let output = handle_command(&mut manager, Commands::Watch, &Settings::default()).unwrap();
+    assert_eq!(output, "No active task to watch");
+}
+
+#[test]
+fn test_handle_today_command() {
+    let mut manager = TaskManager::new();
+    let mut old = Task::new_at(
+        "Last Week".to_string(),
+        Vec::new(),
+        Utc::now() - chrono::Duration::days(7),
+    );
+    old.complete().unwrap();
+    // Pretend the work finished last week rather than just now
+    old.sessions.clear();
+    old.accumulated_duration = Duration::from_secs(3600);
+    manager.import_tasks(vec![old], false).unwrap();
+
+    let output = handle_command(&mut manager, Commands::Today, &Settings::default()).unwrap();
+    assert_eq!(output, "No tasks worked on today");
+
+    manager.start_task("Standup".to_string()).unwrap();
+    let output = handle_command(&mut manager, Commands::Today, &Settings::default()).unwrap();
+    assert!(output.contains("Task Summary (1 tasks)"));
+    assert!(output.contains("2. Standup"));
+    assert!(!output.contains("Last Week"));
+    assert!(!output.contains("Note:"));
+}
+
+#[test]
+fn test_handle_filter_command_invalid_status() {
+    let mut manager = TaskManager::new();
+
+    let command = Commands::Filter { status: "done".to_string() };
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Invalid status 'done'; expected one of: running, paused, completed"));
+}
+
+#[test]
+fn test_handle_add_time_command() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Test Task".to_string()).unwrap();
+    manager.pause_current_task().unwrap();
+
+    let command = Commands::AddTime { index: 1, duration: "1h30m".to_string() };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert!(output.starts_with("Added 1h 30m 0s to 'Test Task' (total 1h 30m 0s"));
+
+    let command = Commands::AddTime { index: 1, duration: "soon".to_string() };
+    let result = handle_command(&mut manager, command, &Settings::default());
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("Invalid duration 'soon'"));
+}
+
+#[test]
+fn test_handle_complete_command_with_index() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Task A".to_string()).unwrap();
+    manager.start_task("Task B".to_string()).unwrap();
+
+    let command = Commands::Complete {
+        index: Some(1),
+        id: None,
+        cap_session: None,
+        note: None,
+        no_journal: false,
+    };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert_eq!(output, "Completed task: 'Task A'");
+    assert!(manager.all_tasks()[0].is_completed());
+    assert_eq!(manager.current_task().unwrap().label, "Task B");
+}
+
+#[test]
+fn test_handle_resume_command_with_index() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Task A".to_string()).unwrap();
+    manager.start_task("Task B".to_string()).unwrap();
+
+    let command = Commands::Resume { index: Some(1) };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+
+    assert!(output.contains("Resumed task. Current Task: Task A"));
+    assert!(manager.all_tasks()[1].is_paused());
+}
+
+#[test]
+fn test_handle_note_command() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Test Task".to_string()).unwrap();
+
+    let command = Commands::Note { index: 1, text: "Shipped it".to_string() };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(output, "Added note to 'Test Task'");
+
+    let command = Commands::Note { index: 1, text: String::new() };
+    let output = handle_command(&mut manager, command, &Settings::default()).unwrap();
+    assert_eq!(output, "Cleared note on 'Test Task'");
+    assert!(manager.all_tasks()[0].note.is_none());
+}
+
+#[test]
+fn test_handle_stop_command() {
+    let mut manager = TaskManager::new();
+
+    let output = handle_command(&mut manager, Commands::Stop, &Settings::default()).unwrap();
+    assert_eq!(output, "Nothing is running; no tasks were paused");
+
+    manager.start_task("Test Task".to_string()).unwrap();
+    let output = handle_command(&mut manager, Commands::Stop, &Settings::default()).unwrap();
+    assert_eq!(output, "Paused 1 running task(s)");
+    assert!(manager.all_tasks()[0].is_paused());
+}
+
+#[test]
+fn test_exit_code_by_error_category() {
+    assert_eq!(exit_code(&TaskError::NoActiveTask), 10);
+    assert_eq!(
+        exit_code(&TaskError::InvalidState {
+            message: "Task index 9 is out of bounds".to_string()
+        }),
+        11
+    );
+    assert_eq!(exit_code(&TaskError::TaskCompleted), 11);
+    assert_eq!(
+        exit_code(&TaskError::IoError(io::Error::other("disk full"))),
+        12
+    );
+    assert_eq!(exit_code(&TaskError::InvalidDuration), 1);
+}
+
+#[test]
+fn test_describe_transitions() {
+    let mut manager = TaskManager::new();
+    manager.start_task("Old task".to_string()).unwrap();
+    manager.complete_current_task().unwrap();
+    manager.start_task("Write docs".to_string()).unwrap();
+    let before = manager.all_tasks().to_vec();
+
+    manager.delete_task(1, false).unwrap();
+    manager.start_task("Review".to_string()).unwrap();
+
+    assert_eq!(
+        describe_transitions(&before, manager.all_tasks()),
+        vec![
+            "paused task index 1 ('Write docs')",
+            "added task index 2 ('Review', running)",
+            "removed task 'Old task'",
+        ]
+    );
+    assert!(describe_transitions(manager.all_tasks(), manager.all_tasks()).is_empty());
+}
@@ -0,0 +1,84 @@
+//! ==================== Abort Command Tests ====================
+use predicates::prelude::*;
+pub mod common;
+use common::{fresh_test_command, test_command};
+
+#[test]
+fn test_cli_abort_all_with_mixed_statuses() {
+    let test_name = "abort_all_mixed_statuses";
+
+    // Completed task
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Finished Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    // Paused task
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Paused Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("pause");
+    cmd.assert().success();
+
+    // Running task
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Running Task");
+    cmd.assert().success();
+
+    // Abort everything in progress
+    let mut cmd = test_command(test_name);
+    cmd.arg("abort").arg("--all").arg("--force");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2 in-progress task(s) aborted"));
+
+    // Only the completed task remains
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Task Summary (1 tasks)"))
+        .stdout(predicate::str::contains("Finished Task"))
+        .stdout(predicate::str::contains("Paused Task").not())
+        .stdout(predicate::str::contains("Running Task").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No active task"));
+}
+
+#[test]
+fn test_cli_abort_all_without_confirmation_keeps_tasks() {
+    let test_name = "abort_all_not_confirmed";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Keep Me");
+    cmd.assert().success();
+
+    // Answering "no" leaves the store untouched
+    let mut cmd = test_command(test_name);
+    cmd.arg("abort").arg("--all").write_stdin("n\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Abort cancelled"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Current Task: Keep Me"));
+
+    // Answering "yes" proceeds
+    let mut cmd = test_command(test_name);
+    cmd.arg("abort").arg("--all").write_stdin("y\n");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 in-progress task(s) aborted"));
+}
@@ -0,0 +1,84 @@
+//! ==================== Clean Command Tests ====================
+use predicates::prelude::*;
+pub mod common;
+use common::{fresh_test_command, test_command};
+
+#[test]
+fn test_cli_clean_keep_active() {
+    let test_name = "clean_keep_active";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Old Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Current Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("clean").arg("--keep-active").arg("--force");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "1 task(s) removed, kept active task 'Current Task'",
+    ));
+
+    // The active task is the only one left, at index 1
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Task Summary (1 tasks)"))
+        .stdout(predicate::str::contains("1. Current Task"))
+        .stdout(predicate::str::contains("Old Task").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("pause");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Current Task: Current Task"));
+}
+
+#[test]
+fn test_cli_clean_removes_everything() {
+    let test_name = "clean_removes_everything";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Running Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("clean").arg("--force");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1 task(s) removed"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks found"));
+}
+
+#[test]
+fn test_cli_clean_without_confirmation_keeps_tasks() {
+    let test_name = "clean_not_confirmed";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Precious Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("clean").write_stdin("n\n");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Clean cancelled, no tasks were removed",
+    ));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Precious Task"));
+}
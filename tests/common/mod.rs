@@ -1,5 +1,5 @@
 use assert_cmd::cargo::cargo_bin_cmd;
-use std::{env, fs};
+use std::{env, fs, path::PathBuf};
 
 /// Create a command with test-specific config directory that persists across calls within the same test
 pub fn test_command(test_name: &str) -> assert_cmd::Command {
@@ -16,6 +16,14 @@ pub fn test_command(test_name: &str) -> assert_cmd::Command {
     cmd
 }
 
+/// Path of the task store used by commands created for `test_name`
+pub fn test_store_path(test_name: &str) -> PathBuf {
+    env::temp_dir()
+        .join("tt_tests")
+        .join(test_name)
+        .join("tasks.json")
+}
+
 /// Create a FRESH command that cleans the test directory first
 pub fn fresh_test_command(test_name: &str) -> assert_cmd::Command {
     cleanup_test_dir(test_name);
@@ -0,0 +1,113 @@
+//! ==================== Complete Command Tests ====================
+use chrono::{Duration, Utc};
+use predicates::prelude::*;
+use std::fs;
+pub mod common;
+use common::{fresh_test_command, test_command, test_store_path};
+
+/// Starts a task and rewrites the store so its session began `hours` ago
+fn start_task_running_for(test_name: &str, label: &str, hours: i64) {
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg(label);
+    cmd.assert().success();
+
+    let path = test_store_path(test_name);
+    let mut store: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+    let started_at = Utc::now() - Duration::hours(hours);
+    store["tasks"][0]["started_at"] = serde_json::json!(started_at);
+    fs::write(&path, serde_json::to_string(&store).unwrap()).unwrap();
+}
+
+#[test]
+fn test_cli_complete_long_session_non_tty_proceeds() {
+    let test_name = "complete_long_session_non_tty";
+    start_task_running_for(test_name, "Forgotten Timer", 9);
+
+    // stdin is not a terminal, so no prompt is shown and the full time is kept
+    let mut cmd = test_command(test_name);
+    cmd.env("TT_CONFIRM_LONG_COMPLETE", "8h").arg("complete");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Completed task: 'Forgotten Timer'",
+        ))
+        .stderr(predicate::str::contains("[y/N]").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Forgotten Timer [✅ Completed] - 9h 0m",
+    ));
+}
+
+#[test]
+fn test_cli_complete_cap_session() {
+    let test_name = "complete_cap_session";
+    start_task_running_for(test_name, "Forgotten Timer", 9);
+
+    let mut cmd = test_command(test_name);
+    cmd.env("TT_CONFIRM_LONG_COMPLETE", "8h")
+        .arg("complete")
+        .arg("--cap-session")
+        .arg("1h30m");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Completed task: 'Forgotten Timer' (discarded 7h 30m",
+    ));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Forgotten Timer [✅ Completed] - 1h 30m 0s",
+    ));
+}
+
+#[test]
+fn test_cli_complete_rejects_invalid_cap() {
+    let mut cmd = fresh_test_command("complete_invalid_cap");
+
+    cmd.arg("complete").arg("--cap-session").arg("1x");
+
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid duration '1x'"));
+}
+
+#[test]
+fn test_cli_complete_with_note_appends_journal_entry() {
+    let test_name = "complete_note_journal";
+    start_task_running_for(test_name, "Write report", 2);
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete").arg("--note").arg("Sent to the team");
+    cmd.assert().success();
+
+    let journal_path = test_store_path(test_name).with_file_name("journal.md");
+    let journal = fs::read_to_string(&journal_path).unwrap();
+    let lines: Vec<&str> = journal.lines().collect();
+    assert_eq!(lines.len(), 1);
+
+    let today = Utc::now().format("%Y-%m-%d").to_string();
+    assert!(lines[0].starts_with(&format!("- {} ", today)));
+    assert!(lines[0].ends_with("  Write report (2h): Sent to the team"));
+}
+
+#[test]
+fn test_cli_complete_no_journal_skips_journal() {
+    let test_name = "complete_no_journal";
+    start_task_running_for(test_name, "Quiet Task", 1);
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["complete", "--note", "Nothing to see", "--no-journal"]);
+    cmd.assert().success();
+
+    let journal_path = test_store_path(test_name).with_file_name("journal.md");
+    assert!(!journal_path.exists());
+
+    // The note is still kept on the task
+    let mut cmd = test_command(test_name);
+    cmd.args(["info", "1"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to see"));
+}
@@ -1,7 +1,7 @@
 //! ==================== Delete Command Tests ====================
 use predicates::prelude::*;
 pub mod common;
-use common::{fresh_test_command, test_command};
+use common::{fresh_test_command, test_command, test_store_path};
 
 #[test]
 fn test_cli_delete_specific_task_by_index() {
@@ -174,7 +174,9 @@ fn test_cli_delete_invalid_index_out_of_bounds() {
     cmd3.arg("delete").arg("5");
     cmd3.assert()
         .failure()
-        .stderr(predicate::str::contains("out of bounds"));
+        .code(11)
+        .stderr(predicate::str::contains("out of bounds"))
+        .stderr(predicate::str::contains("Valid tasks:\n  1. Only Task"));
 
     // Try to delete index 0 (invalid)
     let mut cmd4 = test_command(test_name);
@@ -214,12 +216,47 @@ fn test_cli_delete_active_paused_task() {
     cmd2.arg("pause");
     cmd2.assert().success();
 
-    // Try to delete the paused task
+    // Declining the prompt keeps the task
     let mut cmd3 = test_command(test_name);
-    cmd3.arg("delete").arg("1");
+    cmd3.arg("delete").arg("1").write_stdin("n\n");
     cmd3.assert()
+        .success()
+        .stderr(predicate::str::contains("Task 'Paused Task' is paused"))
+        .stdout(predicate::str::contains("Delete cancelled"));
+
+    let mut cmd4 = test_command(test_name);
+    cmd4.arg("delete").arg("1").write_stdin("y\n");
+    cmd4.assert().success().stdout(predicate::str::contains(
+        "Task \"Paused Task\" deleted successfully",
+    ));
+}
+
+#[test]
+fn test_cli_delete_active_paused_task_with_yes() {
+    let test_name = "delete_active_paused_task_yes";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Paused Task");
+    cmd.assert().success();
+
+    // --yes doesn't let a running task be deleted
+    let mut cmd = test_command(test_name);
+    cmd.args(["delete", "1", "--yes"]);
+    cmd.assert()
         .failure()
-        .stderr(predicate::str::contains("currently paused"));
+        .stderr(predicate::str::contains("currently running"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("pause");
+    cmd.assert().success();
+
+    // No prompt is shown, so nothing is read from stdin
+    let mut cmd = test_command(test_name);
+    cmd.args(["delete", "1", "--yes"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("[y/N]").not())
+        .stdout(predicate::str::contains("deleted successfully"));
 }
 
 #[test]
@@ -300,3 +337,63 @@ fn test_cli_delete_updates_active_task_index() {
     cmd8.arg("pause");
     cmd8.assert().success();
 }
+
+#[test]
+fn test_cli_delete_dry_run_leaves_tasks_untouched() {
+    let test_name = "delete_dry_run";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Done 1");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Keep Me");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Done 2");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    let store = test_store_path(test_name);
+    let before = std::fs::read_to_string(&store).unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["delete", "--completed", "--dry-run"]);
+    cmd.assert().success().stdout(predicate::str::diff(
+        "Dry run: would delete 2 task(s):\n  1. Done 1\n  3. Done 2\n",
+    ));
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["delete", "2", "--dry-run"]);
+    cmd.assert().success().stdout(predicate::str::diff(
+        "Dry run: would delete 1 task(s):\n  2. Keep Me\n",
+    ));
+
+    // Tasks a real delete would refuse fail the same way
+    let mut cmd = test_command(test_name);
+    cmd.args(["delete", "9", "--dry-run"]);
+    cmd.assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains("Task index 9 is out of bounds"));
+    assert_eq!(std::fs::read_to_string(&store).unwrap(), before);
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["resume", "2"]);
+    cmd.assert().success();
+    let before = std::fs::read_to_string(&store).unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["delete", "2", "--dry-run"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("task is currently running"));
+
+    assert_eq!(std::fs::read_to_string(&store).unwrap(), before);
+}
@@ -0,0 +1,63 @@
+//! ==================== Encrypted Storage Tests ====================
+use predicates::prelude::*;
+use std::fs;
+pub mod common;
+use common::{fresh_test_command, test_command, test_store_path};
+
+#[test]
+fn test_cli_encrypted_store_round_trip() {
+    let test_name = "encrypted_store_round_trip";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.env("TT_ENCRYPT_KEY", "correct horse")
+        .arg("start")
+        .arg("Secret Project");
+    cmd.assert().success();
+
+    // The label never reaches the disk in plain text
+    let stored = fs::read(test_store_path(test_name)).unwrap();
+    assert!(stored.starts_with(b"age-encryption.org/"));
+    assert!(!String::from_utf8_lossy(&stored).contains("Secret Project"));
+
+    let mut cmd = test_command(test_name);
+    cmd.env("TT_ENCRYPT_KEY", "correct horse").arg("status");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Current Task: Secret Project"));
+
+    // A wrong key is an error and leaves the file alone
+    let stored = fs::read(test_store_path(test_name)).unwrap();
+    let mut cmd = test_command(test_name);
+    cmd.env("TT_ENCRYPT_KEY", "wrong horse").arg("list");
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "check that TT_ENCRYPT_KEY is correct",
+    ));
+    assert_eq!(fs::read(test_store_path(test_name)).unwrap(), stored);
+
+    let mut cmd = test_command(test_name);
+    cmd.env_remove("TT_ENCRYPT_KEY").arg("list");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("set TT_ENCRYPT_KEY"));
+}
+
+#[test]
+fn test_cli_plaintext_store_is_encrypted_on_next_save() {
+    let test_name = "plaintext_store_encrypted_on_save";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.env_remove("TT_ENCRYPT_KEY")
+        .arg("start")
+        .arg("Plain Task");
+    cmd.assert().success();
+    assert!(fs::read_to_string(test_store_path(test_name))
+        .unwrap()
+        .contains("Plain Task"));
+
+    let mut cmd = test_command(test_name);
+    cmd.env("TT_ENCRYPT_KEY", "correct horse").arg("pause");
+    cmd.assert().success();
+
+    let stored = fs::read(test_store_path(test_name)).unwrap();
+    assert!(stored.starts_with(b"age-encryption.org/"));
+}
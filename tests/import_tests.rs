@@ -0,0 +1,174 @@
+//! ==================== Import Command Tests ====================
+use predicates::prelude::*;
+use std::fs;
+pub mod common;
+use common::{fresh_test_command, test_command, test_store_path};
+
+#[test]
+fn test_cli_import_store_from_another_machine() {
+    let source = "import_source";
+    let target = "import_target";
+
+    let mut cmd = fresh_test_command(source);
+    cmd.arg("start").arg("Laptop Task");
+    cmd.assert().success();
+    let mut cmd = test_command(source);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    let mut cmd = fresh_test_command(target);
+    cmd.arg("start").arg("Desktop Task");
+    cmd.assert().success();
+
+    // A copied tasks.json can be imported directly
+    let mut cmd = test_command(target);
+    cmd.arg("import")
+        .arg(test_store_path(source))
+        .arg("--merge");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 1 task(s)"))
+        .stdout(predicate::str::contains("(2 stored)"));
+
+    let mut cmd = test_command(target);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Desktop Task [🏃 Running]"))
+        .stdout(predicate::str::contains("Laptop Task [✅ Completed]"));
+}
+
+#[test]
+fn test_cli_import_malformed_file_leaves_store_untouched() {
+    let test_name = "import_malformed";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Keep Me");
+    cmd.assert().success();
+
+    let store = test_store_path(test_name);
+    let before = fs::read_to_string(&store).unwrap();
+    let bad_file = store.with_file_name("bad.json");
+    fs::write(&bad_file, "[{ \"label\": ").unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("import").arg(&bad_file);
+    cmd.assert()
+        .failure()
+        .code(12)
+        .stderr(predicate::str::contains("Serialization error"));
+
+    assert_eq!(fs::read_to_string(&store).unwrap(), before);
+}
+
+#[test]
+fn test_cli_full_json_export_round_trips_through_import() {
+    let test_name = "import_full_export_round_trip";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("First");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Second");
+    cmd.assert().success();
+    // Make the earlier task active again, so a guess from the statuses alone would be wrong
+    let mut cmd = test_command(test_name);
+    cmd.arg("resume").arg("1");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("pause");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["export", "--format", "json", "--full"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let backup = test_store_path(test_name).with_file_name("backup.json");
+    fs::write(&backup, output).unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["clean", "--force"]);
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("import").arg(&backup);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Imported 2 task(s)"))
+        .stdout(predicate::str::contains("(2 stored)"));
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["list", "--format", "compact", "--no-float"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("* 1. First"))
+        .stdout(predicate::str::contains("\n2. Second"));
+}
+
+#[test]
+fn test_cli_full_export_needs_json_format() {
+    let mut cmd = fresh_test_command("import_full_export_csv");
+    cmd.args(["export", "--full"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("--format json"));
+}
+
+#[test]
+fn test_cli_json_export_keeps_stored_times() {
+    let mut cmd = fresh_test_command("import_json_export_rounding");
+    cmd.args(["export", "--format", "json", "--round-sessions", "15"]);
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "--round-sessions only applies to csv and worklog exports",
+    ));
+}
+
+#[test]
+fn test_cli_import_warns_about_and_trims_overlapping_sessions() {
+    let test_name = "import_overlaps";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success();
+
+    // Two machines both recorded the 09:20-09:30 stretch
+    let file = test_store_path(test_name).with_file_name("overlapping.json");
+    fs::write(
+        &file,
+        r#"[{
+            "label": "Double counted",
+            "status": "Completed",
+            "created_at": "2024-06-03T09:00:00Z",
+            "started_at": null,
+            "accumulated_duration": { "secs": 3600, "nanos": 0 },
+            "sessions": [
+                { "started_at": "2024-06-03T09:00:00Z", "duration": { "secs": 1800, "nanos": 0 } },
+                { "started_at": "2024-06-03T09:20:00Z", "duration": { "secs": 1800, "nanos": 0 } }
+            ]
+        }]"#,
+    )
+    .unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("import").arg(&file);
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Warning: task 'Double counted' has overlapping sessions 1 and 2",
+    ));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("import").arg(&file).arg("--trim-overlaps");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Trimmed 10m 0s of overlapping session time",
+        ))
+        .stderr(predicate::str::contains("overlapping").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["export", "--format", "json"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let tasks: serde_json::Value = serde_json::from_slice(&output).unwrap();
+    let sessions = tasks[0]["sessions"].as_array().unwrap();
+    assert_eq!(sessions[1]["started_at"], "2024-06-03T09:30:00Z");
+    assert_eq!(sessions[1]["duration"]["secs"], 1200);
+    assert_eq!(tasks[0]["accumulated_duration"]["secs"], 3000);
+}
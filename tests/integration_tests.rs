@@ -1,6 +1,6 @@
 use predicates::prelude::*;
 pub mod common;
-use common::{fresh_test_command, test_command};
+use common::{fresh_test_command, test_command, test_store_path};
 
 #[test]
 fn test_cli_start_task() {
@@ -13,6 +13,21 @@ fn test_cli_start_task() {
         .stdout(predicate::str::contains("Started task: 'My Test Task'"));
 }
 
+#[test]
+fn test_cli_start_task_with_tags() {
+    let test_name = "start_task_with_tags";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["start", "Client call", "--tag", "acme", "-t", "#billable"]);
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "1. Client call #acme #billable [🏃 Running]",
+    ));
+}
+
 #[test]
 fn test_cli_start_task_with_spaces() {
     let mut cmd = fresh_test_command("start_task_with_spaces");
@@ -105,7 +120,13 @@ fn test_cli_invalid_command() {
 
     cmd.assert()
         .failure()
+        .code(2)
         .stderr(predicate::str::contains("error: unrecognized subcommand"));
+
+    // A task-state error never shares clap's usage exit code
+    let mut cmd = fresh_test_command("invalid_command");
+    cmd.arg("pause");
+    cmd.assert().failure().code(10);
 }
 
 #[test]
@@ -119,6 +140,27 @@ fn test_cli_start_without_label() {
     ));
 }
 
+#[test]
+fn test_cli_start_rejects_empty_labels() {
+    let test_name = "start_empty_label";
+
+    for label in ["", "   ", "\t"] {
+        let mut cmd = fresh_test_command(test_name);
+        cmd.arg("start").arg(label);
+        cmd.assert()
+            .failure()
+            .code(11)
+            .stderr(predicate::str::contains("Task label cannot be empty"));
+    }
+
+    // Nothing was created
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks found"));
+}
+
 #[test]
 fn test_cli_complete_without_active_task() {
     let mut cmd = fresh_test_command("complete_without_active_task");
@@ -190,3 +232,1017 @@ fn test_cli_complete_paused_task() {
         .success()
         .stdout(predicate::str::contains("No active task"));
 }
+
+#[test]
+fn test_cli_start_sanitizes_control_characters() {
+    let test_name = "start_sanitizes_control_characters";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Pasted\nlabel \x1b[31mred\x1b[0m");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Started task: 'Pasted label red'"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1. Pasted label red ["))
+        .stdout(predicate::str::contains("\x1b").not());
+}
+
+#[test]
+fn test_cli_start_strict_labels_rejects_control_characters() {
+    let mut cmd = fresh_test_command("start_strict_labels");
+
+    cmd.arg("--strict-labels").arg("start").arg("Pasted\nlabel");
+
+    cmd.assert().failure().stderr(predicate::str::contains(
+        "Task label cannot contain control characters",
+    ));
+}
+
+#[test]
+fn test_cli_list_relative() {
+    let test_name = "list_relative";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Relative Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list").arg("--relative");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Relative Task"))
+        .stdout(predicate::str::contains("(just now)"))
+        .stdout(predicate::str::contains("UTC").not())
+        .stdout(predicate::str::contains("Created:").not());
+}
+
+#[test]
+fn test_cli_bare_invocation_shows_status() {
+    let test_name = "bare_invocation_status";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.env_remove("TT_DEFAULT_COMMAND");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No active task"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Bare Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.env_remove("TT_DEFAULT_COMMAND");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Current Task: Bare Task"));
+}
+
+#[test]
+fn test_cli_bare_invocation_uses_default_command_env() {
+    let mut cmd = fresh_test_command("bare_invocation_default_env");
+
+    cmd.env("TT_DEFAULT_COMMAND", "list");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No tasks found"));
+}
+
+#[test]
+fn test_cli_bare_invocation_warns_once() {
+    let test_name = "bare_invocation_warns_once";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success();
+    std::fs::write(
+        test_store_path(test_name).with_file_name("config.toml"),
+        "round_minutes = [",
+    )
+    .unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.env("TT_DEFAULT_COMMAND", "list")
+        .env("TT_MAX_TASKS", "abc");
+    let output = cmd.assert().success().get_output().stderr.clone();
+    let stderr = String::from_utf8(output).unwrap();
+    assert_eq!(stderr.matches("Ignoring invalid TT_MAX_TASKS").count(), 1);
+    assert_eq!(stderr.matches("Ignoring config").count(), 1);
+}
+
+#[test]
+fn test_cli_bare_invocation_keeps_global_flags() {
+    let test_name = "bare_invocation_global_flags";
+    let store = test_store_path(test_name).with_file_name("other.json");
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Default Store Task");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("--store")
+        .arg(&store)
+        .args(["start", "Other Store Task"]);
+    cmd.assert().success();
+
+    // The original --store and the default command's own --json both apply
+    let mut cmd = test_command(test_name);
+    cmd.env("TT_DEFAULT_COMMAND", "--json list")
+        .arg("--store")
+        .arg(&store);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\"label\": \"Other Store Task\""))
+        .stdout(predicate::str::contains("Default Store Task").not());
+}
+
+#[test]
+fn test_cli_check_fail_if_running() {
+    let test_name = "check_fail_if_running";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("CI Task");
+    cmd.assert().success();
+
+    // A running task fails the check
+    let mut cmd = test_command(test_name);
+    cmd.arg("check").arg("--fail-if-running");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("still running: 1. CI Task"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    // Nothing running passes
+    let mut cmd = test_command(test_name);
+    cmd.arg("check").arg("--fail-if-running");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No running tasks"));
+}
+
+#[test]
+fn test_cli_config_show_reports_sources() {
+    let mut cmd = fresh_test_command("config_show_sources");
+
+    cmd.env("TT_DEFAULT_COMMAND", "list --relative")
+        .arg("--strict-labels")
+        .arg("config")
+        .arg("show");
+
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "default_command       = list --relative (env: TT_DEFAULT_COMMAND)",
+        ))
+        .stdout(predicate::str::contains("(env: TT_CONFIG_DIR)"))
+        .stdout(predicate::str::contains(
+            "strict_labels         = true (flag: --strict-labels)",
+        ))
+        .stdout(predicate::str::contains(
+            "max_tasks             = 10 (default)",
+        ));
+}
+
+#[test]
+fn test_cli_config_unknown_key_keeps_other_keys() {
+    let test_name = "config_unknown_key";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success();
+    std::fs::write(
+        test_store_path(test_name).with_file_name("config.toml"),
+        "round_minute = 15\nno_emoji = true\n",
+    )
+    .unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("config").arg("show");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("= true (config.toml)"))
+        .stderr(predicate::str::contains(
+            "Ignoring unknown key 'round_minute' in config",
+        ));
+}
+
+#[test]
+fn test_cli_reorder_keeps_active_task() {
+    let test_name = "reorder_keeps_active_task";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("First Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Active Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("reorder").arg("2").arg("1");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Moved task \"Active Task\" from position 2 to 1",
+    ));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1. Active Task"))
+        .stdout(predicate::str::contains("2. First Task"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Current Task: Active Task"));
+}
+
+#[test]
+fn test_cli_export_csv_date_range() {
+    let test_name = "export_csv_date_range";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Exported Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("export").arg("--format").arg("csv");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "index,label,project,status,created_at,duration_seconds\n",
+        ))
+        .stdout(predicate::str::contains("1,Exported Task,,running,"));
+
+    // A range in the past excludes the task created today
+    let mut cmd = test_command(test_name);
+    cmd.arg("export")
+        .arg("--since")
+        .arg("2000-01-01")
+        .arg("--until")
+        .arg("2000-01-07");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Exported Task").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("export")
+        .arg("--since")
+        .arg("2000-01-07")
+        .arg("--until")
+        .arg("2000-01-01");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("is after --until"));
+}
+
+#[test]
+fn test_cli_status_label_max() {
+    let test_name = "status_label_max";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Investigate flaky integration tests");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status").arg("--label-max").arg("11");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Current Task: Investigate… ["));
+
+    // Other commands still show the full label
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Investigate flaky integration tests",
+    ));
+}
+
+#[test]
+fn test_cli_relabel_project_prefix() {
+    let test_name = "relabel_project_prefix";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start")
+        .arg("Landing page")
+        .arg("--project")
+        .arg("Web");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start")
+        .arg("Contact form")
+        .arg("--project")
+        .arg("Web");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start")
+        .arg("Release notes")
+        .arg("--project")
+        .arg("Docs");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("relabel")
+        .arg("--project")
+        .arg("Web")
+        .arg("--prefix")
+        .arg("[web] ");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Relabelled 2 task(s) in project 'Web'",
+    ));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1. [web] Landing page"))
+        .stdout(predicate::str::contains("2. [web] Contact form"))
+        .stdout(predicate::str::contains("3. Release notes"));
+}
+
+#[test]
+fn test_cli_repairs_future_start_time_on_load() {
+    let test_name = "repair_future_start_time";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Synced Task");
+    cmd.assert().success();
+
+    // Simulate a store written by a machine whose clock was a day ahead
+    let path = test_store_path(test_name);
+    let mut store: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let future = chrono::Utc::now() + chrono::Duration::days(1);
+    store["tasks"][0]["started_at"] = serde_json::json!(future);
+    std::fs::write(&path, serde_json::to_string(&store).unwrap()).unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Current Task: Synced Task"))
+        .stdout(predicate::str::contains("- 0s"))
+        .stderr(predicate::str::contains(
+            "Warning: Task 'Synced Task' started in the future",
+        ));
+
+    // The repair is saved, so it is only reported once
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Warning").not());
+}
+
+#[test]
+fn test_cli_export_worklog() {
+    let test_name = "export_worklog";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Journal Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+    let mut cmd = test_command(test_name);
+    cmd.arg("export")
+        .arg("--format")
+        .arg("worklog")
+        .arg("--by")
+        .arg("completed");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with(format!("## {}\n\n", today)))
+        .stdout(predicate::str::contains("- Journal Task ("));
+}
+
+#[test]
+fn test_cli_stale_lists_only_old_paused_tasks() {
+    let test_name = "stale_paused_tasks";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Old Paused Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Recent Paused Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("pause");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("stale");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No stale paused tasks"));
+
+    // Move the first task's only session back a little over two days
+    let path = test_store_path(test_name);
+    let mut store: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let two_days_ago = serde_json::json!(chrono::Utc::now() - chrono::Duration::hours(50));
+    store["tasks"][0]["created_at"] = two_days_ago.clone();
+    store["tasks"][0]["sessions"][0]["started_at"] = two_days_ago;
+    std::fs::write(&path, serde_json::to_string(&store).unwrap()).unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("stale");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "1. Old Paused Task - last active 2 days ago",
+        ))
+        .stdout(predicate::str::contains("Recent Paused Task").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("stale").arg("--older-than").arg("3d");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No stale paused tasks"));
+}
+
+#[test]
+fn test_cli_stale_policy_without_days_warns() {
+    let mut cmd = fresh_test_command("stale_policy_without_days");
+    cmd.env("TT_STALE_POLICY", "complete").arg("list");
+    cmd.assert().success().stderr(predicate::str::contains(
+        "TT_STALE_POLICY has no effect unless TT_STALE_DAYS is set",
+    ));
+
+    let mut cmd = test_command("stale_policy_without_days");
+    cmd.env("TT_STALE_POLICY", "complete")
+        .env("TT_STALE_DAYS", "14")
+        .arg("list");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("TT_STALE_POLICY").not());
+}
+
+#[test]
+fn test_cli_summary_line() {
+    let test_name = "summary_line";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("summary");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("No tasks\n"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Paused Work");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Build");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("summary");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("2 tasks · 1 running (Build "))
+        .stdout(predicate::str::contains(
+            "· 1 paused · 0 completed · total ",
+        ));
+
+    // Just the footer of `list`, without the tasks
+    let mut cmd = test_command(test_name);
+    cmd.args(["summary", "--totals"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::starts_with("Total Time: "))
+        .stdout(predicate::str::contains(
+            "Running: 1 | Paused: 1 | Completed: 0\n",
+        ))
+        .stdout(predicate::str::contains("Build").not());
+}
+
+#[test]
+fn test_cli_parse_duration() {
+    let test_name = "parse_duration";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("parse-duration").arg("1h30m");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::diff("5400 seconds (1h 30m 0s)\n"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("parse-duration").arg("90 minutes");
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Invalid duration '90 minutes'"));
+
+    // Read-only: the task store is never created
+    assert!(!test_store_path(test_name).exists());
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("--help");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("parse-duration").not());
+}
+
+#[test]
+fn test_cli_unreadable_store_is_an_error() {
+    let test_name = "unreadable_store";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success();
+    let store = test_store_path(test_name).with_file_name("dir.json");
+    std::fs::create_dir_all(&store).unwrap();
+
+    // Starting fresh would replace whatever is there on the next save
+    let mut cmd = test_command(test_name);
+    cmd.arg("--store").arg(&store).args(["start", "Task"]);
+    cmd.assert()
+        .failure()
+        .code(12)
+        .stderr(predicate::str::contains("Could not load tasks"));
+    assert!(store.is_dir());
+}
+
+#[test]
+fn test_cli_store_flag() {
+    let test_name = "store_flag";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["start", "Default task"]).assert().success();
+
+    // A relative --store path lands in the working directory, with missing parents created
+    let work_dir = test_store_path(test_name).with_file_name("cwd");
+    std::fs::create_dir_all(&work_dir).unwrap();
+    let mut cmd = test_command(test_name);
+    cmd.current_dir(&work_dir)
+        .args(["--store", "work/tasks.json", "start", "Work task"]);
+    cmd.assert().success();
+    assert!(work_dir.join("work/tasks.json").exists());
+
+    let mut cmd = test_command(test_name);
+    cmd.current_dir(&work_dir)
+        .args(["--store", "work/tasks.json", "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Work task"))
+        .stdout(predicate::str::contains("Default task").not());
+
+    // The default store is left alone
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Default task"))
+        .stdout(predicate::str::contains("Work task").not());
+}
+
+#[test]
+fn test_cli_verbose_logs_to_stderr() {
+    let test_name = "verbose";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["start", "Write docs"]).assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["--verbose", "pause"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[tt]").not())
+        .stderr(predicate::str::contains("[tt] loaded 1 tasks from"))
+        .stderr(predicate::str::contains(
+            "[tt] paused task index 1 ('Write docs')",
+        ))
+        .stderr(predicate::str::contains("[tt] saved 1 tasks to"));
+
+    // Quiet by default
+    let mut cmd = test_command(test_name);
+    cmd.arg("resume");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("[tt]").not());
+}
+
+#[test]
+fn test_cli_completions() {
+    let test_name = "completions";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["completions", "fish"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("complete -c tt"))
+        .stdout(predicate::str::contains("start"));
+
+    // Read-only: the task store is never created
+    assert!(!test_store_path(test_name).exists());
+}
+
+#[test]
+fn test_cli_report_weekly() {
+    let test_name = "report_weekly";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Grid Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("report").arg("--weekly");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Task       Mon  Tue  Wed  Thu  Fri  Sat  Sun  Total",
+        ))
+        .stdout(predicate::str::contains("Grid Task"));
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["report", "--weekly", "--week-of", "2020-01-01"]);
+    cmd.assert().success().stdout(predicate::str::diff(
+        "Week of 2019-12-30\nNo time tracked this week\n",
+    ));
+}
+
+#[test]
+fn test_cli_report_date_range() {
+    let test_name = "report_date_range";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Invoiced Task");
+    cmd.assert().success();
+
+    // Only a lower bound, which today's task satisfies
+    let mut cmd = test_command(test_name);
+    cmd.args(["report", "--weekly", "--since", "2020-01-01"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Invoiced Task"));
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["report", "--weekly", "--until", "2020-01-31"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No time tracked this week"));
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["report", "--since", "2020-02-01", "--until", "2020-01-01"]);
+    cmd.assert()
+        .failure()
+        .code(11)
+        .stderr(predicate::str::contains(
+            "--since 2020-02-01 is after --until 2020-01-01",
+        ));
+}
+
+#[test]
+fn test_cli_list_shows_local_time_unless_utc() {
+    let test_name = "list_local_time";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Zoned Task");
+    cmd.assert().success();
+
+    // POSIX TZ for UTC+9, which needs no timezone database
+    let mut cmd = test_command(test_name);
+    cmd.env("TZ", "JST-9").arg("list");
+    cmd.assert().success().stdout(
+        predicate::str::is_match(r"\(Created: \d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2} \+09:00\)")
+            .unwrap(),
+    );
+
+    let mut cmd = test_command(test_name);
+    cmd.env("TZ", "JST-9").arg("list").arg("--utc");
+    cmd.assert().success().stdout(
+        predicate::str::is_match(r"\(Created: \d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2} UTC\)").unwrap(),
+    );
+}
+
+#[test]
+fn test_cli_status_shows_local_time_unless_utc() {
+    let test_name = "status_local_time";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Zoned Task");
+    cmd.assert().success();
+
+    // POSIX TZ for UTC+9, which needs no timezone database
+    let mut cmd = test_command(test_name);
+    cmd.env("TZ", "JST-9").arg("status");
+    cmd.assert().success().stdout(
+        predicate::str::is_match(r"\(started at (\d{4}-\d{2}-\d{2} )?\d{2}:\d{2}\)").unwrap(),
+    );
+
+    let mut cmd = test_command(test_name);
+    cmd.env("TZ", "JST-9").arg("status").arg("--utc");
+    cmd.assert().success().stdout(
+        predicate::str::is_match(r"\(started at (\d{4}-\d{2}-\d{2} )?\d{2}:\d{2} UTC\)").unwrap(),
+    );
+}
+
+#[test]
+fn test_cli_warns_about_timer_left_running() {
+    let test_name = "idle_warning";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Overnight Task");
+    cmd.assert().success();
+
+    // Simulate a session started ten hours ago
+    let path = test_store_path(test_name);
+    let mut store: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let started_at = chrono::Utc::now() - chrono::Duration::hours(10);
+    store["tasks"][0]["started_at"] = serde_json::json!(started_at);
+    std::fs::write(&path, serde_json::to_string(&store).unwrap()).unwrap();
+
+    for command in ["status", "list"] {
+        let mut cmd = test_command(test_name);
+        cmd.arg(command);
+        cmd.assert().success().stderr(predicate::str::contains(
+            "Warning: 'Overnight Task' has been running for 10h 0m",
+        ));
+    }
+
+    // A higher threshold silences the warning
+    let mut cmd = test_command(test_name);
+    cmd.env("TT_IDLE_WARN_HOURS", "12").arg("status");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Warning").not());
+}
+
+#[test]
+fn test_cli_color_only_when_asked_or_on_terminal() {
+    let test_name = "color_output";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Colorful Task");
+    cmd.assert().success();
+
+    // Piped output stays plain by default
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("[🏃 Running]"))
+        .stdout(predicate::str::contains("\u{1b}[").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status").arg("--color").arg("always");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("\u{1b}[32m🏃 Running\u{1b}[39m"));
+}
+
+#[test]
+fn test_cli_status_porcelain() {
+    let test_name = "status_porcelain";
+
+    // No active task prints an empty line
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["status", "--porcelain"]);
+    cmd.assert().success().stdout(predicate::str::diff("\n"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Prompt Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("pause");
+    cmd.assert().success();
+
+    // Emoji and color settings never leak into the porcelain format
+    let mut cmd = test_command(test_name);
+    cmd.args(["status", "--porcelain", "--color", "always"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::is_match(r"^Prompt Task\|paused\|\d+\n$").unwrap());
+}
+
+#[test]
+fn test_cli_goal_reached_is_announced_once() {
+    let test_name = "goal_reached";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["start", "Pomodoro", "--goal", "25m"]);
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Goal reached").not());
+
+    // Simulate a session started half an hour ago
+    let path = test_store_path(test_name);
+    let mut store: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let started_at = chrono::Utc::now() - chrono::Duration::minutes(30);
+    store["tasks"][0]["started_at"] = serde_json::json!(started_at);
+    std::fs::write(&path, serde_json::to_string(&store).unwrap()).unwrap();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert().success().stderr(predicate::str::contains(
+        "Goal reached: 'Pomodoro' has passed its 25m 0s goal",
+    ));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stderr(predicate::str::contains("Goal reached").not());
+}
+
+#[test]
+fn test_cli_archive_moves_completed_tasks() {
+    let test_name = "archive_completed";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["start", "Finished"]);
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.args(["start", "Ongoing"]);
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("archive");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Archived 1 completed task(s)"))
+        .stdout(predicate::str::contains("archive.json"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1. Ongoing"))
+        .stdout(predicate::str::contains("Finished").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["archive", "--list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1. Finished [✅ Completed]"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("archive");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No completed tasks to archive"));
+}
+
+#[test]
+fn test_cli_quiet_hides_confirmations_but_not_listings() {
+    let test_name = "quiet_flag";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["--quiet", "start", "Silent Task"]);
+    cmd.assert().success().stdout(predicate::str::is_empty());
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["pause", "--quiet"]);
+    cmd.assert().success().stdout(predicate::str::is_empty());
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["--quiet", "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Silent Task [⏸️  Paused]"));
+
+    // Errors still reach stderr
+    let mut cmd = test_command(test_name);
+    cmd.args(["--quiet", "pause"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Error:"));
+}
+
+#[cfg(unix)]
+#[test]
+fn test_cli_watch_stops_on_interrupt() {
+    use std::process::{Command, Stdio};
+    use std::{env, thread, time::Duration};
+
+    let test_name = "watch_stops_on_interrupt";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("watch");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No active task to watch"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Focus");
+    cmd.assert().success();
+
+    let config_dir = env::temp_dir().join("tt_tests").join(test_name);
+    let child = Command::new(env!("CARGO_BIN_EXE_tt"))
+        .arg("watch")
+        .env("TT_CONFIG_DIR", &config_dir)
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+
+    thread::sleep(Duration::from_millis(1500));
+    let status = Command::new("kill")
+        .arg("-INT")
+        .arg(child.id().to_string())
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Current Task: Focus"));
+    assert!(stdout.contains("Stopped watching 'Focus'"));
+}
+
+#[test]
+fn test_cli_commands_by_stable_id() {
+    let test_name = "commands_by_stable_id";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("First");
+    cmd.assert().success();
+    for label in ["Second", "Third"] {
+        let mut cmd = test_command(test_name);
+        cmd.arg("start").arg(label);
+        cmd.assert().success();
+    }
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["info", "3"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("ID:       3"));
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["delete", "--id", "1", "--yes"]);
+    cmd.assert().success();
+
+    // Task 3 is now at index 2, but its ID still finds it
+    let mut cmd = test_command(test_name);
+    cmd.args(["rename", "--id", "3", "Renamed"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("from \"Third\" to \"Renamed\""));
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["complete", "--id", "3"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Completed task: 'Renamed'"));
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["delete", "--id", "1"]);
+    cmd.assert()
+        .failure()
+        .stderr(predicate::str::contains("Task not found with id: 1"));
+}
+
+#[test]
+fn test_cli_list_ids_resolve_with_id() {
+    let test_name = "list_ids_resolve_with_id";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("First");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Second");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["list", "--ids", "--format", "compact"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let output = String::from_utf8(output).unwrap();
+    let shown: Vec<&str> = output
+        .lines()
+        .filter_map(|line| line.split_once('[')?.1.split_once(']'))
+        .map(|(id, _)| id)
+        .collect();
+    assert_eq!(shown.len(), 2, "{}", output);
+    assert_ne!(shown[0], shown[1]);
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["complete", "--id", shown[1]]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Completed task: 'Second'"));
+}
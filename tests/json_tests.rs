@@ -0,0 +1,96 @@
+//! ==================== JSON Output Tests ====================
+use predicates::prelude::*;
+pub mod common;
+use common::{fresh_test_command, test_command};
+
+fn stdout_json(cmd: &mut assert_cmd::Command) -> serde_json::Value {
+    let output = cmd.assert().success().get_output().stdout.clone();
+    serde_json::from_slice(&output).unwrap()
+}
+
+#[test]
+fn test_cli_json_status_and_list() {
+    let test_name = "json_status_and_list";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["--json", "status"]);
+    assert_eq!(stdout_json(&mut cmd), serde_json::Value::Null);
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["start", "Scripted Task"]);
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["status", "--json"]);
+    let status = stdout_json(&mut cmd);
+    assert_eq!(status["label"], "Scripted Task");
+    assert_eq!(status["status"], "running");
+    assert!(status["total_seconds"].is_u64());
+    assert!(chrono::DateTime::parse_from_rfc3339(status["created_at"].as_str().unwrap()).is_ok());
+
+    let mut cmd = test_command(test_name);
+    cmd.args(["--json", "list"]);
+    let list = stdout_json(&mut cmd);
+    assert_eq!(list.as_array().unwrap().len(), 1);
+    assert_eq!(list[0]["label"], "Scripted Task");
+}
+
+#[test]
+fn test_cli_json_report() {
+    let mut cmd = fresh_test_command("json_report");
+    cmd.args(["--json", "report", "--week-of", "2020-01-01"]);
+    assert_eq!(
+        stdout_json(&mut cmd),
+        serde_json::json!({
+            "week_of": "2019-12-30",
+            "tasks": [],
+            "day_totals": [0, 0, 0, 0, 0, 0, 0],
+            "total_seconds": 0,
+        })
+    );
+}
+
+#[test]
+fn test_cli_json_does_not_change_errors() {
+    let mut cmd = fresh_test_command("json_errors");
+    cmd.args(["--json", "pause"]);
+    cmd.assert()
+        .failure()
+        .code(10)
+        .stdout(predicate::str::is_empty())
+        .stderr(predicate::str::contains(
+            "Error: No active task to operate on",
+        ));
+}
+
+#[test]
+fn test_cli_json_compact_prints_one_line() {
+    let test_name = "json_compact";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["start", "Piped Task"]);
+    cmd.assert().success();
+
+    for args in [
+        vec!["--json-compact", "status"],
+        vec!["--json-compact", "list"],
+        vec!["--json-compact", "export", "--format", "json"],
+        vec!["--json-compact", "export", "--format", "json", "--full"],
+    ] {
+        let mut cmd = test_command(test_name);
+        cmd.args(&args);
+        let output = cmd.assert().success().get_output().stdout.clone();
+        let stdout = String::from_utf8(output).unwrap();
+        assert_eq!(stdout.lines().count(), 1, "{:?} printed {:?}", args, stdout);
+        assert!(stdout.ends_with("}\n") || stdout.ends_with("]\n"));
+        assert!(serde_json::from_str::<serde_json::Value>(&stdout).is_ok());
+    }
+
+    // Pretty output stays the default and ends with a single newline too
+    let mut cmd = test_command(test_name);
+    cmd.args(["--json", "list"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert!(stdout.lines().count() > 1);
+    assert!(stdout.ends_with("]\n"));
+}
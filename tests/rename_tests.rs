@@ -153,7 +153,8 @@ fn test_cli_rename_invalid_index_out_of_bounds() {
     cmd3.arg("rename").arg("99").arg("New Label");
     cmd3.assert()
         .failure()
-        .stderr(predicate::str::contains("out of bounds"));
+        .stderr(predicate::str::contains("out of bounds"))
+        .stderr(predicate::str::contains("Valid tasks:\n  1. Test Task"));
 }
 
 #[test]
@@ -254,3 +255,54 @@ fn test_cli_rename_multiple_tasks() {
         .stdout(predicate::str::contains("Task 3"))
         .stdout(predicate::str::contains("Original Task Two").not());
 }
+
+#[test]
+fn test_cli_rename_active_task() {
+    let test_name = "rename_active_task";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("First Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Tpyo Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("rename").arg("--active").arg("Typo Task");
+    cmd.assert().success().stdout(predicate::str::contains(
+        "Task renamed from \"Tpyo Task\" to \"Typo Task\"",
+    ));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("First Task"))
+        .stdout(predicate::str::contains("2. Typo Task"));
+
+    // An index and --active can't be combined
+    let mut cmd = test_command(test_name);
+    cmd.arg("rename").arg("1").arg("Other").arg("--active");
+    cmd.assert().failure().code(2);
+}
+
+#[test]
+fn test_cli_rename_active_without_active_task() {
+    let test_name = "rename_active_none";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Done Task");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("rename").arg("--active").arg("New Label");
+    cmd.assert()
+        .failure()
+        .code(10)
+        .stderr(predicate::str::contains("No active task"));
+}
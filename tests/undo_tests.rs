@@ -0,0 +1,162 @@
+//! ==================== Undo Command Tests ====================
+use predicates::prelude::*;
+pub mod common;
+use common::{fresh_test_command, test_command, test_store_path};
+
+#[test]
+fn test_cli_undo_without_snapshot() {
+    let test_name = "undo_without_snapshot";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("undo");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to undo"));
+}
+
+#[test]
+fn test_cli_undo_reverts_accidental_complete() {
+    let test_name = "undo_accidental_complete";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("Keep going");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("undo");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Undid the last change"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("status");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Current Task: Keep going"))
+        .stdout(predicate::str::contains("Running"));
+
+    // Only one level is kept
+    let mut cmd = test_command(test_name);
+    cmd.arg("undo");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Nothing to undo"));
+}
+
+#[test]
+fn test_cli_read_only_command_keeps_snapshot() {
+    let test_name = "undo_after_read_only";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.arg("start").arg("First");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("start").arg("Second");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert().success();
+
+    // The listing changed nothing, so undo still reverts starting "Second"
+    let mut cmd = test_command(test_name);
+    cmd.arg("undo");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("First"))
+        .stdout(predicate::str::contains("Second").not());
+}
+
+#[test]
+fn test_cli_undo_reverts_archive() {
+    let test_name = "undo_archive";
+
+    let mut cmd = fresh_test_command(test_name);
+    cmd.args(["start", "A"]);
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("complete");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.arg("archive");
+    cmd.assert().success();
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("undo");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Undid the last change"));
+
+    // The task is back in the list and no longer in the archive
+    let mut cmd = test_command(test_name);
+    cmd.arg("list");
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("1. A"));
+    let mut cmd = test_command(test_name);
+    cmd.args(["archive", "--list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("No archived tasks"));
+
+    let mut cmd = test_command(test_name);
+    cmd.arg("archive");
+    cmd.assert().success();
+    let mut cmd = test_command(test_name);
+    cmd.args(["archive", "--list"]);
+    let output = cmd.assert().success().get_output().stdout.clone();
+    let stdout = String::from_utf8(output).unwrap();
+    assert_eq!(stdout.matches(". A [").count(), 1, "{}", stdout);
+}
+
+#[test]
+fn test_cli_undo_keeps_stores_in_one_directory_apart() {
+    let test_name = "undo_two_stores";
+
+    fresh_test_command(test_name);
+    let dir = test_store_path(test_name).with_file_name("stores");
+    std::fs::create_dir_all(&dir).unwrap();
+    for (store, label) in [
+        ("a.json", "Task A1"),
+        ("a.json", "Task A2"),
+        ("b.json", "Task B1"),
+        ("b.json", "Task B2"),
+    ] {
+        let mut cmd = test_command(test_name);
+        cmd.current_dir(&dir)
+            .args(["--store", store, "start", label]);
+        cmd.assert().success();
+    }
+    assert!(dir.join("a.undo.json").exists());
+    assert!(dir.join("b.undo.json").exists());
+
+    // Undoing in a.json restores its own earlier state, not b.json's
+    let mut cmd = test_command(test_name);
+    cmd.current_dir(&dir).args(["--store", "a.json", "undo"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Undid the last change"));
+
+    let mut cmd = test_command(test_name);
+    cmd.current_dir(&dir).args(["--store", "a.json", "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Task A1"))
+        .stdout(predicate::str::contains("Task A2").not())
+        .stdout(predicate::str::contains("Task B").not());
+
+    let mut cmd = test_command(test_name);
+    cmd.current_dir(&dir).args(["--store", "b.json", "list"]);
+    cmd.assert()
+        .success()
+        .stdout(predicate::str::contains("Task B2"));
+}